@@ -25,16 +25,16 @@ pub fn run_benchmark(r1cs_path: String, witness_path: String) -> String {
 
   // produce public parameters
   let gens = NIZKGens::<G1Projective>::new(
-    spartan_inst.inst.get_num_cons(), 
-    spartan_inst.inst.get_num_vars(), 
-    spartan_inst.inst.get_num_inputs()
+    spartan_inst.get_num_cons(), 
+    spartan_inst.get_num_vars(), 
+    spartan_inst.get_num_inputs()
   );
 
   // produce a proof of satisfiability
   let mut prover_transcript = Transcript::new(b"nizk_example");
 
-  let mut inputs = vec![Fr::from(0); spartan_inst.inst.get_num_inputs()];
-  for i in 0..spartan_inst.inst.get_num_inputs() {
+  let mut inputs = vec![Fr::from(0); spartan_inst.get_num_inputs()];
+  for i in 0..spartan_inst.get_num_inputs() {
       inputs[i] = witness[i];
   }
   let inputs = InputsAssignment::new(&inputs).unwrap();
@@ -0,0 +1,114 @@
+#![allow(clippy::assertions_on_result_states)]
+
+extern crate criterion;
+extern crate libspartan;
+extern crate merlin;
+
+use ark_bls12_381::G1Projective;
+use ark_ec::CurveGroup;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use ark_std::{test_rng, UniformRand};
+use libspartan::bulletproofs::{InnerProductGens, InnerProductProof};
+use merlin::Transcript;
+
+use criterion::*;
+
+fn inner_product_prove_benchmark<G: CurveGroup>(c: &mut Criterion) {
+  for &s in (5..=12).collect::<Vec<usize>>().iter() {
+    let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    let mut group = c.benchmark_group("InnerProduct_prove_benchmark");
+    group.plot_config(plot_config);
+
+    let n = (2_usize).pow(s as u32);
+    let mut prng = test_rng();
+    let gens = InnerProductGens::<G>::new(n, b"inner_product_bench");
+    let x = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let a = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let blind_x = G::ScalarField::rand(&mut prng);
+    let blind_y = G::ScalarField::rand(&mut prng);
+
+    let mut rng = StdRng::from_entropy();
+
+    let name = format!("InnerProduct_prove_{}", n);
+    group.bench_function(&name, move |b| {
+      b.iter(|| {
+        let mut prover_transcript = Transcript::new(b"example");
+        InnerProductProof::prove(
+          black_box(&x),
+          black_box(&blind_x),
+          black_box(&a),
+          black_box(&blind_y),
+          black_box(&gens),
+          black_box(&mut prover_transcript),
+          black_box(&mut rng),
+        );
+      });
+    });
+    group.finish();
+  }
+}
+
+fn inner_product_verify_benchmark<G: CurveGroup>(c: &mut Criterion) {
+  for &s in (5..=12).collect::<Vec<usize>>().iter() {
+    let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    let mut group = c.benchmark_group("InnerProduct_verify_benchmark");
+    group.plot_config(plot_config);
+
+    let n = (2_usize).pow(s as u32);
+    let mut prng = test_rng();
+    let gens = InnerProductGens::<G>::new(n, b"inner_product_bench");
+    let x = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let a = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let blind_x = G::ScalarField::rand(&mut prng);
+    let blind_y = G::ScalarField::rand(&mut prng);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, _, comm_x, comm_y) = InnerProductProof::prove(
+      &x,
+      &blind_x,
+      &a,
+      &blind_y,
+      &gens,
+      &mut prover_transcript,
+      &mut StdRng::from_entropy(),
+    );
+
+    let name = format!("InnerProduct_verify_{}", n);
+    group.bench_function(&name, move |b| {
+      b.iter(|| {
+        let mut verifier_transcript = Transcript::new(b"example");
+        assert!(proof
+          .verify(
+            black_box(&a),
+            black_box(&comm_x),
+            black_box(&comm_y),
+            black_box(&gens),
+            black_box(&mut verifier_transcript)
+          )
+          .is_ok());
+      });
+    });
+    group.finish();
+  }
+}
+
+fn set_duration() -> Criterion {
+  Criterion::default().sample_size(10)
+}
+
+criterion_group! {
+name = benches_inner_product;
+config = set_duration();
+targets = inner_product_prove_benchmark::<G1Projective>,
+inner_product_verify_benchmark::<G1Projective>
+}
+
+criterion_main!(benches_inner_product);
@@ -10,7 +10,7 @@ extern crate sha3;
 
 use ark_bls12_381::G1Projective;
 use ark_ec::CurveGroup;
-use libspartan::{Instance, NIZKGens, NIZK};
+use libspartan::{Instance, NIZKGens, NIZKVerifierKey, PlainNIZK, NIZK};
 use merlin::Transcript;
 
 use criterion::*;
@@ -47,6 +47,38 @@ fn nizk_prove_benchmark<G: CurveGroup>(c: &mut Criterion) {
   }
 }
 
+fn plain_nizk_prove_benchmark<G: CurveGroup>(c: &mut Criterion) {
+  for &s in [10, 12, 16].iter() {
+    let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    let mut group = c.benchmark_group("PlainNIZK_prove_benchmark");
+    group.plot_config(plot_config);
+
+    let num_vars = (2_usize).pow(s as u32);
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let (inst, vars, inputs) =
+      Instance::<G::ScalarField>::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+
+    let name = format!("PlainNIZK_prove_{}", num_vars);
+    group.bench_function(&name, move |b| {
+      b.iter(|| {
+        let mut prover_transcript = Transcript::new(b"example");
+        PlainNIZK::prove(
+          black_box(&inst),
+          black_box(vars.clone()),
+          black_box(&inputs),
+          black_box(&gens),
+          black_box(&mut prover_transcript),
+        );
+      });
+    });
+    group.finish();
+  }
+}
+
 fn nizk_verify_benchmark<G: CurveGroup>(c: &mut Criterion) {
   for &s in [10, 12, 16].iter() {
     let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
@@ -83,6 +115,43 @@ fn nizk_verify_benchmark<G: CurveGroup>(c: &mut Criterion) {
   }
 }
 
+fn nizk_verify_with_key_benchmark<G: CurveGroup>(c: &mut Criterion) {
+  for &s in [10, 12, 16].iter() {
+    let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    let mut group = c.benchmark_group("NIZK_verify_with_key_benchmark");
+    group.plot_config(plot_config);
+
+    let num_vars = (2_usize).pow(s as u32);
+    let num_cons = num_vars;
+    let num_inputs = 10;
+    let (inst, vars, inputs) =
+      Instance::<G::ScalarField>::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+
+    // produce a proof of satisfiability
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let key = NIZKVerifierKey::new(&inst, gens);
+
+    let name = format!("NIZK_verify_with_key_{}", num_cons);
+    group.bench_function(&name, move |b| {
+      b.iter(|| {
+        let mut verifier_transcript = Transcript::new(b"example");
+        assert!(proof
+          .verify_with_key(
+            black_box(&key),
+            black_box(&inputs),
+            black_box(&mut verifier_transcript)
+          )
+          .is_ok());
+      });
+    });
+    group.finish();
+  }
+}
+
 fn set_duration() -> Criterion {
   Criterion::default().sample_size(10)
 }
@@ -90,7 +159,21 @@ fn set_duration() -> Criterion {
 criterion_group! {
 name = benches_nizk;
 config = set_duration();
-targets = nizk_prove_benchmark::<G1Projective>, nizk_verify_benchmark::<G1Projective>
+targets = nizk_prove_benchmark::<G1Projective>, nizk_verify_benchmark::<G1Projective>,
+nizk_verify_with_key_benchmark::<G1Projective>, plain_nizk_prove_benchmark::<G1Projective>
+}
+
+criterion_group! {
+name = benches_nizk_non_pairing;
+config = set_duration();
+targets = nizk_prove_benchmark::<ark_secq256k1::Projective>,
+nizk_verify_benchmark::<ark_secq256k1::Projective>,
+nizk_prove_benchmark::<ark_secp256k1::Projective>,
+nizk_verify_benchmark::<ark_secp256k1::Projective>,
+nizk_prove_benchmark::<ark_pallas::Projective>,
+nizk_verify_benchmark::<ark_pallas::Projective>,
+nizk_prove_benchmark::<ark_vesta::Projective>,
+nizk_verify_benchmark::<ark_vesta::Projective>
 }
 
-criterion_main!(benches_nizk);
+criterion_main!(benches_nizk, benches_nizk_non_pairing);
@@ -134,4 +134,17 @@ targets = snark_encode_benchmark::<G1Projective>,
 snark_prove_benchmark::<G1Projective>, snark_verify_benchmark::<G1Projective>
 }
 
-criterion_main!(benches_snark);
+criterion_group! {
+name = benches_snark_non_pairing;
+config = set_duration();
+targets = snark_prove_benchmark::<ark_secq256k1::Projective>,
+snark_verify_benchmark::<ark_secq256k1::Projective>,
+snark_prove_benchmark::<ark_secp256k1::Projective>,
+snark_verify_benchmark::<ark_secp256k1::Projective>,
+snark_prove_benchmark::<ark_pallas::Projective>,
+snark_verify_benchmark::<ark_pallas::Projective>,
+snark_prove_benchmark::<ark_vesta::Projective>,
+snark_verify_benchmark::<ark_vesta::Projective>
+}
+
+criterion_main!(benches_snark, benches_snark_non_pairing);
@@ -0,0 +1,441 @@
+//! `spartan` is a command-line interface to prove and verify the satisfiability of circom
+//! circuits with Spartan, so that it can be used from scripts.
+//!
+//! Spartan's setup is transparent: the public parameters are derived from the dimensions of a
+//! circuit, which `setup` records in the setup file together with, for SNARKs, the computation
+//! commitment. The public signals of a circuit (its outputs, followed by its public inputs)
+//! are the inputs of the Spartan instance, and are passed to `verify` in the format of
+//! snarkjs' `public.json`: a JSON array of decimal strings.
+//!
+//! Proofs are written as `ProofEnvelope`s, so that a proof for another curve, scheme or circuit
+//! is rejected when it is read.
+#![allow(non_snake_case)]
+
+extern crate libspartan;
+extern crate merlin;
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use libspartan::circom_reader::{
+  from_reader, load_as_spartan_inst_with_public_signals, load_r1cs_from_bin_file,
+  load_witness_from_bin_reader, split_witness,
+};
+use libspartan::{
+  ComputationCommitment, InputsAssignment, Instance, NIZKGens, ProofEnvelope, SNARKGens, NIZK,
+  SNARK,
+};
+use merlin::Transcript;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+const TRANSCRIPT_LABEL: &[u8] = b"spartan_cli";
+
+#[derive(Parser)]
+#[command(
+  name = "spartan",
+  version,
+  about = "Prove and verify circom circuits with Spartan"
+)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Curve {
+  Bn254,
+  #[value(name = "bls12-381")]
+  Bls12_381,
+  #[cfg(feature = "secp256k1")]
+  Secp256k1,
+  #[cfg(feature = "secq256k1")]
+  Secq256k1,
+  #[cfg(feature = "pallas")]
+  Pallas,
+  #[cfg(feature = "vesta")]
+  Vesta,
+}
+
+/// Evaluates `$body` with the type `$G` bound to the group of `$curve`
+macro_rules! with_curve {
+  ($curve:expr, $G:ident => $body:expr) => {
+    match $curve {
+      Curve::Bn254 => {
+        type $G = ark_bn254::G1Projective;
+        $body
+      }
+      Curve::Bls12_381 => {
+        type $G = ark_bls12_381::G1Projective;
+        $body
+      }
+      #[cfg(feature = "secp256k1")]
+      Curve::Secp256k1 => {
+        type $G = ark_secp256k1::Projective;
+        $body
+      }
+      #[cfg(feature = "secq256k1")]
+      Curve::Secq256k1 => {
+        type $G = ark_secq256k1::Projective;
+        $body
+      }
+      #[cfg(feature = "pallas")]
+      Curve::Pallas => {
+        type $G = ark_pallas::Projective;
+        $body
+      }
+      #[cfg(feature = "vesta")]
+      Curve::Vesta => {
+        type $G = ark_vesta::Projective;
+        $body
+      }
+    }
+  };
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Scheme {
+  /// Short proofs that are verified against the computation commitment in the setup file
+  Snark,
+  /// Proofs that are verified against the circuit itself, without preprocessing
+  Nizk,
+}
+
+#[derive(Args)]
+struct ProofSystem {
+  /// The curve, whose scalar field must be the prime of the circuit
+  #[arg(long, value_enum, default_value_t = Curve::Bn254)]
+  curve: Curve,
+  /// The proof system
+  #[arg(long, value_enum, default_value_t = Scheme::Snark)]
+  scheme: Scheme,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Writes the public parameters of a circuit and, for SNARKs, its computation commitment
+  Setup {
+    /// The circuit (.r1cs)
+    #[arg(long)]
+    r1cs: PathBuf,
+    /// The setup file to write
+    #[arg(long)]
+    out: PathBuf,
+    #[command(flatten)]
+    system: ProofSystem,
+  },
+  /// Proves that a witness satisfies a circuit
+  Prove {
+    /// The circuit (.r1cs)
+    #[arg(long)]
+    r1cs: PathBuf,
+    /// The witness computed by circom (.wtns)
+    #[arg(long)]
+    wtns: PathBuf,
+    /// The setup file written by `setup`
+    #[arg(long)]
+    setup: PathBuf,
+    /// The proof file to write
+    #[arg(long)]
+    out: PathBuf,
+    /// Also writes the public signals as a JSON array of decimal strings
+    #[arg(long)]
+    public: Option<PathBuf>,
+    #[command(flatten)]
+    system: ProofSystem,
+  },
+  /// Verifies a proof given the public signals as a JSON array of decimal strings
+  Verify {
+    /// The setup file written by `setup`
+    #[arg(long)]
+    setup: PathBuf,
+    /// The proof file written by `prove`
+    #[arg(long)]
+    proof: PathBuf,
+    /// The public signals (JSON)
+    #[arg(long)]
+    public: PathBuf,
+    /// The circuit (.r1cs), which is needed to verify NIZK proofs
+    #[arg(long, required_if_eq("scheme", "nizk"))]
+    r1cs: Option<PathBuf>,
+    #[command(flatten)]
+    system: ProofSystem,
+  },
+  /// Prints the dimensions of a circuit, its digest and the savings of optimizing it
+  Inspect {
+    /// The circuit (.r1cs)
+    #[arg(long)]
+    r1cs: PathBuf,
+    /// The curve, whose scalar field must be the prime of the circuit
+    #[arg(long, value_enum, default_value_t = Curve::Bn254)]
+    curve: Curve,
+  },
+}
+
+/// The contents of a setup file: the dimensions of the (padded) instance, from which the
+/// public parameters are derived, and the computation commitment of SNARKs
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+struct Setup<G: CurveGroup> {
+  num_cons: usize,
+  num_vars: usize,
+  num_inputs: usize,
+  num_nz_entries: usize,
+  comm: Option<ComputationCommitment<G>>,
+}
+
+impl<G: CurveGroup> Setup<G> {
+  fn new(inst: &Instance<G::ScalarField>) -> Self {
+    Setup {
+      num_cons: inst.get_num_cons(),
+      num_vars: inst.get_num_vars(),
+      num_inputs: inst.get_num_inputs(),
+      num_nz_entries: inst.get_num_nz_entries(),
+      comm: None,
+    }
+  }
+
+  fn check(&self, inst: &Instance<G::ScalarField>) -> CliResult<()> {
+    let setup = Setup::<G>::new(inst);
+    if (
+      self.num_cons,
+      self.num_vars,
+      self.num_inputs,
+      self.num_nz_entries,
+    ) != (
+      setup.num_cons,
+      setup.num_vars,
+      setup.num_inputs,
+      setup.num_nz_entries,
+    ) {
+      return Err("the setup file was created for another circuit".into());
+    }
+    Ok(())
+  }
+
+  fn snark_gens(&self) -> SNARKGens<G> {
+    SNARKGens::new(
+      self.num_cons,
+      self.num_vars,
+      self.num_inputs,
+      self.num_nz_entries,
+    )
+  }
+
+  fn nizk_gens(&self) -> NIZKGens<G> {
+    NIZKGens::new(self.num_cons, self.num_vars, self.num_inputs)
+  }
+
+  fn comm(&self) -> CliResult<&ComputationCommitment<G>> {
+    self
+      .comm
+      .as_ref()
+      .ok_or_else(|| "the setup file was created for NIZK proofs".into())
+  }
+}
+
+fn setup<G: CurveGroup>(r1cs: &Path, out: &Path, scheme: Scheme) -> CliResult<()> {
+  let inst = load::<G::ScalarField>(r1cs)?;
+  let mut setup = Setup::<G>::new(&inst);
+  if let Scheme::Snark = scheme {
+    let (comm, _decomm) = SNARK::encode(&inst, &setup.snark_gens());
+    setup.comm = Some(comm);
+  }
+  write(out, &setup)
+}
+
+fn prove<G: CurveGroup>(
+  r1cs: &Path,
+  wtns: &Path,
+  setup: &Path,
+  out: &Path,
+  public: Option<&Path>,
+  scheme: Scheme,
+) -> CliResult<()> {
+  let inst = load::<G::ScalarField>(r1cs)?;
+  let setup = read::<Setup<G>>(setup)?;
+  setup.check(&inst)?;
+
+  let witness =
+    load_witness_from_bin_reader::<G::ScalarField, _>(BufReader::new(File::open(wtns)?))?;
+  let (vars, inputs) = split_witness(&witness, inst.get_num_inputs());
+  if !inst
+    .is_sat(&vars, &inputs)
+    .map_err(|e| format!("invalid witness: {e:?}"))?
+  {
+    return Err("the witness does not satisfy the circuit".into());
+  }
+
+  let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+  match scheme {
+    Scheme::Snark => {
+      let gens = setup.snark_gens();
+      let (comm, decomm) = SNARK::encode(&inst, &gens);
+      if to_bytes(&comm)? != to_bytes(setup.comm()?)? {
+        return Err("the setup file was created for another circuit".into());
+      }
+      let proof = SNARK::prove(&inst, &comm, &decomm, vars, &inputs, &gens, &mut transcript);
+      ProofEnvelope::<SNARK<G>>::new(proof, &comm).write(BufWriter::new(File::create(out)?))?;
+    }
+    Scheme::Nizk => {
+      let proof = NIZK::prove(&inst, vars, &inputs, &setup.nizk_gens(), &mut transcript);
+      ProofEnvelope::<NIZK<G>>::new(proof, &inst).write(BufWriter::new(File::create(out)?))?;
+    }
+  }
+
+  if let Some(public) = public {
+    let signals = inputs
+      .assignment
+      .iter()
+      .map(|x| x.into_bigint().to_string())
+      .collect::<Vec<String>>();
+    serde_json::to_writer(BufWriter::new(File::create(public)?), &signals)?;
+  }
+  Ok(())
+}
+
+fn verify<G: CurveGroup>(
+  setup: &Path,
+  proof: &Path,
+  public: &Path,
+  r1cs: Option<&Path>,
+  scheme: Scheme,
+) -> CliResult<()> {
+  let setup = read::<Setup<G>>(setup)?;
+
+  let input = InputsAssignment::<G::ScalarField>::from_json(&std::fs::read_to_string(public)?)
+    .map_err(|e| format!("invalid public signals: {e:?}"))?;
+  if input.assignment.len() != setup.num_inputs {
+    return Err(
+      format!(
+        "expected {} public signals, found {}",
+        setup.num_inputs,
+        input.assignment.len()
+      )
+      .into(),
+    );
+  }
+
+  let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+  match scheme {
+    Scheme::Snark => {
+      let comm = setup.comm()?;
+      let proof = ProofEnvelope::<SNARK<G>>::read(BufReader::new(File::open(proof)?), comm)?;
+      proof
+        .proof()
+        .verify(comm, &input, &mut transcript, &setup.snark_gens())?;
+    }
+    Scheme::Nizk => {
+      let inst = load::<G::ScalarField>(r1cs.ok_or("verifying NIZK proofs needs the circuit")?)?;
+      setup.check(&inst)?;
+      let proof = ProofEnvelope::<NIZK<G>>::read(BufReader::new(File::open(proof)?), &inst)?;
+      proof
+        .proof()
+        .verify(&inst, &input, &mut transcript, &setup.nizk_gens())?;
+    }
+  }
+
+  println!("proof verified");
+  Ok(())
+}
+
+fn inspect<G: CurveGroup>(r1cs: &Path) -> CliResult<()> {
+  let inst = load::<G::ScalarField>(r1cs)?;
+  let (circuit, _) = load_r1cs_from_bin_file::<G::ScalarField>(r1cs);
+  println!("constraints: {}", circuit.constraints.len());
+  println!("wires: {}", circuit.num_variables);
+  println!("public signals: {}", circuit.num_inputs - 1);
+  println!("private signals: {}", circuit.num_aux);
+  println!("padded constraints: {}", inst.get_num_cons());
+  println!("padded variables: {}", inst.get_num_vars());
+  println!(
+    "padded non-zero entries per matrix: {}",
+    inst.get_num_nz_entries()
+  );
+  println!(
+    "digest: {}",
+    inst
+      .digest()
+      .iter()
+      .map(|b| format!("{:02x}", b))
+      .collect::<String>()
+  );
+  let (_, optimization) = inst
+    .optimize()
+    .map_err(|e| format!("cannot optimize the circuit: {e:?}"))?;
+  println!("optimization:");
+  for line in optimization.report.to_string().lines() {
+    println!("  {line}");
+  }
+  Ok(())
+}
+
+/// Loads a circuit, reporting malformed files and circuits over another field as errors
+/// rather than panicking in the loader
+fn load<F: PrimeField>(r1cs: &Path) -> CliResult<Instance<F>> {
+  from_reader::<F, _>(BufReader::new(File::open(r1cs)?))?;
+  Ok(load_as_spartan_inst_with_public_signals::<F>(r1cs))
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> CliResult<Vec<u8>> {
+  let mut bytes = vec![];
+  value.serialize_compressed(&mut bytes)?;
+  Ok(bytes)
+}
+
+fn write<T: CanonicalSerialize>(path: &Path, value: &T) -> CliResult<()> {
+  value.serialize_compressed(BufWriter::new(File::create(path)?))?;
+  Ok(())
+}
+
+fn read<T: CanonicalDeserialize>(path: &Path) -> CliResult<T> {
+  Ok(T::deserialize_compressed(BufReader::new(File::open(
+    path,
+  )?))?)
+}
+
+fn run(command: Command) -> CliResult<()> {
+  match command {
+    Command::Setup { r1cs, out, system } => {
+      with_curve!(system.curve, G => setup::<G>(&r1cs, &out, system.scheme))
+    }
+    Command::Prove {
+      r1cs,
+      wtns,
+      setup,
+      out,
+      public,
+      system,
+    } => {
+      let public = public.as_deref();
+      with_curve!(system.curve, G => {
+        prove::<G>(&r1cs, &wtns, &setup, &out, public, system.scheme)
+      })
+    }
+    Command::Verify {
+      setup,
+      proof,
+      public,
+      r1cs,
+      system,
+    } => {
+      let r1cs = r1cs.as_deref();
+      with_curve!(system.curve, G => verify::<G>(&setup, &proof, &public, r1cs, system.scheme))
+    }
+    Command::Inspect { r1cs, curve } => {
+      with_curve!(curve, G => inspect::<G>(&r1cs))
+    }
+  }
+}
+
+fn main() {
+  let cli = Cli::parse();
+  if let Err(e) = run(cli.command) {
+    eprintln!("error: {e}");
+    std::process::exit(1);
+  }
+}
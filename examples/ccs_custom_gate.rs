@@ -0,0 +1,88 @@
+//! Demonstrates how to prove a high-degree custom gate with a customizable constraint system (CCS).
+//! The statement is the Poseidon-style S-box equation `x^5 + x + 5 = y`, where `y` is public.
+//!
+//! In R1CS this needs one constraint per multiplication. As a CCS it is a single constraint
+//! over three matrices that select `x`, the constant `1` and `y` from `z = (vars, 1, inputs)`:
+//! `(M0 z)^5 + (M0 z) + 5 * (M1 z) - (M2 z) = 0`
+#![allow(clippy::assertions_on_result_states)]
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::PrimeField;
+use libspartan::{CCSInstance, CCSPlainNIZK, InputsAssignment, NIZKGens, VarsAssignment};
+use merlin::Transcript;
+
+fn produce_ccs<F: PrimeField>() -> (
+  usize,
+  usize,
+  usize,
+  CCSInstance<F>,
+  VarsAssignment<F>,
+  InputsAssignment<F>,
+) {
+  // parameters of the CCS instance
+  let num_cons = 1;
+  let num_vars = 1;
+  let num_inputs = 1;
+
+  let one = F::one();
+
+  // matrices selecting x, the constant term and the input y
+  let matrices = vec![
+    vec![(0, 0, one)],
+    vec![(0, num_vars, one)],
+    vec![(0, num_vars + 1, one)],
+  ];
+
+  // one multiset (and constant) per term of the gate
+  let multisets = vec![vec![0, 0, 0, 0, 0], vec![0], vec![1], vec![2]];
+  let constants = vec![one, one, F::from(5u64), -one];
+
+  let inst = CCSInstance::new(
+    num_cons, num_vars, num_inputs, &matrices, &multisets, &constants,
+  )
+  .unwrap();
+
+  // compute a satisfying assignment
+  let x = F::from(3u64);
+  let y = x * x * x * x * x + x + F::from(5u64);
+  let assignment_vars = VarsAssignment::new(&[x]).unwrap();
+  let assignment_inputs = InputsAssignment::new(&[y]).unwrap();
+
+  // check if the instance we created is satisfiable
+  let res = inst.is_sat(&assignment_vars, &assignment_inputs);
+  assert!(res.unwrap());
+
+  (
+    num_cons,
+    num_vars,
+    num_inputs,
+    inst,
+    assignment_vars,
+    assignment_inputs,
+  )
+}
+
+fn main() {
+  let (num_cons, num_vars, num_inputs, inst, assignment_vars, assignment_inputs) =
+    produce_ccs::<Fr>();
+  assert_eq!(inst.get_degree(), 5);
+
+  // produce public parameters
+  let gens = NIZKGens::<G1Projective>::new(num_cons, num_vars, num_inputs);
+
+  // produce a proof of satisfiability
+  let mut prover_transcript = Transcript::new(b"ccs_example");
+  let proof = CCSPlainNIZK::prove(
+    &inst,
+    assignment_vars,
+    &assignment_inputs,
+    &gens,
+    &mut prover_transcript,
+  );
+
+  // verify the proof of satisfiability
+  let mut verifier_transcript = Transcript::new(b"ccs_example");
+  assert!(proof
+    .verify(&inst, &assignment_inputs, &mut verifier_transcript, &gens)
+    .is_ok());
+  println!("proof verification successful!");
+}
@@ -115,7 +115,7 @@ fn main() {
     num_cons,
     num_vars,
     num_inputs,
-    num_non_zero_entries,
+    _num_non_zero_entries,
     inst,
     assignment_vars,
     assignment_inputs,
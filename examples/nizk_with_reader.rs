@@ -1,5 +1,4 @@
 #![allow(clippy::assertions_on_result_states)]
-use ark_bn254::Fr;
 use ark_bn254::G1Projective;
 use libspartan::{InputsAssignment, NIZKGens, VarsAssignment, NIZK, circom_reader::{load_as_spartan_inst, load_witness_from_bin_reader}};
 use merlin::Transcript;
@@ -27,19 +26,15 @@ fn main() {
 
   // produce public parameters
   let gens = NIZKGens::<G1Projective>::new(
-    spartan_inst.inst.get_num_cons(), 
-    spartan_inst.inst.get_num_vars(), 
-    spartan_inst.inst.get_num_inputs()
+    spartan_inst.get_num_cons(), 
+    spartan_inst.get_num_vars(), 
+    spartan_inst.get_num_inputs()
   );
 
   // produce a proof of satisfiability
   let mut prover_transcript = Transcript::new(b"nizk_example");
 
-  let mut inputs = vec![Fr::from(0); spartan_inst.inst.get_num_inputs()];
-  for i in 0..spartan_inst.inst.get_num_inputs() {
-      inputs[i] = witness[i];
-  }
-  let inputs = InputsAssignment::new(&inputs).unwrap();
+  let inputs = InputsAssignment::new(&witness[..spartan_inst.get_num_inputs()]).unwrap();
 
   let start_proving = Instant::now();
   let proof = NIZK::prove(
@@ -0,0 +1,64 @@
+//! Demonstrates that Spartan runs over curves without a pairing, e.g. the
+//! secp256k1/secq256k1 cycle (used to verify ECDSA signatures in-circuit) and
+//! the Pallas/Vesta cycle. A synthetic R1CS instance is proven with both the
+//! NIZK and the SNARK variants over each curve.
+#![allow(clippy::assertions_on_result_states)]
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalSerialize, Compress};
+use libspartan::{Instance, NIZKGens, SNARKGens, NIZK, SNARK};
+use merlin::Transcript;
+
+fn prove_and_verify<G: CurveGroup>(curve: &str) {
+  let num_vars = 1024;
+  let num_cons = num_vars;
+  let num_inputs = 10;
+
+  let (inst, vars, inputs) =
+    Instance::<G::ScalarField>::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+  // NIZK: the verifier holds the R1CS instance
+  let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+  let mut prover_transcript = Transcript::new(b"non_pairing_example");
+  let proof = NIZK::prove(&inst, vars.clone(), &inputs, &gens, &mut prover_transcript);
+
+  let mut verifier_transcript = Transcript::new(b"non_pairing_example");
+  assert!(proof
+    .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+    .is_ok());
+  println!(
+    "{}: NIZK proof verified ({} bytes)",
+    curve,
+    proof.serialized_size(Compress::Yes)
+  );
+
+  // SNARK: the verifier holds a succinct commitment to the R1CS instance
+  let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+  let (comm, decomm) = SNARK::encode(&inst, &gens);
+  let mut prover_transcript = Transcript::new(b"non_pairing_example");
+  let proof = SNARK::prove(
+    &inst,
+    &comm,
+    &decomm,
+    vars,
+    &inputs,
+    &gens,
+    &mut prover_transcript,
+  );
+
+  let mut verifier_transcript = Transcript::new(b"non_pairing_example");
+  assert!(proof
+    .verify(&comm, &inputs, &mut verifier_transcript, &gens)
+    .is_ok());
+  println!(
+    "{}: SNARK proof verified ({} bytes)",
+    curve,
+    proof.serialized_size(Compress::Yes)
+  );
+}
+
+fn main() {
+  prove_and_verify::<ark_secp256k1::Projective>("secp256k1");
+  prove_and_verify::<ark_secq256k1::Projective>("secq256k1");
+  prove_and_verify::<ark_pallas::Projective>("pallas");
+  prove_and_verify::<ark_vesta::Projective>("vesta");
+}
@@ -0,0 +1,99 @@
+//! Demonstrates how to use Spartan's Hyrax-style polynomial commitment on its own, as a vector
+//! commitment: a vector of length `2^num_vars` is viewed as the evaluations of a multilinear
+//! polynomial, and an entry or a random linear combination of the entries is opened by
+//! evaluating the polynomial at a point.
+#![allow(clippy::assertions_on_result_states)]
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::{One, Zero};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use ark_std::{test_rng, UniformRand};
+use libspartan::pcs::{
+  BatchedEvalProof, Commitment, EvalProof, HidingEvalProof, MultilinearPolynomial, PCSGens,
+};
+use merlin::Transcript;
+
+fn main() {
+  let num_vars = 10;
+  let mut prng = test_rng();
+  // the randomness of hiding commitments and proofs must come from a cryptographically secure RNG
+  let mut rng = StdRng::from_entropy();
+  let gens = PCSGens::<G1Projective>::new(num_vars, b"pcs_example");
+
+  // commit to a vector without hiding it
+  let vector = (0..1 << num_vars)
+    .map(|_| Fr::rand(&mut prng))
+    .collect::<Vec<_>>();
+  let poly = MultilinearPolynomial::new(vector.clone());
+  let comm = Commitment::commit(&poly, &gens);
+
+  // open the entry at index 5 by evaluating at its binary representation
+  let index = 5;
+  let r = (0..num_vars)
+    .map(|i| {
+      if (index >> (num_vars - 1 - i)) & 1 == 1 {
+        Fr::one()
+      } else {
+        Fr::zero()
+      }
+    })
+    .collect::<Vec<_>>();
+  let mut prover_transcript = Transcript::new(b"pcs_example");
+  let (proof, eval) = EvalProof::prove(&poly, None, &r, &gens, &mut prover_transcript, &mut rng);
+  assert_eq!(eval, vector[index]);
+
+  let mut verifier_transcript = Transcript::new(b"pcs_example");
+  assert!(proof
+    .verify(&comm, &r, &eval, &gens, &mut verifier_transcript)
+    .is_ok());
+  println!("opened entry {} of a committed vector", index);
+
+  // commit to a second vector with a hiding commitment, and prove its evaluation at a random
+  // point without revealing it
+  let hidden =
+    MultilinearPolynomial::new((0..1 << num_vars).map(|_| Fr::rand(&mut prng)).collect());
+  let (comm_hidden, decomm_hidden) = Commitment::commit_hiding(&hidden, &gens, &mut rng);
+  let r = (0..num_vars)
+    .map(|_| Fr::rand(&mut prng))
+    .collect::<Vec<_>>();
+  let mut prover_transcript = Transcript::new(b"pcs_example");
+  let (proof, _eval, _blind_eval) = HidingEvalProof::prove(
+    &hidden,
+    &decomm_hidden,
+    &r,
+    &gens,
+    &mut prover_transcript,
+    &mut rng,
+  );
+
+  let mut verifier_transcript = Transcript::new(b"pcs_example");
+  assert!(proof
+    .verify(&comm_hidden, &r, &gens, &mut verifier_transcript)
+    .is_ok());
+  println!("proved a hidden evaluation of a hiding commitment");
+
+  // open two non-hiding commitments at the same point with a single proof
+  let other = MultilinearPolynomial::new((0..1 << num_vars).map(|_| Fr::rand(&mut prng)).collect());
+  let comm_other = Commitment::commit(&other, &gens);
+  let points = vec![r.clone(), r];
+  let mut prover_transcript = Transcript::new(b"pcs_example");
+  let (proof, evals) = BatchedEvalProof::prove(
+    &[&poly, &other],
+    &[&comm, &comm_other],
+    None,
+    &points,
+    &gens,
+    &mut prover_transcript,
+    &mut rng,
+  );
+  let mut verifier_transcript = Transcript::new(b"pcs_example");
+  assert!(proof
+    .verify(
+      &[&comm, &comm_other],
+      &points,
+      &evals,
+      &gens,
+      &mut verifier_transcript
+    )
+    .is_ok());
+  println!("opened two committed vectors with one proof");
+}
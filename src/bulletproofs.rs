@@ -0,0 +1,530 @@
+//! Bulletproofs-style inner-product and range proofs.
+//!
+//! Both proofs work over any `CurveGroup` with Pedersen commitments from `MultiCommitGens`, the
+//! same generators used by the rest of the crate, and have size logarithmic in the length of the
+//! committed vector.
+//!
+//! An `InnerProductProof` shows that a committed vector `x` and a public vector `a` satisfy
+//! `<x, a> = y` for a committed `y`. A `RangeProof` shows that a value committed as
+//! `v * G[0] + blind * h` under a single-generator `MultiCommitGens` lies in `[0, 2^n)`, for
+//! `n` up to 64. Provers draw their randomness from a cryptographically secure `rng` of the caller.
+//!
+//! ```
+//! # use ark_bls12_381::{Fr, G1Projective};
+//! # use ark_std::rand::{rngs::StdRng, SeedableRng};
+//! # use libspartan::bulletproofs::{RangeProof, RangeProofGens};
+//! # use libspartan::MultiCommitGens;
+//! # use merlin::Transcript;
+//! let gens_1 = MultiCommitGens::<G1Projective>::new(1, b"value_gens");
+//! let gens = RangeProofGens::new(64, b"range_proof_gens", &gens_1);
+//!
+//! let mut rng = StdRng::from_entropy();
+//! let blind = Fr::from(1234u64);
+//! let mut prover_transcript = Transcript::new(b"range_example");
+//! let (proof, comm) = RangeProof::prove(42u64, &blind, &gens, &mut prover_transcript, &mut rng);
+//!
+//! let mut verifier_transcript = Transcript::new(b"range_example");
+//! assert!(proof.verify(&comm, &gens, &mut verifier_transcript).is_ok());
+//! ```
+#![allow(non_snake_case)]
+use super::commitments::{Commitments, MultiCommitGens};
+use super::errors::ProofVerifyError;
+use super::nizk::{BulletInnerProductProof, DotProductProofGens, DotProductProofLog};
+use super::random::RandomTape;
+use super::transcript::ProofTranscript;
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::One;
+use core::iter;
+use merlin::Transcript;
+
+/// `InnerProductGens` holds the generators to commit to vectors of length `n` and to their
+/// inner products
+pub struct InnerProductGens<G: CurveGroup> {
+  gens: DotProductProofGens<G>,
+}
+
+impl<G: CurveGroup> InnerProductGens<G> {
+  /// Produces generators for vectors of length `n`, which must be a power of two
+  pub fn new(n: usize, label: &'static [u8]) -> Self {
+    assert!(n.is_power_of_two());
+    InnerProductGens {
+      gens: DotProductProofGens::new(n, label),
+    }
+  }
+
+  /// Returns the length of the vectors
+  pub fn n(&self) -> usize {
+    self.gens.gens_n.n()
+  }
+
+  /// Returns the generators under which the vector `x` is committed
+  pub fn gens_n(&self) -> &MultiCommitGens<G> {
+    &self.gens.gens_n
+  }
+
+  /// Returns the generators under which the inner product `y` is committed
+  pub fn gens_1(&self) -> &MultiCommitGens<G> {
+    &self.gens.gens_1
+  }
+}
+
+/// `InnerProductProof` shows that the vector committed in `Cx` and a public vector `a` have
+/// the inner product committed in `Cy`
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct InnerProductProof<G: CurveGroup> {
+  proof: DotProductProofLog<G>,
+}
+
+impl<G: CurveGroup> InnerProductProof<G> {
+  /// Proves `<x, a> = y` and returns the proof along with `y` and the commitments `Cx` to `x`
+  /// and `Cy` to `y`, blinded with `blind_x` and `blind_y`
+  pub fn prove<R: RngCore + CryptoRng>(
+    x: &[G::ScalarField],
+    blind_x: &G::ScalarField,
+    a: &[G::ScalarField],
+    blind_y: &G::ScalarField,
+    gens: &InnerProductGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G::ScalarField, G, G) {
+    assert_eq!(x.len(), gens.n());
+    assert_eq!(a.len(), gens.n());
+
+    let y = DotProductProofLog::<G>::compute_dotproduct(x, a);
+
+    let mut random_tape = RandomTape::<G>::new_with_rng(b"inner_product_proof_randomness", rng);
+    random_tape.append_scalar(b"blind_x", blind_x);
+    let (proof, Cx, Cy) = DotProductProofLog::prove(
+      &gens.gens,
+      transcript,
+      &mut random_tape,
+      x,
+      blind_x,
+      a,
+      &y,
+      blind_y,
+    );
+
+    (InnerProductProof { proof }, y, Cx, Cy)
+  }
+
+  /// Verifies the proof against the commitments `Cx` and `Cy` and the public vector `a`
+  pub fn verify(
+    &self,
+    a: &[G::ScalarField],
+    Cx: &G,
+    Cy: &G,
+    gens: &InnerProductGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    if a.len() != gens.n() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    self
+      .proof
+      .verify(gens.n(), &gens.gens, transcript, a, Cx, Cy)
+  }
+}
+
+/// `RangeProofGens` holds the generators to prove that values committed under `gens_1` fit in
+/// `num_bits` bits
+pub struct RangeProofGens<G: CurveGroup> {
+  num_bits: usize,
+  gens_1: MultiCommitGens<G>,
+  G_vec: Vec<G::Affine>,
+  H_vec: Vec<G::Affine>,
+}
+
+impl<G: CurveGroup> RangeProofGens<G> {
+  /// Produces generators for `num_bits`-bit range proofs over values committed under `gens_1`;
+  /// `num_bits` must be a power of two no larger than 64
+  pub fn new(num_bits: usize, label: &'static [u8], gens_1: &MultiCommitGens<G>) -> Self {
+    assert!(num_bits.is_power_of_two() && num_bits <= 64);
+    assert_eq!(gens_1.n(), 1);
+    let (gens_G, gens_H) = MultiCommitGens::new(2 * num_bits, label).split_at(num_bits);
+    RangeProofGens {
+      num_bits,
+      gens_1: gens_1.clone(),
+      G_vec: G::normalize_batch(gens_G.G()),
+      H_vec: G::normalize_batch(gens_H.G()),
+    }
+  }
+
+  /// Returns the number of bits of the range
+  pub fn num_bits(&self) -> usize {
+    self.num_bits
+  }
+
+  /// Returns the generators under which values are committed
+  pub fn gens_1(&self) -> &MultiCommitGens<G> {
+    &self.gens_1
+  }
+}
+
+/// `RangeProof` shows that a committed value lies in `[0, 2^num_bits)`
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeProof<G: CurveGroup> {
+  A: G,
+  S: G,
+  T_1: G,
+  T_2: G,
+  t_x: G::ScalarField,
+  t_x_blinding: G::ScalarField,
+  e_blinding: G::ScalarField,
+  ipp_proof: BulletInnerProductProof<G>,
+}
+
+impl<G: CurveGroup> RangeProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"range proof"
+  }
+
+  /// Proves that `v` fits in `gens.num_bits()` bits and returns the proof along with the
+  /// commitment `v * G[0] + blind * h` under `gens.gens_1()`
+  pub fn prove<R: RngCore + CryptoRng>(
+    v: u64,
+    blind: &G::ScalarField,
+    gens: &RangeProofGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G) {
+    let n = gens.num_bits;
+    assert!(n == 64 || v >> n == 0);
+
+    let mut random_tape = RandomTape::<G>::new_with_rng(b"range_proof_randomness", rng);
+    random_tape.append_scalar(b"blind", blind);
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RangeProof::<G>::protocol_name(),
+    );
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      transcript,
+      b"n",
+      &G::ScalarField::from(n as u64),
+    );
+
+    let V = G::ScalarField::from(v).commit(blind, &gens.gens_1);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"V", &V);
+
+    // commit to the bits a_L of v and to a_R = a_L - 1
+    let a_L = (0..n)
+      .map(|i| G::ScalarField::from((v >> i) & 1))
+      .collect::<Vec<_>>();
+    let a_R = a_L
+      .iter()
+      .map(|a| *a - G::ScalarField::one())
+      .collect::<Vec<_>>();
+    let a_blinding = random_tape.random_scalar(b"a_blinding");
+    let A = commit_pair(&a_L, &a_R, &a_blinding, gens);
+
+    // commit to the blinding vectors s_L and s_R
+    let s_L = random_tape.random_vector(b"s_L", n);
+    let s_R = random_tape.random_vector(b"s_R", n);
+    let s_blinding = random_tape.random_scalar(b"s_blinding");
+    let S = commit_pair(&s_L, &s_R, &s_blinding, gens);
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"A", &A);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"S", &S);
+    let y = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"y");
+    let z = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"z");
+
+    // l(X) = (a_L - z) + s_L X and r(X) = y^n o (a_R + z + s_R X) + z^2 2^n
+    let y_pows = powers(&y, n);
+    let two_pows = powers(&G::ScalarField::from(2u64), n);
+    let z_sq = z.square();
+    let l_0 = a_L.iter().map(|a| *a - z).collect::<Vec<_>>();
+    let l_1 = s_L;
+    let r_0 = (0..n)
+      .map(|i| y_pows[i] * (a_R[i] + z) + z_sq * two_pows[i])
+      .collect::<Vec<_>>();
+    let r_1 = (0..n).map(|i| y_pows[i] * s_R[i]).collect::<Vec<_>>();
+
+    // t(X) = <l(X), r(X)> = t_0 + t_1 X + t_2 X^2
+    let t_1 = inner_product(&l_0, &r_1) + inner_product(&l_1, &r_0);
+    let t_2 = inner_product(&l_1, &r_1);
+    let t_1_blinding = random_tape.random_scalar(b"t_1_blinding");
+    let t_2_blinding = random_tape.random_scalar(b"t_2_blinding");
+    let T_1 = t_1.commit(&t_1_blinding, &gens.gens_1);
+    let T_2 = t_2.commit(&t_2_blinding, &gens.gens_1);
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"T_1", &T_1);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"T_2", &T_2);
+    let x = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"x");
+
+    let l = (0..n).map(|i| l_0[i] + l_1[i] * x).collect::<Vec<_>>();
+    let r = (0..n).map(|i| r_0[i] + r_1[i] * x).collect::<Vec<_>>();
+    let t_x = inner_product(&l, &r);
+    let t_x_blinding = t_2_blinding * x.square() + t_1_blinding * x + z_sq * blind;
+    let e_blinding = a_blinding + s_blinding * x;
+
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"t_x", &t_x);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"t_x_blinding", &t_x_blinding);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"e_blinding", &e_blinding);
+
+    // prove <l, r> = t_x with respect to G and H' = y^{-n} o H
+    let w = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"w");
+    let Q = (gens.gens_1.G()[0] * w).into_affine();
+    let H_prime = H_prime(gens, &y);
+    let ipp_proof = BulletInnerProductProof::prove(transcript, &Q, &gens.G_vec, &H_prime, &l, &r);
+
+    (
+      RangeProof {
+        A,
+        S,
+        T_1,
+        T_2,
+        t_x,
+        t_x_blinding,
+        e_blinding,
+        ipp_proof,
+      },
+      V,
+    )
+  }
+
+  /// Verifies that the value committed in `V` fits in `gens.num_bits()` bits
+  pub fn verify(
+    &self,
+    V: &G,
+    gens: &RangeProofGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    let n = gens.num_bits;
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RangeProof::<G>::protocol_name(),
+    );
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      transcript,
+      b"n",
+      &G::ScalarField::from(n as u64),
+    );
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"V", V);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"A", &self.A);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"S", &self.S);
+    let y = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"y");
+    let z = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"z");
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"T_1", &self.T_1);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"T_2", &self.T_2);
+    let x = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"x");
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"t_x", &self.t_x);
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      transcript,
+      b"t_x_blinding",
+      &self.t_x_blinding,
+    );
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"e_blinding", &self.e_blinding);
+    let w = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"w");
+
+    let y_pows = powers(&y, n);
+    let two_pows = powers(&G::ScalarField::from(2u64), n);
+    let z_sq = z.square();
+
+    // check that t_x = t(x) using the commitments to v, t_1 and t_2:
+    // t_x g + t_x_blinding h = z^2 V + delta(y, z) g + x T_1 + x^2 T_2
+    let sum_y: G::ScalarField = y_pows.iter().sum();
+    let sum_two: G::ScalarField = two_pows.iter().sum();
+    let delta = (z - z_sq) * sum_y - z_sq * z * sum_two;
+    let g = gens.gens_1.G()[0];
+    let h = gens.gens_1.h();
+    let lhs = self.t_x.commit(&self.t_x_blinding, &gens.gens_1);
+    let rhs = msm(
+      &G::normalize_batch(&[*V, g, self.T_1, self.T_2]),
+      &[z_sq, delta, x, x.square()],
+    );
+    if lhs != rhs {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    // P = A + x S - z <1, G> + <z y^n + z^2 2^n, H'> - e_blinding h + t_x Q, which must open
+    // to <l, G> + <r, H'> + <l, r> Q
+    let Q = (g * w).into_affine();
+    let H_prime = H_prime(gens, &y);
+    let bases = G::normalize_batch(&[self.A, self.S, h])
+      .iter()
+      .chain(iter::once(&Q))
+      .chain(gens.G_vec.iter())
+      .chain(H_prime.iter())
+      .copied()
+      .collect::<Vec<_>>();
+    let scalars = [G::ScalarField::one(), x, -self.e_blinding, self.t_x]
+      .into_iter()
+      .chain(iter::repeat_n(-z, n))
+      .chain((0..n).map(|i| z * y_pows[i] + z_sq * two_pows[i]))
+      .collect::<Vec<_>>();
+    let P = msm(&bases, &scalars);
+
+    self
+      .ipp_proof
+      .verify(n, transcript, &P, &Q, &gens.G_vec, &H_prime)
+  }
+}
+
+fn commit_pair<G: CurveGroup>(
+  a: &[G::ScalarField],
+  b: &[G::ScalarField],
+  blind: &G::ScalarField,
+  gens: &RangeProofGens<G>,
+) -> G {
+  let bases = gens
+    .G_vec
+    .iter()
+    .chain(gens.H_vec.iter())
+    .chain(iter::once(&gens.gens_1.h().into_affine()))
+    .copied()
+    .collect::<Vec<_>>();
+  let scalars = a
+    .iter()
+    .chain(b.iter())
+    .chain(iter::once(blind))
+    .copied()
+    .collect::<Vec<_>>();
+  msm(&bases, &scalars)
+}
+
+fn H_prime<G: CurveGroup>(gens: &RangeProofGens<G>, y: &G::ScalarField) -> Vec<G::Affine> {
+  let y_inv_pows = powers(&y.inverse().unwrap(), gens.num_bits);
+  let H_prime = gens
+    .H_vec
+    .iter()
+    .zip(y_inv_pows.iter())
+    .map(|(H, y_inv)| *H * y_inv)
+    .collect::<Vec<G>>();
+  G::normalize_batch(&H_prime)
+}
+
+fn msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+  VariableBaseMSM::msm(bases, scalars).unwrap()
+}
+
+fn powers<F: PrimeField>(x: &F, n: usize) -> Vec<F> {
+  iter::successors(Some(F::one()), |p| Some(*p * x))
+    .take(n)
+    .collect()
+}
+
+fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+  a.iter()
+    .zip(b.iter())
+    .fold(F::zero(), |acc, (a, b)| acc + *a * b)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_std::rand::{rngs::StdRng, SeedableRng};
+  use ark_std::UniformRand;
+
+  #[test]
+  fn check_inner_product_proof() {
+    check_inner_product_proof_helper::<G1Projective>()
+  }
+
+  fn check_inner_product_proof_helper<G: CurveGroup>() {
+    let n = 32;
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens = InnerProductGens::<G>::new(n, b"test-inner-product");
+    let x = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let a = (0..n)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let blind_x = G::ScalarField::rand(&mut prng);
+    let blind_y = G::ScalarField::rand(&mut prng);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, y, Cx, Cy) = InnerProductProof::prove(
+      &x,
+      &blind_x,
+      &a,
+      &blind_y,
+      &gens,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    assert_eq!(y, inner_product(&x, &a));
+    assert_eq!(Cy, y.commit(&blind_y, gens.gens_1()));
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&a, &Cx, &Cy, &gens, &mut verifier_transcript)
+      .is_ok());
+
+    // a commitment to a different inner product is rejected
+    let Cy_wrong = (y + G::ScalarField::one()).commit(&blind_y, gens.gens_1());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&a, &Cx, &Cy_wrong, &gens, &mut verifier_transcript)
+      .is_err());
+  }
+
+  #[test]
+  fn check_range_proof() {
+    check_range_proof_helper::<G1Projective>()
+  }
+
+  fn check_range_proof_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-range-proof-value");
+
+    for (num_bits, v) in [(8, 0u64), (8, 255), (32, 1 << 31), (64, u64::MAX)] {
+      let gens = RangeProofGens::new(num_bits, b"test-range-proof", &gens_1);
+      let blind = G::ScalarField::rand(&mut prng);
+
+      let mut prover_transcript = Transcript::new(b"example");
+      let (proof, V) = RangeProof::prove(v, &blind, &gens, &mut prover_transcript, &mut prng);
+      assert_eq!(V, G::ScalarField::from(v).commit(&blind, &gens_1));
+
+      let mut verifier_transcript = Transcript::new(b"example");
+      assert!(proof.verify(&V, &gens, &mut verifier_transcript).is_ok());
+
+      // the proof does not verify against a commitment to another value
+      let V_wrong = G::ScalarField::from(v ^ 1).commit(&blind, &gens_1);
+      let mut verifier_transcript = Transcript::new(b"example");
+      assert!(proof
+        .verify(&V_wrong, &gens, &mut verifier_transcript)
+        .is_err());
+    }
+
+    // a value that does not fit in the range cannot be proven by committing to its low bits
+    let gens = RangeProofGens::new(8, b"test-range-proof", &gens_1);
+    let blind = G::ScalarField::rand(&mut prng);
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, _) = RangeProof::prove(44u64, &blind, &gens, &mut prover_transcript, &mut prng);
+    let V_out = G::ScalarField::from(300u64).commit(&blind, &gens_1);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&V_out, &gens, &mut verifier_transcript)
+      .is_err());
+
+    // proofs of the same opening draw fresh randomness, e.g., for the commitment A to the bits
+    let encode = |proof: &RangeProof<G>| {
+      let mut bytes = Vec::new();
+      proof.serialize_compressed(&mut bytes).unwrap();
+      bytes
+    };
+    let (proof1, _) = RangeProof::prove(
+      44u64,
+      &blind,
+      &gens,
+      &mut Transcript::new(b"example"),
+      &mut prng,
+    );
+    let (proof2, _) = RangeProof::prove(
+      44u64,
+      &blind,
+      &gens,
+      &mut Transcript::new(b"example"),
+      &mut prng,
+    );
+    assert_ne!(proof1.A, proof2.A);
+    assert_ne!(encode(&proof1), encode(&proof2));
+  }
+}
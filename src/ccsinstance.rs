@@ -0,0 +1,383 @@
+//! Customizable constraint systems (CCS).
+//!
+//! A CCS instance consists of matrices `M_0, ..., M_{t-1}`, multisets `S_0, ..., S_{q-1}`
+//! over `{0, ..., t-1}` and constants `c_0, ..., c_{q-1}`. An assignment `z = (vars, 1, io)`
+//! satisfies the instance if `sum_i c_i * hadamard_{j in S_i} (M_j * z) = 0`.
+//! R1CS is the special case with matrices `A, B, C`, multisets `{0, 1}, {2}` and constants `1, -1`.
+use super::dense_mlpoly::DensePolynomial;
+use super::errors::{ProofVerifyError, R1CSError};
+use super::math::Math;
+use super::r1csinstance::R1CSInstance;
+use super::sparse_mlpoly::{SparseMatEntry, SparseMatPolynomial};
+use super::timer::Timer;
+use super::transcript::AppendToTranscript;
+use super::{InputsAssignment, VarsAssignment};
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::test_rng;
+use core::cmp::max;
+use merlin::Transcript;
+
+/// `CCSInstance` holds the description of a customizable constraint system
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CCSInstance<F: PrimeField> {
+  num_cons: usize,
+  num_vars: usize,
+  num_inputs: usize,
+  matrices: Vec<SparseMatPolynomial<F>>,
+  multisets: Vec<Vec<usize>>,
+  constants: Vec<F>,
+}
+
+impl<G: CurveGroup> AppendToTranscript<G> for CCSInstance<G::ScalarField> {
+  fn append_to_transcript(&self, _label: &'static [u8], transcript: &mut Transcript) {
+    let mut data = vec![];
+    self.serialize_compressed(&mut data).unwrap();
+
+    transcript.append_message(b"CCSInstance", &data);
+  }
+}
+
+impl<F: PrimeField> CCSInstance<F> {
+  /// Constructs a new `CCSInstance` from matrices given as (row, col, val) tuples,
+  /// the multisets of matrix indices, and one constant per multiset.
+  /// As with `Instance::new`, columns are laid out as `[vars, 1, io]` and the
+  /// number of constraints and variables are padded to powers of two.
+  pub fn new(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    matrices: &[Vec<(usize, usize, F)>],
+    multisets: &[Vec<usize>],
+    constants: &[F],
+  ) -> Result<Self, R1CSError> {
+    if multisets.is_empty() || multisets.len() != constants.len() {
+      return Err(R1CSError::InvalidMultiset);
+    }
+    for multiset in multisets {
+      if multiset.is_empty() || multiset.iter().any(|&j| j >= matrices.len()) {
+        return Err(R1CSError::InvalidMultiset);
+      }
+    }
+
+    let num_vars_padded = max(num_vars, num_inputs + 1).next_power_of_two();
+    let num_cons_padded = max(num_cons, 2).next_power_of_two();
+
+    let num_poly_vars_x = num_cons_padded.log_2();
+    let num_poly_vars_y = (2 * num_vars_padded).log_2();
+
+    let mut mats = Vec::with_capacity(matrices.len());
+    for tups in matrices {
+      let mut mat: Vec<SparseMatEntry<F>> = Vec::new();
+      for &(row, col, val) in tups {
+        // row must be smaller than num_cons
+        if row >= num_cons {
+          return Err(R1CSError::InvalidIndex);
+        }
+
+        // col must be smaller than num_vars + 1 + num_inputs
+        if col >= num_vars + 1 + num_inputs {
+          return Err(R1CSError::InvalidIndex);
+        }
+
+        if col >= num_vars {
+          mat.push(SparseMatEntry::new(
+            row,
+            col + num_vars_padded - num_vars,
+            val,
+          ));
+        } else {
+          mat.push(SparseMatEntry::new(row, col, val));
+        }
+      }
+      mats.push(SparseMatPolynomial::new(
+        num_poly_vars_x,
+        num_poly_vars_y,
+        mat,
+      ));
+    }
+
+    Timer::print(&format!("number_of_constraints {}", num_cons_padded));
+    Timer::print(&format!("number_of_variables {}", num_vars_padded));
+    Timer::print(&format!("number_of_inputs {}", num_inputs));
+    Timer::print(&format!("number_of_matrices {}", mats.len()));
+
+    Ok(CCSInstance {
+      num_cons: num_cons_padded,
+      num_vars: num_vars_padded,
+      num_inputs,
+      matrices: mats,
+      multisets: multisets.to_vec(),
+      constants: constants.to_vec(),
+    })
+  }
+
+  /// Converts an R1CS instance into an equivalent CCS instance
+  pub(crate) fn from_r1cs(inst: &R1CSInstance<F>) -> Self {
+    CCSInstance {
+      num_cons: inst.get_num_cons(),
+      num_vars: inst.get_num_vars(),
+      num_inputs: inst.get_num_inputs(),
+      matrices: inst
+        .get_matrices()
+        .iter()
+        .map(|&M| M.clone())
+        .collect::<Vec<SparseMatPolynomial<F>>>(),
+      multisets: vec![vec![0, 1], vec![2]],
+      constants: vec![F::one(), -F::one()],
+    }
+  }
+
+  /// Returns the number of constraints (padded to a power of two)
+  pub fn get_num_cons(&self) -> usize {
+    self.num_cons
+  }
+
+  /// Returns the number of variables (padded to a power of two)
+  pub fn get_num_vars(&self) -> usize {
+    self.num_vars
+  }
+
+  /// Returns the number of public inputs
+  pub fn get_num_inputs(&self) -> usize {
+    self.num_inputs
+  }
+
+  /// Returns the number of matrices
+  pub fn get_num_matrices(&self) -> usize {
+    self.matrices.len()
+  }
+
+  /// Returns the degree of the constraint system, i.e., the size of the largest multiset
+  pub fn get_degree(&self) -> usize {
+    self.multisets.iter().map(|s| s.len()).max().unwrap()
+  }
+
+  /// Checks if the instance is satisfiable with the given variables and inputs assignments
+  pub fn is_sat(
+    &self,
+    vars: &VarsAssignment<F>,
+    inputs: &InputsAssignment<F>,
+  ) -> Result<bool, R1CSError> {
+    if vars.assignment.len() > self.num_vars {
+      return Err(R1CSError::InvalidNumberOfVars);
+    }
+
+    if inputs.assignment.len() != self.num_inputs {
+      return Err(R1CSError::InvalidNumberOfInputs);
+    }
+
+    let z = {
+      let mut z = vars.clone().pad(self.num_vars).assignment;
+      z.push(F::one());
+      z.extend(&inputs.assignment);
+      z
+    };
+
+    let Mz = self.multiply_vec(self.num_cons, z.len(), &z);
+    let res = (0..self.num_cons).all(|i| {
+      self
+        .combine(&Mz.iter().map(|p| p[i]).collect::<Vec<F>>())
+        .is_zero()
+    });
+
+    Ok(res)
+  }
+
+  /// Evaluates `sum_i c_i * prod_{j in S_i} v_j` given the values `v_j` of the matrix-vector products
+  pub(crate) fn combine(&self, vals: &[F]) -> F {
+    self
+      .multisets
+      .iter()
+      .zip(self.constants.iter())
+      .map(|(multiset, c)| multiset.iter().fold(*c, |acc, &j| acc * vals[j]))
+      .sum()
+  }
+
+  pub(crate) fn multiply_vec(
+    &self,
+    num_rows: usize,
+    num_cols: usize,
+    z: &[F],
+  ) -> Vec<DensePolynomial<F>> {
+    assert_eq!(num_rows, self.num_cons);
+    assert_eq!(z.len(), num_cols);
+    assert!(num_cols > self.num_vars);
+    self
+      .matrices
+      .iter()
+      .map(|M| DensePolynomial::new(M.multiply_vec(num_rows, num_cols, z)))
+      .collect()
+  }
+
+  pub(crate) fn compute_eval_table_sparse(
+    &self,
+    num_rows: usize,
+    num_cols: usize,
+    evals: &[F],
+  ) -> Vec<Vec<F>> {
+    assert_eq!(num_rows, self.num_cons);
+    assert!(num_cols > self.num_vars);
+    self
+      .matrices
+      .iter()
+      .map(|M| M.compute_eval_table_sparse(evals, num_rows, num_cols))
+      .collect()
+  }
+
+  /// Evaluates the multilinear extensions of all matrices at (rx, ry)
+  pub fn evaluate(&self, rx: &[F], ry: &[F]) -> Vec<F> {
+    let matrices = self
+      .matrices
+      .iter()
+      .collect::<Vec<&SparseMatPolynomial<F>>>();
+    SparseMatPolynomial::multi_evaluate(&matrices, rx, ry)
+  }
+
+  // evaluates the matrices at a point supplied by a prover, which must have one coordinate per
+  // variable of the matrices
+  pub(crate) fn evaluate_claimed(&self, rx: &[F], ry: &[F]) -> Result<Vec<F>, ProofVerifyError> {
+    if rx.len() != self.num_cons.log_2() || ry.len() != (2 * self.num_vars).log_2() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    Ok(self.evaluate(rx, ry))
+  }
+
+  /// Constructs a synthetic degree-3 `CCSInstance` and an associated satisfying assignment.
+  /// Each constraint has the form `(M_0 z) * (M_1 z) * (M_2 z) - (M_3 z) = 0`.
+  pub fn produce_synthetic_ccs(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+  ) -> (CCSInstance<F>, VarsAssignment<F>, InputsAssignment<F>) {
+    Timer::print(&format!("number_of_constraints {}", num_cons));
+    Timer::print(&format!("number_of_variables {}", num_vars));
+    Timer::print(&format!("number_of_inputs {}", num_inputs));
+
+    let mut prng = test_rng();
+
+    // assert num_cons and num_vars are power of 2
+    assert_eq!(num_cons.log_2().pow2(), num_cons);
+    assert_eq!(num_vars.log_2().pow2(), num_vars);
+
+    // num_inputs + 1 <= num_vars
+    assert!(num_inputs < num_vars);
+
+    // z is organized as [vars,1,io]
+    let size_z = num_vars + num_inputs + 1;
+
+    // produce a random satisfying assignment
+    let Z = {
+      let mut Z: Vec<F> = (0..size_z).map(|_i| F::rand(&mut prng)).collect::<Vec<F>>();
+      Z[num_vars] = F::one(); // set the constant term to 1
+      Z
+    };
+
+    // four sparse matrices
+    let mut M: Vec<Vec<SparseMatEntry<F>>> = vec![Vec::new(); 4];
+    let one = F::one();
+    for i in 0..num_cons {
+      let idx = [i % size_z, (i + 2) % size_z, (i + 5) % size_z];
+      let mut prod = F::one();
+      for (j, &k) in idx.iter().enumerate() {
+        M[j].push(SparseMatEntry::new(i, k, one));
+        prod *= Z[k];
+      }
+
+      let out_idx = (i + 3) % size_z;
+      let out_val = Z[out_idx];
+      if out_val.is_zero() {
+        M[3].push(SparseMatEntry::new(i, num_vars, prod));
+      } else {
+        M[3].push(SparseMatEntry::new(
+          i,
+          out_idx,
+          prod * out_val.inverse().unwrap(),
+        ));
+      }
+    }
+
+    let num_poly_vars_x = num_cons.log_2();
+    let num_poly_vars_y = (2 * num_vars).log_2();
+    let inst = CCSInstance {
+      num_cons,
+      num_vars,
+      num_inputs,
+      matrices: M
+        .into_iter()
+        .map(|entries| SparseMatPolynomial::new(num_poly_vars_x, num_poly_vars_y, entries))
+        .collect(),
+      multisets: vec![vec![0, 1, 2], vec![3]],
+      constants: vec![F::one(), -F::one()],
+    };
+
+    let vars = VarsAssignment {
+      assignment: Z[..num_vars].to_vec(),
+    };
+    let inputs = InputsAssignment {
+      assignment: Z[num_vars + 1..].to_vec(),
+    };
+    assert!(inst.is_sat(&vars, &inputs).unwrap());
+
+    (inst, vars, inputs)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::Fr;
+
+  #[test]
+  fn check_ccs_is_sat() {
+    check_ccs_is_sat_helper::<Fr>()
+  }
+
+  fn check_ccs_is_sat_helper<F: PrimeField>() {
+    // a single constraint x^5 = y, using five copies of the matrix selecting x
+    let num_cons = 1;
+    let num_vars = 2;
+    let num_inputs = 1;
+
+    let one = F::one();
+    let x = (0, 0, one);
+    let y = (0, num_vars + 1, one);
+    let matrices = vec![vec![x], vec![y]];
+    let multisets = vec![vec![0, 0, 0, 0, 0], vec![1]];
+    let constants = vec![one, -one];
+    let inst = CCSInstance::new(
+      num_cons, num_vars, num_inputs, &matrices, &multisets, &constants,
+    )
+    .unwrap();
+    assert_eq!(inst.get_degree(), 5);
+    assert_eq!(inst.get_num_cons(), 2);
+
+    let three = F::from(3u64);
+    let vars = VarsAssignment::new(&[three, F::zero()]).unwrap();
+    let good = InputsAssignment::new(&[F::from(243u64)]).unwrap();
+    let bad = InputsAssignment::new(&[F::from(242u64)]).unwrap();
+    assert!(inst.is_sat(&vars, &good).unwrap());
+    assert!(!inst.is_sat(&vars, &bad).unwrap());
+
+    // multisets must refer to existing matrices and come with a constant each
+    assert!(CCSInstance::new(
+      num_cons,
+      num_vars,
+      num_inputs,
+      &matrices,
+      &[vec![2]],
+      &[one]
+    )
+    .is_err());
+    assert!(CCSInstance::new(
+      num_cons,
+      num_vars,
+      num_inputs,
+      &matrices,
+      &multisets,
+      &[one]
+    )
+    .is_err());
+  }
+}
@@ -0,0 +1,232 @@
+use super::ccsinstance::CCSInstance;
+use super::dense_mlpoly::{DensePolynomial, EqPolynomial, PolyCommitment, PolyEvalProof};
+use super::errors::ProofVerifyError;
+use super::math::Math;
+use super::r1csproof::{
+  build_z, commit_vars_plain, eval_Z_at_ry, eval_inputs, prove_eval_plain, prove_phase_two_plain,
+  verify_eval_plain, R1CSGens,
+};
+use super::random::RandomTape;
+use super::sumcheck::SumcheckInstanceProof;
+use super::timer::Timer;
+use super::transcript::{AppendToTranscript, ProofTranscript};
+use ark_ec::CurveGroup;
+use ark_serialize::*;
+use ark_std::{One, Zero};
+use merlin::Transcript;
+
+/// A SuperSpartan proof of satisfiability of a CCS instance. As with
+/// `R1CSPlainProof`, the witness commitment is not hiding and the sum-check
+/// messages are sent in the clear.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct CCSProof<G: CurveGroup> {
+  comm_vars: PolyCommitment<G>,
+  sc_proof_phase1: SumcheckInstanceProof<G::ScalarField>,
+  claims_phase2: Vec<G::ScalarField>,
+  sc_proof_phase2: SumcheckInstanceProof<G::ScalarField>,
+  eval_vars_at_ry: G::ScalarField,
+  proof_eval_vars_at_ry: PolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> CCSProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"CCS proof"
+  }
+
+  pub fn prove(
+    inst: &CCSInstance<G::ScalarField>,
+    vars: Vec<G::ScalarField>,
+    input: &[G::ScalarField],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (CCSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    let timer_prove = Timer::new("CCSProof::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      CCSProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    let timer_commit = Timer::new("polycommit");
+    let (poly_vars, comm_vars) = commit_vars_plain(&vars, gens, transcript);
+    timer_commit.stop();
+
+    let timer_sc_proof_phase1 = Timer::new("prove_sc_phase_one");
+    let z = build_z::<G>(vars, G::ScalarField::one(), input);
+
+    // derive the verifier's challenge tau
+    let num_rounds_x = inst.get_num_cons().log_2();
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // the first polynomial is eq(tau, x), followed by M_j * z for every matrix M_j
+    let mut polys = vec![DensePolynomial::new(EqPolynomial::new(tau).evals())];
+    polys.extend(inst.multiply_vec(inst.get_num_cons(), z.len(), &z));
+
+    let comb_func =
+      |evals: &[G::ScalarField]| -> G::ScalarField { evals[0] * inst.combine(&evals[1..]) };
+    let (sc_proof_phase1, rx, claims_phase1) = SumcheckInstanceProof::prove_arbitrary::<_, G>(
+      &G::ScalarField::zero(), // claim is zero
+      num_rounds_x,
+      &mut polys,
+      comb_func,
+      inst.get_degree() + 1,
+      transcript,
+    );
+    timer_sc_proof_phase1.stop();
+
+    let claims_phase2 = claims_phase1[1..].to_vec();
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &claims_phase2,
+    );
+
+    let timer_sc_proof_phase2 = Timer::new("prove_sc_phase_two");
+    // combine the claims on M_j * z into a single claim
+    let rho = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_rho",
+      inst.get_num_matrices(),
+    );
+    let claim_phase2 = (0..rho.len())
+      .map(|j| rho[j] * claims_phase2[j])
+      .sum::<G::ScalarField>();
+
+    let evals_M = {
+      let evals_rx = EqPolynomial::new(rx.clone()).evals();
+      let evals = inst.compute_eval_table_sparse(inst.get_num_cons(), z.len(), &evals_rx);
+      (0..z.len())
+        .map(|i| (0..rho.len()).map(|j| rho[j] * evals[j][i]).sum())
+        .collect::<Vec<G::ScalarField>>()
+    };
+
+    let (sc_proof_phase2, ry) = prove_phase_two_plain::<G>(&claim_phase2, z, evals_M, transcript);
+    timer_sc_proof_phase2.stop();
+
+    let timer_polyeval = Timer::new("polyeval");
+    let (eval_vars_at_ry, proof_eval_vars_at_ry) = prove_eval_plain(
+      b"eval_vars_at_ry",
+      &poly_vars,
+      &ry[1..],
+      &gens.gens_pc,
+      transcript,
+      random_tape,
+    );
+    timer_polyeval.stop();
+
+    timer_prove.stop();
+
+    (
+      CCSProof {
+        comm_vars,
+        sc_proof_phase1,
+        claims_phase2,
+        sc_proof_phase2,
+        eval_vars_at_ry,
+        proof_eval_vars_at_ry,
+      },
+      rx,
+      ry,
+    )
+  }
+
+  #[allow(clippy::type_complexity)]
+  pub fn verify(
+    &self,
+    inst: &CCSInstance<G::ScalarField>,
+    input: &[G::ScalarField],
+    evals: &[G::ScalarField],
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      CCSProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+
+    let n = inst.get_num_vars();
+    // add the commitment to the verifier's transcript
+    self
+      .comm_vars
+      .append_to_transcript(b"poly_commitment", transcript);
+
+    let (num_rounds_x, num_rounds_y) = (inst.get_num_cons().log_2(), (2 * n).log_2());
+
+    // derive the verifier's challenge tau
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // verify the first sum-check instance
+    let (claim_post_phase1, rx) = self.sc_proof_phase1.verify::<G>(
+      G::ScalarField::zero(),
+      num_rounds_x,
+      inst.get_degree() + 1,
+      transcript,
+    )?;
+
+    // perform the intermediate sum-check test with the claimed M_j * z values
+    if self.claims_phase2.len() != inst.get_num_matrices() || evals.len() != inst.get_num_matrices()
+    {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let taus_bound_rx = EqPolynomial::new(tau).evaluate(&rx);
+    if claim_post_phase1 != inst.combine(&self.claims_phase2) * taus_bound_rx {
+      return Err(ProofVerifyError::InternalError);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &self.claims_phase2,
+    );
+
+    // derive public challenges and then derive a joint claim
+    let rho = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_rho",
+      inst.get_num_matrices(),
+    );
+    let claim_phase2 = (0..rho.len())
+      .map(|j| rho[j] * self.claims_phase2[j])
+      .sum::<G::ScalarField>();
+
+    // verify the joint claim with a sum-check protocol
+    let (claim_post_phase2, ry) =
+      self
+        .sc_proof_phase2
+        .verify::<G>(claim_phase2, num_rounds_y, 2, transcript)?;
+
+    // verify Z(ry) proof against the initial commitment
+    verify_eval_plain(
+      b"eval_vars_at_ry",
+      &self.proof_eval_vars_at_ry,
+      &ry[1..],
+      &self.eval_vars_at_ry,
+      &self.comm_vars,
+      &gens.gens_pc,
+      transcript,
+    )?;
+
+    let eval_inputs_at_ry = eval_inputs::<G>(n, G::ScalarField::one(), input, &ry[1..]);
+    let eval_Z_at_ry = eval_Z_at_ry::<G>(&ry, self.eval_vars_at_ry, eval_inputs_at_ry);
+
+    // perform the final check in the second sum-check protocol
+    let eval_M_r = (0..rho.len())
+      .map(|j| rho[j] * evals[j])
+      .sum::<G::ScalarField>();
+    if claim_post_phase2 != eval_Z_at_ry * eval_M_r {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    Ok((rx, ry))
+  }
+}
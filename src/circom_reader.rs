@@ -1,13 +1,12 @@
-// use ark_ec::bn::G1Projective;
 // Code borrowed from Nova-Scotia https://github.com/nalinbhardwaj/Nova-Scotia
-use super::Instance;
-// use ff::PrimeField;
-// use secq256k1::AffinePoint;
-// use secq256k1::FieldBytes;
-use ark_bn254::{Fr, G1Projective};
-use ark_ec::CurveGroup;
-use ark_ff::PrimeField;
-use ark_serialize::CanonicalDeserialize;
+//! Loaders for circom's `.r1cs` and `.wtns` binary files.
+//!
+//! The loaders are generic over the scalar field, so the same code reads
+//! circuits compiled for bn254 (circom's default), secq256k1 (ECDSA-in-circuit),
+//! pallas/vesta, etc. The prime recorded in the file is checked against the
+//! modulus of the requested field.
+use super::{Instance, InputsAssignment, VarsAssignment};
+use ark_ff::{BigInteger, PrimeField};
 
 
 use std::path::PathBuf;
@@ -54,10 +53,12 @@ pub struct R1CSFile<Fr> {
 use std::fs::OpenOptions;
 use std::path::Path;
 
-pub fn load_as_spartan_inst(circuit_file: PathBuf, num_pub_inputs: usize) -> Instance<Fr> {
-    let (r1cs, _) = load_r1cs_from_bin_file::<G1Projective>(&circuit_file);
-    let spartan_inst = convert_to_spartan_r1cs(&r1cs, num_pub_inputs);
-    spartan_inst
+pub fn load_as_spartan_inst<Fr: PrimeField>(
+    circuit_file: PathBuf,
+    num_pub_inputs: usize,
+) -> Instance<Fr> {
+    let (r1cs, _) = load_r1cs_from_bin_file::<Fr>(&circuit_file);
+    convert_to_spartan_r1cs(&r1cs, num_pub_inputs)
 }
   
 fn convert_to_spartan_r1cs<Fr: PrimeField>(
@@ -105,16 +106,64 @@ fn convert_to_spartan_r1cs<Fr: PrimeField>(
     inst
 }
 
-pub fn load_r1cs_from_bin_file<G1: CurveGroup>(filename: &Path) -> (R1CS<Fr>, Vec<usize>) {
+/// Loads a circuit such that circom's public signals (wires `1..=n_pub_out + n_pub_in`) are the
+/// inputs of the Spartan instance and the private wires are its variables, so that a proof
+/// binds the public signals. Use `split_witness` to obtain the matching assignments.
+pub fn load_as_spartan_inst_with_public_signals<Fr: PrimeField>(
+    circuit_file: &Path,
+) -> Instance<Fr> {
+    let (r1cs, _) = load_r1cs_from_bin_file::<Fr>(circuit_file);
+    convert_to_spartan_r1cs_with_public_signals(&r1cs)
+}
+
+fn convert_to_spartan_r1cs_with_public_signals<Fr: PrimeField>(r1cs: &R1CS<Fr>) -> Instance<Fr> {
+    let num_cons = r1cs.constraints.len();
+    let num_vars = r1cs.num_aux;
+    let num_inputs = r1cs.num_inputs - 1;
+
+    // circom's wires are (1, public signals, private signals), while Spartan expects
+    // z = (vars, 1, inputs)
+    let column = |wire: usize| {
+        if wire < r1cs.num_inputs {
+            num_vars + wire
+        } else {
+            wire - r1cs.num_inputs
+        }
+    };
+
+    let mut A = vec![];
+    let mut B = vec![];
+    let mut C = vec![];
+    for (i, (a, b, c)) in r1cs.constraints.iter().enumerate() {
+        A.extend(a.iter().map(|(j, coeff)| (i, column(*j), *coeff)));
+        B.extend(b.iter().map(|(j, coeff)| (i, column(*j), *coeff)));
+        C.extend(c.iter().map(|(j, coeff)| (i, column(*j), *coeff)));
+    }
+
+    Instance::<Fr>::new(num_cons, num_vars, num_inputs, &A, &B, &C).unwrap()
+}
+
+/// Splits a circom witness into the variables and the public inputs of an instance with
+/// `num_inputs` inputs loaded with `load_as_spartan_inst_with_public_signals`
+pub fn split_witness<Fr: PrimeField>(
+    witness: &[Fr],
+    num_inputs: usize,
+) -> (VarsAssignment<Fr>, InputsAssignment<Fr>) {
+    let vars = VarsAssignment::new(&witness[num_inputs + 1..]).unwrap();
+    let inputs = InputsAssignment::new(&witness[1..num_inputs + 1]).unwrap();
+    (vars, inputs)
+}
+
+pub fn load_r1cs_from_bin_file<Fr: PrimeField>(filename: &Path) -> (R1CS<Fr>, Vec<usize>) {
     let reader = OpenOptions::new()
         .read(true)
         .open(filename)
         .expect("unable to open.");
-    load_r1cs_from_bin::<G1, _>(BufReader::new(reader))
+    load_r1cs_from_bin::<Fr, _>(BufReader::new(reader))
 }
 
-pub fn load_r1cs_from_bin<G1: CurveGroup, R: Read + Seek>(reader: R) -> (R1CS<Fr>, Vec<usize>) {
-    let file = from_reader::<G1, R>(reader).expect("unable to read.");
+pub fn load_r1cs_from_bin<Fr: PrimeField, R: Read + Seek>(reader: R) -> (R1CS<Fr>, Vec<usize>) {
+    let file = from_reader::<Fr, R>(reader).expect("unable to read.");
     let num_inputs = (1 + file.header.n_pub_in + file.header.n_pub_out) as usize;
     let num_variables = file.header.n_wires as usize;
     let num_aux = num_variables - num_inputs;
@@ -129,7 +178,7 @@ pub fn load_r1cs_from_bin<G1: CurveGroup, R: Read + Seek>(reader: R) -> (R1CS<Fr
     )
 }
 
-pub(crate) fn read_field<R: Read>(mut reader: R) -> Result<Fr> {
+pub(crate) fn read_field<Fr: PrimeField, R: Read>(mut reader: R) -> Result<Fr> {
     Fr::deserialize_compressed(&mut reader).map_err(|e| Error::new(ErrorKind::InvalidData, e))
 }
 
@@ -156,7 +205,7 @@ fn read_header<R: Read>(mut reader: R, size: u64) -> Result<Header> {
     })
 }
 
-fn read_constraint_vec<R: Read>(mut reader: R) -> Result<Vec<(usize, Fr)>> {
+fn read_constraint_vec<Fr: PrimeField, R: Read>(mut reader: R) -> Result<Vec<(usize, Fr)>> {
     let n_vec = reader.read_u32::<LittleEndian>()? as usize;
     let mut vec = Vec::with_capacity(n_vec);
     for _ in 0..n_vec {
@@ -168,7 +217,7 @@ fn read_constraint_vec<R: Read>(mut reader: R) -> Result<Vec<(usize, Fr)>> {
     Ok(vec)
 }
 
-fn read_constraints<R: Read>(
+fn read_constraints<Fr: PrimeField, R: Read>(
     mut reader: R,
     header: &Header,
 ) -> Result<Vec<Constraint<Fr>>> {
@@ -204,7 +253,7 @@ fn read_map<R: Read>(mut reader: R, size: u64, header: &Header) -> Result<Vec<u6
     Ok(vec)
 }
 
-pub fn from_reader<G1: CurveGroup, R: Read + Seek>(mut reader: R) -> Result<R1CSFile<Fr>> {
+pub fn from_reader<Fr: PrimeField, R: Read + Seek>(mut reader: R) -> Result<R1CSFile<Fr>> {
     let mut magic = [0u8; 4];
     reader.read_exact(&mut magic)?;
     if magic != [0x72, 0x31, 0x63, 0x73] {
@@ -227,7 +276,7 @@ pub fn from_reader<G1: CurveGroup, R: Read + Seek>(mut reader: R) -> Result<R1CS
     for _ in 0..num_sections {
         let section_type = reader.read_u32::<LittleEndian>()?;
         let section_size = reader.read_u64::<LittleEndian>()?;
-        let offset = reader.seek(SeekFrom::Current(0))?;
+        let offset = reader.stream_position()?;
         section_offsets.insert(section_type, offset);
         section_sizes.insert(section_type, section_size);
         reader.seek(SeekFrom::Current(section_size as i64))?;
@@ -239,15 +288,7 @@ pub fn from_reader<G1: CurveGroup, R: Read + Seek>(mut reader: R) -> Result<R1CS
 
     reader.seek(SeekFrom::Start(*section_offsets.get(&header_type).unwrap()))?;
     let header = read_header(&mut reader, *section_sizes.get(&header_type).unwrap())?;
-    if header.field_size != 32 {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            "This parser only supports 32-byte fields",
-        ));
-    }
-    // if header.prime_size != hex!("010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430") {
-    //     return Err(Error::new(ErrorKind::InvalidData, "This parser only supports bn256"));
-    // }
+    check_prime::<Fr>(&header.prime_size)?;
 
     reader.seek(SeekFrom::Start(
         *section_offsets.get(&constraint_type).unwrap(),
@@ -271,7 +312,7 @@ pub fn from_reader<G1: CurveGroup, R: Read + Seek>(mut reader: R) -> Result<R1CS
     })
 }
 
-pub fn load_witness_from_bin_reader<R: Read>(mut reader: R) -> Result<Vec<Fr>> {
+pub fn load_witness_from_bin_reader<Fr: PrimeField, R: Read>(mut reader: R) -> Result<Vec<Fr>> {
     let mut wtns_header = [0u8; 4];
     reader.read_exact(&mut wtns_header)?;
     if wtns_header != [119, 116, 110, 115] {
@@ -302,9 +343,7 @@ pub fn load_witness_from_bin_reader<R: Read>(mut reader: R) -> Result<Vec<Fr>> {
     }
     let mut prime = vec![0u8; field_size as usize];
     reader.read_exact(&mut prime)?;
-    // if prime != hex!("010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430") {
-    //     bail!("invalid curve prime {:?}", prime);
-    // }
+    check_prime::<Fr>(&prime)?;
     let witness_len = reader.read_u32::<LittleEndian>()?;
     // println!("witness len {}", witness_len);
     let sec_type = reader.read_u32::<LittleEndian>()?;
@@ -320,4 +359,146 @@ pub fn load_witness_from_bin_reader<R: Read>(mut reader: R) -> Result<Vec<Fr>> {
         result.push(read_field(&mut reader)?);
     }
     Ok(result)
-}
\ No newline at end of file
+}
+
+/// Checks that the little-endian prime stored in a circom file is the modulus of `Fr`.
+fn check_prime<Fr: PrimeField>(prime: &[u8]) -> Result<()> {
+    if prime != Fr::MODULUS.to_bytes_le().as_slice() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The file's prime does not match the modulus of the requested field",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::One;
+    use byteorder::WriteBytesExt;
+    use std::io::Cursor;
+
+    fn write_field<Fr: PrimeField>(buf: &mut Vec<u8>, value: &Fr) {
+        value.serialize_compressed(buf).unwrap();
+    }
+
+    fn write_section(buf: &mut Vec<u8>, section_type: u32, content: &[u8]) {
+        buf.write_u32::<LittleEndian>(section_type).unwrap();
+        buf.write_u64::<LittleEndian>(content.len() as u64).unwrap();
+        buf.extend_from_slice(content);
+    }
+
+    // wires: [1, x, x^2]; a single constraint x * x = x^2 with x^2 as the public output
+    fn square_r1cs_file<Fr: PrimeField>() -> Vec<u8> {
+        let mut header = vec![];
+        header.write_u32::<LittleEndian>(32).unwrap();
+        header.extend_from_slice(&Fr::MODULUS.to_bytes_le());
+        header.write_u32::<LittleEndian>(3).unwrap(); // n_wires
+        header.write_u32::<LittleEndian>(1).unwrap(); // n_pub_out
+        header.write_u32::<LittleEndian>(0).unwrap(); // n_pub_in
+        header.write_u32::<LittleEndian>(1).unwrap(); // n_prv_in
+        header.write_u64::<LittleEndian>(3).unwrap(); // n_labels
+        header.write_u32::<LittleEndian>(1).unwrap(); // n_constraints
+
+        let mut constraints = vec![];
+        for wire in [1u32, 1, 2] {
+            constraints.write_u32::<LittleEndian>(1).unwrap();
+            constraints.write_u32::<LittleEndian>(wire).unwrap();
+            write_field(&mut constraints, &Fr::one());
+        }
+
+        let mut map = vec![];
+        for label in 0..3u64 {
+            map.write_u64::<LittleEndian>(label).unwrap();
+        }
+
+        let mut file = b"r1cs".to_vec();
+        file.write_u32::<LittleEndian>(1).unwrap();
+        file.write_u32::<LittleEndian>(3).unwrap();
+        write_section(&mut file, 1, &header);
+        write_section(&mut file, 2, &constraints);
+        write_section(&mut file, 3, &map);
+        file
+    }
+
+    fn witness_file<Fr: PrimeField>(witness: &[Fr]) -> Vec<u8> {
+        let mut header = vec![];
+        header.write_u32::<LittleEndian>(32).unwrap();
+        header.extend_from_slice(&Fr::MODULUS.to_bytes_le());
+        header.write_u32::<LittleEndian>(witness.len() as u32).unwrap();
+
+        let mut values = vec![];
+        for w in witness {
+            write_field(&mut values, w);
+        }
+
+        let mut file = b"wtns".to_vec();
+        file.write_u32::<LittleEndian>(2).unwrap();
+        file.write_u32::<LittleEndian>(2).unwrap();
+        write_section(&mut file, 1, &header);
+        write_section(&mut file, 2, &values);
+        file
+    }
+
+    #[test]
+    fn check_circom_loader() {
+        check_circom_loader_helper::<ark_bn254::Fr>();
+        check_circom_loader_helper::<ark_secq256k1::Fr>();
+        check_circom_loader_helper::<ark_secp256k1::Fr>();
+        check_circom_loader_helper::<ark_pallas::Fr>();
+        check_circom_loader_helper::<ark_vesta::Fr>();
+    }
+
+    fn check_circom_loader_helper<Fr: PrimeField>() {
+        let (r1cs, wire_mapping) = load_r1cs_from_bin::<Fr, _>(Cursor::new(square_r1cs_file::<Fr>()));
+        assert_eq!(r1cs.num_variables, 3);
+        assert_eq!(r1cs.num_inputs, 2);
+        assert_eq!(wire_mapping, vec![0, 1, 2]);
+
+        let x = Fr::from(7u64);
+        let witness = load_witness_from_bin_reader::<Fr, _>(Cursor::new(witness_file(&[
+            Fr::one(),
+            x,
+            x * x,
+        ])))
+        .unwrap();
+        assert_eq!(witness, vec![Fr::one(), x, x * x]);
+
+        let num_pub_inputs = 1;
+        let inst = convert_to_spartan_r1cs(&r1cs, num_pub_inputs);
+        let vars = crate::VarsAssignment::new(&witness).unwrap();
+        let inputs = crate::InputsAssignment::new(&witness[1..2]).unwrap();
+        assert!(inst.is_sat(&vars, &inputs).unwrap());
+
+        let bad_witness = [Fr::one(), x, x];
+        let vars = crate::VarsAssignment::new(&bad_witness).unwrap();
+        assert!(!inst.is_sat(&vars, &inputs).unwrap());
+    }
+
+    #[test]
+    fn check_circom_loader_with_public_signals() {
+        let file = square_r1cs_file::<ark_bn254::Fr>();
+        let (r1cs, _) = load_r1cs_from_bin::<ark_bn254::Fr, _>(Cursor::new(file));
+        let inst = convert_to_spartan_r1cs_with_public_signals(&r1cs);
+        assert_eq!(inst.inst.get_num_inputs(), 1);
+
+        // wire 1 is the public signal, so it must match the public input
+        let x = ark_bn254::Fr::from(7u64);
+        let (vars, inputs) = split_witness(&[ark_bn254::Fr::one(), x, x * x], 1);
+        assert_eq!(inputs.assignment, vec![x]);
+        assert!(inst.is_sat(&vars, &inputs).unwrap());
+
+        let bad_inputs = InputsAssignment::new(&[x + ark_bn254::Fr::one()]).unwrap();
+        assert!(!inst.is_sat(&vars, &bad_inputs).unwrap());
+    }
+
+    #[test]
+    fn check_circom_loader_rejects_wrong_field() {
+        let file = square_r1cs_file::<ark_bn254::Fr>();
+        assert!(from_reader::<ark_secq256k1::Fr, _>(Cursor::new(file)).is_err());
+
+        let file = witness_file(&[ark_bn254::Fr::one()]);
+        assert!(load_witness_from_bin_reader::<ark_pallas::Fr, _>(Cursor::new(file)).is_err());
+    }
+}
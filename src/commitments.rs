@@ -1,18 +1,43 @@
+use crate::hash_to_curve::HashToCurveGroup;
+use ark_ec::scalar_mul::fixed_base::FixedBase;
 use ark_ec::CurveGroup;
 use ark_ec::VariableBaseMSM;
+use ark_ff::PrimeField;
 use ark_std::rand::SeedableRng;
+use core::fmt;
+use core::ops::Range;
 use digest::{ExtendableOutput, Input};
 use rand_chacha::ChaCha20Rng;
 use sha3::Shake256;
 use std::io::Read;
+use std::sync::Arc;
 
-#[derive(Debug)]
-pub struct MultiCommitGens<G> {
-  pub n: usize,
-  pub G: Vec<G>,
-  pub h: G,
+// the prefix of the domain separation tag under which generators are hashed to the curve, which
+// ends with the identifier of the suite as RFC 9380 recommends
+const HASH_TO_CURVE_DST_PREFIX: &[u8] = b"LIBSPARTAN-V01-CS01-with-";
+
+// the window of the fixed-base tables: a multiplication takes one addition per window of the
+// scalar, and a table holds 2^FIXED_BASE_WINDOW points per window
+const FIXED_BASE_WINDOW: usize = 8;
+
+/// Pedersen generators `G` with blinding generator `h`. Their affine forms are cached when the
+/// generators are created, so the generators are read-only.
+#[derive(Clone, Debug)]
+pub struct MultiCommitGens<G: CurveGroup> {
+  n: usize,
+  G: Vec<G>,
+  h: G,
+  // `G` followed by `h` in affine form, normalized once rather than in every commitment
+  bases: Vec<G::Affine>,
+  // optional fixed-base tables for `G[0]` and `h`, shared between clones
+  table_G_0: Option<Arc<FixedBaseTable<G>>>,
+  table_h: Option<Arc<FixedBaseTable<G>>>,
 }
 
+// derives `n` generators and a blinding generator from a label, which is either
+// `MultiCommitGens::new` or `MultiCommitGens::new_hash_to_curve`
+pub(crate) type GensDerivation<G> = fn(usize, &[u8]) -> MultiCommitGens<G>;
+
 impl<G: CurveGroup> MultiCommitGens<G> {
   pub fn new(n: usize, label: &[u8]) -> Self {
     let mut shake = Shake256::default();
@@ -31,36 +56,129 @@ impl<G: CurveGroup> MultiCommitGens<G> {
       gens.push(G::rand(&mut rng));
     }
 
+    MultiCommitGens::from_generators(gens[..n].to_vec(), gens[n])
+  }
+
+  /// generators made of the given `G` and blinding generator `h`
+  pub fn from_generators(G: Vec<G>, h: G) -> Self {
+    let bases = G::normalize_batch(&[G.as_slice(), &[h]].concat());
     MultiCommitGens {
-      n,
-      G: gens[..n].to_vec(),
-      h: gens[n],
+      n: G.len(),
+      G,
+      h,
+      bases,
+      table_G_0: None,
+      table_h: None,
     }
   }
 
-  pub fn clone(&self) -> Self {
-    MultiCommitGens {
-      n: self.n,
-      h: self.h,
-      G: self.G.clone(),
+  /// Returns the number of generators, excluding the blinding generator
+  pub fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Returns the generators `G`
+  pub fn G(&self) -> &[G] {
+    &self.G
+  }
+
+  /// Returns the blinding generator `h`
+  pub fn h(&self) -> G {
+    self.h
+  }
+
+  /// precomputes fixed-base tables for `G[0]` and `h`, which make commitments to single values
+  /// several times faster at the cost of about a megabyte of memory per table
+  pub fn precompute_fixed_base_tables(&mut self) {
+    if self.n > 0 && self.table_G_0.is_none() {
+      self.table_G_0 = Some(Arc::new(FixedBaseTable::new(self.G[0])));
+    }
+    if self.table_h.is_none() {
+      self.table_h = Some(Arc::new(FixedBaseTable::new(self.h)));
     }
   }
 
   pub fn split_at(&self, mid: usize) -> (Self, Self) {
-    let (G1, G2) = self.G.split_at(mid);
+    (self.slice(0..mid), self.slice(mid..self.n))
+  }
 
-    (
-      MultiCommitGens {
-        n: G1.len(),
-        G: G1.to_vec(),
-        h: self.h,
-      },
-      MultiCommitGens {
-        n: G2.len(),
-        G: G2.to_vec(),
-        h: self.h,
+  // the generators `G[range]`, with the same blinding generator
+  pub(crate) fn slice(&self, range: Range<usize>) -> Self {
+    let start = range.start;
+    let G = self.G[range].to_vec();
+    let bases = [&self.bases[start..start + G.len()], &[self.bases[self.n]]].concat();
+    MultiCommitGens {
+      n: G.len(),
+      G,
+      h: self.h,
+      bases,
+      table_G_0: if start == 0 {
+        self.table_G_0.clone()
+      } else {
+        None
       },
-    )
+      table_h: self.table_h.clone(),
+    }
+  }
+
+  // computes `G[0] * scalar`
+  pub(crate) fn mul_G_0(&self, scalar: &G::ScalarField) -> G {
+    match &self.table_G_0 {
+      Some(table) => table.mul(scalar),
+      None => self.G[0] * scalar,
+    }
+  }
+
+  // computes `h * scalar`
+  pub(crate) fn mul_h(&self, scalar: &G::ScalarField) -> G {
+    match &self.table_h {
+      Some(table) => table.mul(scalar),
+      None => self.h * scalar,
+    }
+  }
+}
+
+impl<G: HashToCurveGroup> MultiCommitGens<G> {
+  /// Derives `n` generators and a blinding generator with the RFC 9380 suite of the group: the
+  /// `i`-th generator is `hash_to_curve(label || I2OSP(i, 8))` for `i < n` and the blinding
+  /// generator is the `n`-th one, under the domain separation tag
+  /// `"LIBSPARTAN-V01-CS01-with-" || G::SUITE_ID`
+  pub fn new_hash_to_curve(n: usize, label: &[u8]) -> Self {
+    let dst = [HASH_TO_CURVE_DST_PREFIX, G::SUITE_ID].concat();
+    let gens = (0..=n as u64)
+      .map(|i| G::hash_to_curve(&[label, &i.to_be_bytes()].concat(), &dst))
+      .collect::<Vec<G>>();
+
+    MultiCommitGens::from_generators(gens[..n].to_vec(), gens[n])
+  }
+}
+
+// the multiples of a base for each window of a scalar, with which a scalar multiplication
+// takes only additions
+struct FixedBaseTable<G: CurveGroup> {
+  outerc: usize,
+  table: Vec<Vec<G::Affine>>,
+}
+
+impl<G: CurveGroup> FixedBaseTable<G> {
+  fn new(base: G) -> Self {
+    let scalar_size = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    FixedBaseTable {
+      outerc: scalar_size.div_ceil(FIXED_BASE_WINDOW),
+      table: FixedBase::get_window_table(scalar_size, FIXED_BASE_WINDOW, base),
+    }
+  }
+
+  fn mul(&self, scalar: &G::ScalarField) -> G {
+    FixedBase::windowed_mul::<G>(self.outerc, FIXED_BASE_WINDOW, &self.table, scalar)
+  }
+}
+
+impl<G: CurveGroup> fmt::Debug for FixedBaseTable<G> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("FixedBaseTable")
+      .field("window", &FIXED_BASE_WINDOW)
+      .finish()
   }
 }
 
@@ -73,17 +191,85 @@ impl<G: CurveGroup> Commitments<G> for G::ScalarField {
   fn commit(&self, blind: &G::ScalarField, gens_n: &MultiCommitGens<G>) -> G {
     assert_eq!(gens_n.n, 1);
 
-    gens_n.G[0] * self + gens_n.h * blind
+    gens_n.mul_G_0(self) + gens_n.mul_h(blind)
   }
 
   fn batch_commit(inputs: &[Self], blind: &G::ScalarField, gens_n: &MultiCommitGens<G>) -> G {
     assert_eq!(gens_n.n, inputs.len());
 
-    let mut bases = CurveGroup::normalize_batch(gens_n.G.as_ref());
     let mut scalars = inputs.to_vec();
-    bases.push(gens_n.h.into_affine());
     scalars.push(*blind);
 
-    VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap()
+    VariableBaseMSM::msm(gens_n.bases.as_ref(), scalars.as_ref()).unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_std::{test_rng, UniformRand};
+
+  #[test]
+  fn check_fixed_base_tables() {
+    check_fixed_base_tables_helper::<G1Projective>()
+  }
+
+  fn check_fixed_base_tables_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let gens = MultiCommitGens::<G>::new(5, b"test-fixed-base");
+    let mut gens_tables = gens.clone();
+    gens_tables.precompute_fixed_base_tables();
+
+    // commitments to single values agree with and without the tables
+    let (gens_1, _) = gens.split_at(1);
+    let (gens_1_tables, gens_rest) = gens_tables.split_at(1);
+    assert!(gens_1_tables.table_G_0.is_some() && gens_rest.table_G_0.is_none());
+    for _ in 0..10 {
+      let v = G::ScalarField::rand(&mut prng);
+      let r = G::ScalarField::rand(&mut prng);
+      assert_eq!(v.commit(&r, &gens_1), gens_1.G[0] * v + gens_1.h * r);
+      assert_eq!(v.commit(&r, &gens_1_tables), v.commit(&r, &gens_1));
+    }
+
+    // the cached affine bases of a slice match its generators
+    let gens_slice = gens_tables.slice(1..4);
+    let inputs = (0..3)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let r = G::ScalarField::rand(&mut prng);
+    let expected = (0..3).fold(gens.h * r, |acc, i| acc + gens.G[i + 1] * inputs[i]);
+    assert_eq!(
+      Commitments::batch_commit(&inputs, &r, &gens_slice),
+      expected
+    );
+  }
+
+  #[test]
+  fn check_hash_to_curve_gens() {
+    check_hash_to_curve_gens_helper::<G1Projective>();
+    check_hash_to_curve_gens_helper::<ark_bn254::G1Projective>();
+  }
+
+  fn check_hash_to_curve_gens_helper<G: HashToCurveGroup>() {
+    let gens = MultiCommitGens::<G>::new_hash_to_curve(4, b"test-hash-to-curve");
+    let dst = [b"LIBSPARTAN-V01-CS01-with-", G::SUITE_ID].concat();
+
+    // each generator is the hash of the label and its index, the blinding generator coming last
+    let hash = |i: u64| {
+      G::hash_to_curve(
+        &[&b"test-hash-to-curve"[..], &i.to_be_bytes()].concat(),
+        &dst,
+      )
+    };
+    for i in 0..4 {
+      assert_eq!(gens.G[i], hash(i as u64));
+    }
+    assert_eq!(gens.h, hash(4));
+
+    // a prefix of more generators has the same generators, but not the same blinding generator
+    let more_gens = MultiCommitGens::<G>::new_hash_to_curve(8, b"test-hash-to-curve");
+    assert_eq!(gens.G, more_gens.G[..4]);
+    assert_ne!(gens.h, more_gens.h);
   }
 }
@@ -1,15 +1,16 @@
 #![allow(clippy::too_many_arguments)]
-use super::commitments::{Commitments, MultiCommitGens};
+use super::commitments::{Commitments, GensDerivation, MultiCommitGens};
 use super::errors::ProofVerifyError;
 use super::math::Math;
 use super::nizk::{DotProductProofGens, DotProductProofLog};
 use super::random::RandomTape;
+use super::sumcheck::SumcheckInstanceProof;
 use super::transcript::{AppendToTranscript, ProofTranscript};
 use ark_ec::CurveGroup;
 use ark_ec::VariableBaseMSM;
 use ark_ff::PrimeField;
 use ark_serialize::*;
-use ark_std::Zero;
+use ark_std::{One, Zero};
 use core::ops::Index;
 use merlin::Transcript;
 
@@ -23,15 +24,23 @@ pub struct DensePolynomial<F> {
   Z: Vec<F>, // evaluations of the polynomial in all the 2^num_vars Boolean inputs
 }
 
-pub struct PolyCommitmentGens<G> {
+pub struct PolyCommitmentGens<G: CurveGroup> {
   pub gens: DotProductProofGens<G>,
 }
 
 impl<G: CurveGroup> PolyCommitmentGens<G> {
   // the number of variables in the multilinear polynomial
   pub fn new(num_vars: usize, label: &'static [u8]) -> Self {
+    PolyCommitmentGens::new_with_derivation(num_vars, label, MultiCommitGens::new)
+  }
+
+  pub fn new_with_derivation(
+    num_vars: usize,
+    label: &'static [u8],
+    derive: GensDerivation<G>,
+  ) -> Self {
     let (_left, right) = EqPolynomial::<G::ScalarField>::compute_factored_lens(num_vars);
-    let gens = DotProductProofGens::new(right.pow2(), label);
+    let gens = DotProductProofGens::new_with_derivation(right.pow2(), label, derive);
     PolyCommitmentGens { gens }
   }
 }
@@ -40,14 +49,69 @@ pub struct PolyCommitmentBlinds<F> {
   blinds: Vec<F>,
 }
 
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+impl<F: PrimeField> PolyCommitmentBlinds<F> {
+  // the blinds of commitments to consecutive rows of a polynomial
+  pub fn concat(blinds: &[PolyCommitmentBlinds<F>]) -> Self {
+    PolyCommitmentBlinds {
+      blinds: blinds.iter().flat_map(|b| b.blinds.clone()).collect(),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct PolyCommitment<G: CurveGroup> {
   C: Vec<G>,
 }
 
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
-pub struct ConstPolyCommitment<G: CurveGroup> {
-  C: G,
+impl<G: CurveGroup> PolyCommitment<G> {
+  pub fn get_num_rows(&self) -> usize {
+    self.C.len()
+  }
+
+  // commits to `Z`, whose length is a multiple of the row length of `gens`, one row at a time;
+  // this produces the commitments to the rows of a polynomial that start with `Z`
+  pub fn commit_rows(
+    Z: &[G::ScalarField],
+    gens: &PolyCommitmentGens<G>,
+    random_tape: &mut RandomTape<G>,
+  ) -> (PolyCommitment<G>, PolyCommitmentBlinds<G::ScalarField>) {
+    let R_size = gens.gens.gens_n.n();
+    assert_eq!(Z.len() % R_size, 0);
+    let blinds = random_tape.random_vector(b"poly_blinds", Z.len() / R_size);
+    let C = Z
+      .chunks(R_size)
+      .zip(blinds.iter())
+      .map(|(row, blind)| Commitments::batch_commit(row, blind, &gens.gens.gens_n))
+      .collect();
+    (PolyCommitment { C }, PolyCommitmentBlinds { blinds })
+  }
+
+  // returns the commitments to the rows start..end
+  pub fn get_rows(&self, start: usize, end: usize) -> PolyCommitment<G> {
+    PolyCommitment {
+      C: self.C[start..end].to_vec(),
+    }
+  }
+
+  // the commitment to a polynomial from the commitments to consecutive rows of it
+  pub fn concat(comms: &[PolyCommitment<G>]) -> PolyCommitment<G> {
+    PolyCommitment {
+      C: comms.iter().flat_map(|comm| comm.C.clone()).collect(),
+    }
+  }
+
+  // given commitments to polynomials P and Q (with the same number of variables),
+  // returns the commitment to P + r * Q, whose blinds are blinds_P + r * blinds_Q
+  pub fn combine(&self, other: &PolyCommitment<G>, r: &G::ScalarField) -> PolyCommitment<G> {
+    assert_eq!(self.C.len(), other.C.len());
+    let C = self
+      .C
+      .iter()
+      .zip(other.C.iter())
+      .map(|(C_P, C_Q)| *C_P + *C_Q * r)
+      .collect();
+    PolyCommitment { C }
+  }
 }
 
 pub struct EqPolynomial<F> {
@@ -120,7 +184,7 @@ impl IdentityPolynomial {
 impl<F: PrimeField> DensePolynomial<F> {
   pub fn new(Z: Vec<F>) -> Self {
     DensePolynomial {
-      num_vars: Z.len().log_2() as usize,
+      num_vars: Z.len().log_2(),
       len: Z.len(),
       Z,
     }
@@ -384,6 +448,20 @@ impl<G: CurveGroup> PolyEvalProof<G> {
     r: &[G::ScalarField], // point at which the polynomial is evaluated
     C_Zr: &G,             // commitment to \widetilde{Z}(r)
     comm: &PolyCommitment<G>,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_combined(gens, transcript, r, C_Zr, &[comm], &[G::ScalarField::one()])
+  }
+
+  // verifies the evaluation of the linear combination of the committed polynomials with `coeffs`,
+  // folding the combination of the commitments into the MSM with L
+  fn verify_combined(
+    &self,
+    gens: &PolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    r: &[G::ScalarField],
+    C_Zr: &G,
+    comms: &[&PolyCommitment<G>],
+    coeffs: &[G::ScalarField],
   ) -> Result<(), ProofVerifyError> {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
@@ -393,11 +471,22 @@ impl<G: CurveGroup> PolyEvalProof<G> {
     // compute L and R
     let eq = EqPolynomial::new(r.to_vec());
     let (L, R) = eq.compute_factored_evals();
+    if comms.iter().any(|comm| comm.C.len() != L.len()) {
+      return Err(ProofVerifyError::InternalError);
+    }
 
     // compute a weighted sum of commitments and L
-    let C_affine = G::normalize_batch(&comm.C);
+    let C = comms
+      .iter()
+      .flat_map(|comm| comm.C.iter().cloned())
+      .collect::<Vec<G>>();
+    let scalars = coeffs
+      .iter()
+      .flat_map(|coeff| L.iter().map(move |L_i| *coeff * L_i))
+      .collect::<Vec<G::ScalarField>>();
+    let C_affine = G::normalize_batch(&C);
 
-    let C_LZ = VariableBaseMSM::msm(C_affine.as_ref(), L.as_ref()).unwrap();
+    let C_LZ = VariableBaseMSM::msm(C_affine.as_ref(), scalars.as_ref()).unwrap();
 
     self
       .proof
@@ -419,11 +508,277 @@ impl<G: CurveGroup> PolyEvalProof<G> {
   }
 }
 
+/// A proof of the evaluations of several polynomials, committed under the same generators, at one
+/// or more points. The claims are combined with random coefficients; claims at distinct points are
+/// first reduced to claims at a single point with a sum-check, so that a single `PolyEvalProof`
+/// opens all polynomials. As with `PolyEvalProof::verify_plain`, the evaluations are public, and
+/// the sum-check is not zero-knowledge. It serves callers of the `pcs` module; the openings in the
+/// sparse polynomial evaluation proofs are each under their own generators, of different sizes,
+/// so they cannot be combined with it and keep one `PolyEvalProof` per commitment.
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchedPolyEvalProof<G: CurveGroup> {
+  sc_proof: Option<SumcheckInstanceProof<G::ScalarField>>,
+  evals_at_r: Vec<G::ScalarField>, // evaluation at the reduced point of the combination per point
+  proof: PolyEvalProof<G>,
+}
+
+// groups the indices of the claims by their evaluation point, in the order of first occurrence
+fn group_by_point<F: PrimeField>(points: &[Vec<F>]) -> Vec<Vec<usize>> {
+  let mut groups: Vec<Vec<usize>> = Vec::new();
+  for (i, point) in points.iter().enumerate() {
+    match groups.iter_mut().find(|group| points[group[0]] == *point) {
+      Some(group) => group.push(i),
+      None => groups.push(vec![i]),
+    }
+  }
+  groups
+}
+
+fn powers<F: PrimeField>(s: &F, n: usize) -> Vec<F> {
+  let mut powers = Vec::with_capacity(n);
+  let mut power = F::one();
+  for _i in 0..n {
+    powers.push(power);
+    power *= s;
+  }
+  powers
+}
+
+impl<F: PrimeField> DensePolynomial<F> {
+  // returns \sum_i coeffs[i] * polys[i]
+  fn linear_combination(polys: &[&DensePolynomial<F>], coeffs: &[F]) -> DensePolynomial<F> {
+    assert_eq!(polys.len(), coeffs.len());
+    let mut Z = vec![F::zero(); polys[0].len()];
+    for (poly, coeff) in polys.iter().zip(coeffs.iter()) {
+      assert_eq!(poly.len(), Z.len());
+      for (z, z_poly) in Z.iter_mut().zip(poly.Z.iter()) {
+        *z += *coeff * z_poly;
+      }
+    }
+    DensePolynomial::new(Z)
+  }
+}
+
+impl<G: CurveGroup> PolyEvalProof<G> {
+  fn protocol_name_batched() -> &'static [u8] {
+    b"batched polynomial evaluation proof"
+  }
+
+  // draws the challenge rho that combines the claims, after the whole statement is in the
+  // transcript: were a commitment or a point absorbed after rho, the prover could choose it
+  // depending on rho and cancel a false claim in the combination
+  fn challenge_rho(
+    transcript: &mut Transcript,
+    comms: &[&PolyCommitment<G>],
+    points: &[Vec<G::ScalarField>],
+    evals: &[G::ScalarField],
+  ) -> G::ScalarField {
+    for comm in comms {
+      comm.append_to_transcript(b"comm", transcript);
+    }
+    for point in points {
+      <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"point", point);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"evals", evals);
+    <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_rho")
+  }
+
+  /// Proves that `polys[i]`, committed to in `comms[i]`, evaluates to `evals[i]` at `points[i]`
+  /// for every `i`
+  pub fn prove_batched(
+    polys: &[&DensePolynomial<G::ScalarField>],
+    blinds_opt: Option<&[&PolyCommitmentBlinds<G::ScalarField>]>,
+    comms: &[&PolyCommitment<G>],
+    points: &[Vec<G::ScalarField>],
+    evals: &[G::ScalarField],
+    gens: &PolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> BatchedPolyEvalProof<G> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PolyEvalProof::<G>::protocol_name_batched(),
+    );
+
+    // assert vectors are of the right size
+    assert!(!polys.is_empty());
+    assert_eq!(polys.len(), comms.len());
+    assert_eq!(polys.len(), points.len());
+    assert_eq!(polys.len(), evals.len());
+    let num_vars = points[0].len();
+    for (poly, point) in polys.iter().zip(points.iter()) {
+      assert_eq!(poly.get_num_vars(), num_vars);
+      assert_eq!(point.len(), num_vars);
+    }
+
+    // combine the claims at each point with powers of rho
+    let rho = PolyEvalProof::challenge_rho(transcript, comms, points, evals);
+    let mut coeffs = powers(&rho, polys.len());
+    let claim: G::ScalarField = coeffs.iter().zip(evals.iter()).map(|(c, e)| *c * e).sum();
+
+    let groups = group_by_point(points);
+    let (sc_proof, evals_at_r, r, eval) = if groups.len() == 1 {
+      (None, Vec::new(), points[0].clone(), claim)
+    } else {
+      // reduce the claims to a single point with a sum-check over
+      // \sum_x \sum_j eq(r_j, x) * P_j(x), where P_j combines the polynomials evaluated at r_j
+      let mut sc_polys = Vec::with_capacity(2 * groups.len());
+      for group in groups.iter() {
+        let polys_group = group.iter().map(|&i| polys[i]).collect::<Vec<_>>();
+        let coeffs_group = group.iter().map(|&i| coeffs[i]).collect::<Vec<_>>();
+        sc_polys.push(DensePolynomial::new(
+          EqPolynomial::new(points[group[0]].clone()).evals(),
+        ));
+        sc_polys.push(DensePolynomial::linear_combination(
+          &polys_group,
+          &coeffs_group,
+        ));
+      }
+      let comb_func = |evals: &[G::ScalarField]| -> G::ScalarField {
+        evals.chunks(2).map(|pair| pair[0] * pair[1]).sum()
+      };
+      let (sc_proof, r, claims) = SumcheckInstanceProof::prove_arbitrary::<_, G>(
+        &claim,
+        num_vars,
+        &mut sc_polys,
+        comb_func,
+        2,
+        transcript,
+      );
+      let evals_at_r = claims.into_iter().skip(1).step_by(2).collect::<Vec<_>>();
+
+      // combine the claims at the reduced point with powers of beta
+      <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"evals_at_r", &evals_at_r);
+      let beta =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_beta");
+      let beta_powers = powers(&beta, groups.len());
+      for (group, beta_power) in groups.iter().zip(beta_powers.iter()) {
+        for &i in group {
+          coeffs[i] *= beta_power;
+        }
+      }
+      let eval = beta_powers
+        .iter()
+        .zip(evals_at_r.iter())
+        .map(|(b, e)| *b * e)
+        .sum();
+      (Some(sc_proof), evals_at_r, r, eval)
+    };
+
+    // open the combined polynomial at r
+    let poly = DensePolynomial::linear_combination(polys, &coeffs);
+    let blinds = blinds_opt.map(|blinds| {
+      assert_eq!(blinds.len(), polys.len());
+      let mut combined = vec![G::ScalarField::zero(); blinds[0].blinds.len()];
+      for (blinds_poly, coeff) in blinds.iter().zip(coeffs.iter()) {
+        assert_eq!(blinds_poly.blinds.len(), combined.len());
+        for (b, b_poly) in combined.iter_mut().zip(blinds_poly.blinds.iter()) {
+          *b += *coeff * b_poly;
+        }
+      }
+      PolyCommitmentBlinds { blinds: combined }
+    });
+    debug_assert_eq!(poly.evaluate::<G>(&r), eval);
+
+    let (proof, _C_eval) = PolyEvalProof::prove(
+      &poly,
+      blinds.as_ref(),
+      &r,
+      &eval,
+      None,
+      gens,
+      transcript,
+      random_tape,
+    );
+
+    BatchedPolyEvalProof {
+      sc_proof,
+      evals_at_r,
+      proof,
+    }
+  }
+
+  /// Verifies a `BatchedPolyEvalProof` that the polynomials committed to in `comms` evaluate to
+  /// `evals` at `points`
+  pub fn verify_batched(
+    proof: &BatchedPolyEvalProof<G>,
+    gens: &PolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    points: &[Vec<G::ScalarField>],
+    evals: &[G::ScalarField],
+    comms: &[&PolyCommitment<G>],
+  ) -> Result<(), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PolyEvalProof::<G>::protocol_name_batched(),
+    );
+
+    if comms.is_empty() || comms.len() != points.len() || comms.len() != evals.len() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let num_vars = points[0].len();
+    if points.iter().any(|point| point.len() != num_vars) {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    let rho = PolyEvalProof::challenge_rho(transcript, comms, points, evals);
+    let mut coeffs = powers(&rho, comms.len());
+    let claim: G::ScalarField = coeffs.iter().zip(evals.iter()).map(|(c, e)| *c * e).sum();
+
+    let groups = group_by_point(points);
+    let (r, eval) = match &proof.sc_proof {
+      None if groups.len() == 1 && proof.evals_at_r.is_empty() => (points[0].clone(), claim),
+      Some(sc_proof) if groups.len() > 1 && proof.evals_at_r.len() == groups.len() => {
+        let (claim_final, r) = sc_proof.verify::<G>(claim, num_vars, 2, transcript)?;
+
+        // check the final claim of the sum-check against the claimed evaluations at r
+        let expected: G::ScalarField = groups
+          .iter()
+          .zip(proof.evals_at_r.iter())
+          .map(|(group, eval)| EqPolynomial::new(points[group[0]].clone()).evaluate(&r) * eval)
+          .sum();
+        if claim_final != expected {
+          return Err(ProofVerifyError::InternalError);
+        }
+
+        <Transcript as ProofTranscript<G>>::append_scalars(
+          transcript,
+          b"evals_at_r",
+          &proof.evals_at_r,
+        );
+        let beta =
+          <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_beta");
+        let beta_powers = powers(&beta, groups.len());
+        for (group, beta_power) in groups.iter().zip(beta_powers.iter()) {
+          for &i in group {
+            coeffs[i] *= beta_power;
+          }
+        }
+        let eval = beta_powers
+          .iter()
+          .zip(proof.evals_at_r.iter())
+          .map(|(b, e)| *b * e)
+          .sum();
+        (r, eval)
+      }
+      _ => return Err(ProofVerifyError::InternalError),
+    };
+
+    // compute a commitment to eval with a blind of zero
+    let C_eval = eval.commit(&G::ScalarField::zero(), &gens.gens.gens_1);
+
+    proof
+      .proof
+      .verify_combined(gens, transcript, &r, &C_eval, comms, &coeffs)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use ark_bls12_381::Fr;
   use ark_bls12_381::G1Projective;
+  use ark_ff::Field;
   use ark_std::test_rng;
   use ark_std::One;
   use ark_std::UniformRand;
@@ -479,7 +834,7 @@ mod tests {
     let mut R: Vec<F> = Vec::new();
 
     let ell = r.len();
-    assert!(ell % 2 == 0); // ensure ell is even
+    assert!(ell.is_multiple_of(2)); // ensure ell is even
     let n = ell.pow2();
     let m = n.square_root();
 
@@ -640,4 +995,175 @@ mod tests {
       .verify(&gens, &mut verifier_transcript, &r, &C_Zr, &poly_commitment)
       .is_ok());
   }
+  #[test]
+  fn check_batched_polynomial_evaluation() {
+    check_batched_polynomial_evaluation_helper::<G1Projective>()
+  }
+
+  fn check_batched_polynomial_evaluation_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let num_vars = 5;
+    let gens = PolyCommitmentGens::<G>::new(num_vars, b"test-batched");
+    let polys = (0..4)
+      .map(|_| {
+        DensePolynomial::new(
+          (0..num_vars.pow2())
+            .map(|_| G::ScalarField::rand(&mut prng))
+            .collect(),
+        )
+      })
+      .collect::<Vec<_>>();
+    let mut random_tape = RandomTape::new(b"proof");
+    let (comms, blinds): (Vec<_>, Vec<_>) = polys
+      .iter()
+      .map(|poly| poly.commit(&gens, Some(&mut random_tape)))
+      .unzip();
+    let polys = polys.iter().collect::<Vec<_>>();
+    let comms = comms.iter().collect::<Vec<_>>();
+    let blinds = blinds.iter().collect::<Vec<_>>();
+
+    let r = (0..num_vars)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let s = (0..num_vars)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+
+    // all polynomials at the same point, and at two distinct points
+    for points in [
+      vec![r.clone(), r.clone(), r.clone(), r.clone()],
+      vec![r.clone(), s.clone(), r.clone(), s.clone()],
+    ] {
+      let evals = polys
+        .iter()
+        .zip(points.iter())
+        .map(|(poly, point)| poly.evaluate::<G>(point))
+        .collect::<Vec<_>>();
+
+      let mut prover_transcript = Transcript::new(b"example");
+      let proof = PolyEvalProof::prove_batched(
+        &polys,
+        Some(&blinds),
+        &comms,
+        &points,
+        &evals,
+        &gens,
+        &mut prover_transcript,
+        &mut random_tape,
+      );
+
+      let mut verifier_transcript = Transcript::new(b"example");
+      assert!(PolyEvalProof::verify_batched(
+        &proof,
+        &gens,
+        &mut verifier_transcript,
+        &points,
+        &evals,
+        &comms
+      )
+      .is_ok());
+
+      // a wrong evaluation is rejected
+      let mut bad_evals = evals.clone();
+      bad_evals[1] += G::ScalarField::one();
+      let mut verifier_transcript = Transcript::new(b"example");
+      assert!(PolyEvalProof::verify_batched(
+        &proof,
+        &gens,
+        &mut verifier_transcript,
+        &points,
+        &bad_evals,
+        &comms
+      )
+      .is_err());
+    }
+  }
+
+  #[test]
+  fn check_batched_adaptive_commitment() {
+    check_batched_adaptive_commitment_helper::<G1Projective>()
+  }
+
+  // a prover that chooses a commitment after learning rho, to cancel a false evaluation of another
+  // polynomial in the combination of the claims
+  fn check_batched_adaptive_commitment_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let num_vars = 4;
+    let gens = PolyCommitmentGens::<G>::new(num_vars, b"test-batched");
+    let polys = (0..2)
+      .map(|_| {
+        DensePolynomial::new(
+          (0..num_vars.pow2())
+            .map(|_| G::ScalarField::rand(&mut prng))
+            .collect(),
+        )
+      })
+      .collect::<Vec<_>>();
+    let r = (0..num_vars)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let points = vec![r.clone(), r.clone()];
+    // the evaluation of the first polynomial is off by one
+    let one = G::ScalarField::one();
+    let evals = vec![polys[0].evaluate::<G>(&r) + one, polys[1].evaluate::<G>(&r)];
+
+    // rho, were it drawn from the evaluations alone
+    let mut prover_transcript = Transcript::new(b"example");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      &mut prover_transcript,
+      PolyEvalProof::<G>::protocol_name_batched(),
+    );
+    <Transcript as ProofTranscript<G>>::append_scalars(&mut prover_transcript, b"evals", &evals);
+    let rho = <Transcript as ProofTranscript<G>>::challenge_scalar(
+      &mut prover_transcript,
+      b"challenge_rho",
+    );
+
+    // the second commitment also holds delta / rho for the constant delta = 1, which the
+    // combination P_0 + rho * P_1 turns into delta, making up for the false evaluation
+    let delta = DensePolynomial::new(vec![one; num_vars.pow2()]);
+    let rho_inv = rho.inverse().unwrap();
+    let (comm_0, _) = polys[0].commit(&gens, None);
+    let (comm_1, _) = polys[1].commit(&gens, None);
+    let (comm_delta, _) = delta.commit(&gens, None);
+    let comm_1 = PolyCommitment {
+      C: comm_1
+        .C
+        .iter()
+        .zip(comm_delta.C.iter())
+        .map(|(c, d)| *c + *d * rho_inv)
+        .collect(),
+    };
+
+    let combined =
+      DensePolynomial::linear_combination(&[&polys[0], &polys[1], &delta], &[one, rho, one]);
+    let eval = evals[0] + rho * evals[1];
+    let mut random_tape = RandomTape::new(b"proof");
+    let (proof, _) = PolyEvalProof::prove(
+      &combined,
+      None,
+      &r,
+      &eval,
+      None,
+      &gens,
+      &mut prover_transcript,
+      &mut random_tape,
+    );
+    let proof = BatchedPolyEvalProof {
+      sc_proof: None,
+      evals_at_r: Vec::new(),
+      proof,
+    };
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(PolyEvalProof::verify_batched(
+      &proof,
+      &gens,
+      &mut verifier_transcript,
+      &points,
+      &evals,
+      &[&comm_0, &comm_1]
+    )
+    .is_err());
+  }
 }
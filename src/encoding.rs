@@ -0,0 +1,370 @@
+//! serde support for proofs, commitments, instances and assignments (the `serde` feature).
+//!
+//! Proofs, commitments and instances are encoded as their canonical compressed encoding, which
+//! is a `0x`-prefixed hex string in human-readable formats such as JSON and a byte string in
+//! binary formats such as bincode. Assignments are encoded as a sequence of field elements, each
+//! a `0x`-prefixed big-endian hex string in human-readable formats; see `ASSIGNMENT_JSON_SCHEMA`
+//! for the values that are accepted when parsing them.
+use super::bulletproofs::{InnerProductProof, RangeProof};
+use super::errors::R1CSError;
+use super::grand_product::MultisetEqualityProof;
+use super::pcs::{BatchedEvalProof, Commitment, EvalProof, HidingEvalProof};
+use super::sigma::{
+  DotProductProof, EqualityProof, KnowledgeProof, OrKnowledgeProof, ProductProof,
+};
+use super::{
+  Assignment, CCSPlainNIZK, ComputationCommitment, Instance, PlainNIZK, PlainSNARK, RelaxedSNARK,
+  NIZK, SNARK,
+};
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::de::{self, SeqAccess, Unexpected, Visitor};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A JSON schema for the encoding of an `Assignment` that `Assignment::from_json` parses: an
+/// array of field elements, each a decimal string, a `0x`-prefixed big-endian hex string, or
+/// a non-negative integer, which must be smaller than the modulus of the field
+pub const ASSIGNMENT_JSON_SCHEMA: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "Spartan assignment",
+  "type": "array",
+  "items": {
+    "oneOf": [
+      { "type": "string", "pattern": "^(0|[1-9][0-9]*)$" },
+      { "type": "string", "pattern": "^0x[0-9a-fA-F]+$" },
+      { "type": "integer", "minimum": 0 }
+    ]
+  }
+}"#;
+
+impl<F: PrimeField> Assignment<F> {
+  /// Constructs a new `Assignment` from JSON that follows `ASSIGNMENT_JSON_SCHEMA`
+  pub fn from_json(json: &str) -> Result<Self, R1CSError> {
+    serde_json::from_str(json).map_err(R1CSError::InvalidJson)
+  }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+  let mut s = String::with_capacity(2 + 2 * bytes.len());
+  s.push_str("0x");
+  for b in bytes {
+    s.push_str(&format!("{:02x}", b));
+  }
+  s
+}
+
+// decodes hex digits, without a `0x` prefix
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+  if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+    return None;
+  }
+  (0..s.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+fn without_leading_zeros(bytes: &[u8]) -> &[u8] {
+  &bytes[bytes.iter().take_while(|b| **b == 0).count()..]
+}
+
+/// accepts a hex string, a byte string, or a sequence of bytes
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+  type Value = Vec<u8>;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("a hex string or a byte string")
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+    decode_hex(v.strip_prefix("0x").unwrap_or(v))
+      .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+  }
+
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+    Ok(v.to_vec())
+  }
+
+  fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+    Ok(v)
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+    while let Some(b) = seq.next_element()? {
+      bytes.push(b);
+    }
+    Ok(bytes)
+  }
+}
+
+fn serialize_canonical<T: CanonicalSerialize, S: Serializer>(
+  value: &T,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  let mut bytes = vec![];
+  value
+    .serialize_compressed(&mut bytes)
+    .map_err(S::Error::custom)?;
+  if serializer.is_human_readable() {
+    serializer.serialize_str(&encode_hex(&bytes))
+  } else {
+    serializer.serialize_bytes(&bytes)
+  }
+}
+
+fn deserialize_canonical<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<T, D::Error> {
+  let bytes = if deserializer.is_human_readable() {
+    deserializer.deserialize_str(BytesVisitor)?
+  } else {
+    deserializer.deserialize_bytes(BytesVisitor)?
+  };
+  T::deserialize_compressed(&bytes[..]).map_err(de::Error::custom)
+}
+
+macro_rules! impl_serde_canonical {
+  ($($name:ident),*) => {
+    $(
+      impl<G: CurveGroup> Serialize for $name<G> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          serialize_canonical(self, serializer)
+        }
+      }
+
+      impl<'de, G: CurveGroup> Deserialize<'de> for $name<G> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+          deserialize_canonical(deserializer)
+        }
+      }
+    )*
+  };
+}
+
+impl_serde_canonical!(
+  ComputationCommitment,
+  SNARK,
+  NIZK,
+  PlainSNARK,
+  RelaxedSNARK,
+  PlainNIZK,
+  CCSPlainNIZK,
+  Commitment,
+  EvalProof,
+  HidingEvalProof,
+  BatchedEvalProof,
+  MultisetEqualityProof,
+  InnerProductProof,
+  RangeProof,
+  KnowledgeProof,
+  EqualityProof,
+  ProductProof,
+  DotProductProof,
+  OrKnowledgeProof
+);
+
+impl<F: PrimeField> Serialize for Instance<F> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_canonical(&self.inst, serializer)
+  }
+}
+
+impl<'de, F: PrimeField> Deserialize<'de> for Instance<F> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Ok(Instance::from_r1cs_instance(deserialize_canonical(
+      deserializer,
+    )?))
+  }
+}
+
+/// a field element, as a big-endian hex string; only used for the human-readable encoding of
+/// assignments, since binary formats encode them canonically
+struct FieldElement<F>(F);
+
+impl<F: PrimeField> Serialize for FieldElement<F> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode_hex(&self.0.into_bigint().to_bytes_be()))
+  }
+}
+
+impl<'de, F: PrimeField> Deserialize<'de> for FieldElement<F> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer
+      .deserialize_any(FieldElementVisitor(PhantomData))
+      .map(FieldElement)
+  }
+}
+
+struct FieldElementVisitor<F>(PhantomData<F>);
+
+impl<'de, F: PrimeField> Visitor<'de> for FieldElementVisitor<F> {
+  type Value = F;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("a field element as a decimal or 0x-prefixed hex string, or an integer")
+  }
+
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<F, E> {
+    Ok(F::from(v))
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<F, E> {
+    // reject encodings of integers that are not smaller than the modulus, which would
+    // otherwise be silently reduced
+    let x = match v.strip_prefix("0x") {
+      Some(digits) if !digits.is_empty() => {
+        let digits = format!("{:0>1$}", digits, digits.len() + digits.len() % 2);
+        decode_hex(&digits).and_then(|bytes| {
+          let x = F::from_be_bytes_mod_order(&bytes);
+          let canonical = x.into_bigint().to_bytes_be();
+          (without_leading_zeros(&bytes) == without_leading_zeros(&canonical)).then_some(x)
+        })
+      }
+      Some(_) => None,
+      None => F::from_str(v)
+        .ok()
+        .filter(|x| x.into_bigint().to_string() == v),
+    };
+    x.ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+  }
+}
+
+impl<F: PrimeField> Serialize for Assignment<F> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+      serializer.collect_seq(self.assignment.iter().map(|x| FieldElement(*x)))
+    } else {
+      serialize_canonical(&self.assignment, serializer)
+    }
+  }
+}
+
+impl<'de, F: PrimeField> Deserialize<'de> for Assignment<F> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let assignment = if deserializer.is_human_readable() {
+      Vec::<FieldElement<F>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+    } else {
+      deserialize_canonical(deserializer)?
+    };
+    Ok(Assignment { assignment })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{InputsAssignment, SNARKGens};
+  use ark_bls12_381::{Fr, G1Projective};
+  use ark_ff::One;
+  use merlin::Transcript;
+
+  #[test]
+  fn check_serde_snark() {
+    check_serde_snark_helper::<G1Projective>();
+  }
+
+  fn check_serde_snark_helper<G: CurveGroup>() {
+    let (num_cons, num_vars, num_inputs) = (16, 16, 2);
+    let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    // a proof, commitment and inputs exchanged as JSON
+    let json = serde_json::to_string(&(&proof, &comm, &inputs)).unwrap();
+    let (proof_json, comm_json, inputs_json): (SNARK<G>, ComputationCommitment<G>, _) =
+      serde_json::from_str(&json).unwrap();
+    let inputs_json: InputsAssignment<G::ScalarField> = inputs_json;
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_json
+      .verify(&comm_json, &inputs_json, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // and in a compact binary encoding
+    let bytes = bincode::serialize(&(&proof, &comm, &inputs)).unwrap();
+    assert!(bytes.len() < json.len());
+    let (proof_bin, comm_bin, inputs_bin): (SNARK<G>, ComputationCommitment<G>, _) =
+      bincode::deserialize(&bytes).unwrap();
+    let inputs_bin: InputsAssignment<G::ScalarField> = inputs_bin;
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_bin
+      .verify(&comm_bin, &inputs_bin, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // an instance round-trips through JSON
+    let inst_json: Instance<G::ScalarField> =
+      serde_json::from_str(&serde_json::to_string(&inst).unwrap()).unwrap();
+    assert_eq!(
+      serde_json::to_string(&inst_json).unwrap(),
+      serde_json::to_string(&inst).unwrap()
+    );
+
+    // malformed hex is rejected
+    assert!(serde_json::from_str::<SNARK<G>>("\"0xzz\"").is_err());
+    let json = serde_json::to_string(&comm).unwrap();
+    let nested = json.replacen("0x", "0x0x", 1);
+    assert!(serde_json::from_str::<ComputationCommitment<G>>(&nested).is_err());
+  }
+
+  #[test]
+  fn check_assignment_json() {
+    let assignment = Assignment::<Fr>::from_json(r#"["0", "42", "0x2a", "0x02a", 42]"#).unwrap();
+    assert_eq!(
+      assignment.assignment,
+      vec![
+        Fr::from(0u64),
+        Fr::from(42u64),
+        Fr::from(42u64),
+        Fr::from(42u64),
+        Fr::from(42u64)
+      ]
+    );
+
+    // the encoding is big-endian hex, which parses back
+    let x = -Fr::one();
+    let json = serde_json::to_string(&Assignment::new(&[x]).unwrap()).unwrap();
+    assert!(json.starts_with("[\"0x73eda753"));
+    assert_eq!(
+      Assignment::<Fr>::from_json(&json).unwrap().assignment,
+      vec![x]
+    );
+
+    // non-canonical or malformed elements are rejected
+    let modulus = Fr::MODULUS.to_string();
+    for json in [
+      format!("[\"{}\"]", modulus),
+      format!("[\"0x{}\"]", "ff".repeat(32)),
+      "[\"0x\"]".to_string(),
+      "[\"0x0x2a\"]".to_string(),
+      "[\"0x+2a\"]".to_string(),
+      "[\"042\"]".to_string(),
+      "[\"-1\"]".to_string(),
+      "[-1]".to_string(),
+      "{}".to_string(),
+    ] {
+      assert!(matches!(
+        Assignment::<Fr>::from_json(&json),
+        Err(R1CSError::InvalidJson(_))
+      ));
+    }
+  }
+}
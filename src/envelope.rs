@@ -0,0 +1,296 @@
+//! A versioned, self-describing encoding of proofs.
+//!
+//! `ProofEnvelope` prefixes the canonical encoding of a proof with a header that identifies it:
+//!
+//! | field           | size | contents                                                  |
+//! |-----------------|------|-----------------------------------------------------------|
+//! | magic           | 4    | `b"SPRT"`                                                 |
+//! | version         | 2    | format version, little-endian                             |
+//! | curve           | 32   | fingerprint of the curve (scalar field modulus, generator) |
+//! | kind            | 1    | 1 for `SNARK`, 2 for `NIZK`                               |
+//! | instance digest | 32   | hash of the `ComputationCommitment` or the `Instance`     |
+//!
+//! so that a proof over another curve, of another kind, or for another statement is rejected
+//! with an `EnvelopeError` when it is loaded rather than failing verification obscurely.
+use super::errors::EnvelopeError;
+use super::{ComputationCommitment, Instance, NIZK, SNARK};
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use sha3::{Digest, Sha3_256};
+use std::io::{Read, Write};
+
+const MAGIC: [u8; 4] = *b"SPRT";
+// bumped whenever the encoding of proofs changes; version 2 compresses the ZK sum-check
+const VERSION: u16 = 2;
+
+/// The kind of proof held in a `ProofEnvelope`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofKind {
+  SNARK,
+  NIZK,
+}
+
+impl ProofKind {
+  fn to_byte(self) -> u8 {
+    match self {
+      ProofKind::SNARK => 1,
+      ProofKind::NIZK => 2,
+    }
+  }
+
+  fn from_byte(byte: u8) -> Option<Self> {
+    match byte {
+      1 => Some(ProofKind::SNARK),
+      2 => Some(ProofKind::NIZK),
+      _ => None,
+    }
+  }
+}
+
+/// A proof that can be wrapped in a `ProofEnvelope`
+pub trait EnvelopedProof: CanonicalSerialize + CanonicalDeserialize {
+  type Group: CurveGroup;
+  const KIND: ProofKind;
+}
+
+impl<G: CurveGroup> EnvelopedProof for SNARK<G> {
+  type Group = G;
+  const KIND: ProofKind = ProofKind::SNARK;
+}
+
+impl<G: CurveGroup> EnvelopedProof for NIZK<G> {
+  type Group = G;
+  const KIND: ProofKind = ProofKind::NIZK;
+}
+
+/// `ProofEnvelope` holds a proof together with the digest of the statement it was produced for
+pub struct ProofEnvelope<P> {
+  digest: [u8; 32],
+  proof: P,
+}
+
+impl<G: CurveGroup> ProofEnvelope<SNARK<G>> {
+  /// Wraps a SNARK produced for the computation committed to in `comm`
+  pub fn new(proof: SNARK<G>, comm: &ComputationCommitment<G>) -> Self {
+    ProofEnvelope {
+      digest: commitment_digest(comm),
+      proof,
+    }
+  }
+
+  /// Reads a SNARK, checking that its header matches `G` and `comm`
+  pub fn read<R: Read>(reader: R, comm: &ComputationCommitment<G>) -> Result<Self, EnvelopeError> {
+    Self::read_with_digest(reader, &commitment_digest(comm))
+  }
+}
+
+impl<G: CurveGroup> ProofEnvelope<NIZK<G>> {
+  /// Wraps a NIZK produced for `inst`
+  pub fn new(proof: NIZK<G>, inst: &Instance<G::ScalarField>) -> Self {
+    ProofEnvelope {
+      digest: inst.digest(),
+      proof,
+    }
+  }
+
+  /// Reads a NIZK, checking that its header matches `G` and `inst`
+  pub fn read<R: Read>(reader: R, inst: &Instance<G::ScalarField>) -> Result<Self, EnvelopeError> {
+    Self::read_with_digest(reader, &inst.digest())
+  }
+}
+
+impl<P: EnvelopedProof> ProofEnvelope<P> {
+  /// Returns the wrapped proof
+  pub fn proof(&self) -> &P {
+    &self.proof
+  }
+
+  /// Unwraps the proof
+  pub fn into_proof(self) -> P {
+    self.proof
+  }
+
+  /// Writes the header followed by the compressed proof
+  pub fn write<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&curve_id::<P::Group>())?;
+    writer.write_all(&[P::KIND.to_byte()])?;
+    writer.write_all(&self.digest)?;
+    self.proof.serialize_compressed(writer)
+  }
+
+  /// Returns the encoding of the envelope
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = vec![];
+    self.write(&mut bytes).unwrap();
+    bytes
+  }
+
+  fn read_with_digest<R: Read>(mut reader: R, digest: &[u8; 32]) -> Result<Self, EnvelopeError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+      return Err(EnvelopeError::InvalidMagic);
+    }
+
+    let mut version = [0u8; 2];
+    reader.read_exact(&mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version != VERSION {
+      return Err(EnvelopeError::UnsupportedVersion(version));
+    }
+
+    let mut curve = [0u8; 32];
+    reader.read_exact(&mut curve)?;
+    if curve != curve_id::<P::Group>() {
+      return Err(EnvelopeError::CurveMismatch);
+    }
+
+    let mut kind = [0u8; 1];
+    reader.read_exact(&mut kind)?;
+    let kind = ProofKind::from_byte(kind[0]).ok_or(EnvelopeError::UnknownKind(kind[0]))?;
+    if kind != P::KIND {
+      return Err(EnvelopeError::KindMismatch {
+        expected: P::KIND,
+        found: kind,
+      });
+    }
+
+    let mut found = [0u8; 32];
+    reader.read_exact(&mut found)?;
+    if &found != digest {
+      return Err(EnvelopeError::InstanceMismatch);
+    }
+
+    let proof = P::deserialize_compressed(reader)?;
+    Ok(ProofEnvelope {
+      digest: found,
+      proof,
+    })
+  }
+}
+
+fn hash(label: &[u8], data: &[u8]) -> [u8; 32] {
+  let mut hasher = Sha3_256::new();
+  hasher.input(label);
+  hasher.input(data);
+  let mut digest = [0u8; 32];
+  digest.copy_from_slice(&hasher.result());
+  digest
+}
+
+/// identifies the curve by the modulus of its scalar field and its generator
+fn curve_id<G: CurveGroup>() -> [u8; 32] {
+  let mut data = G::ScalarField::MODULUS.to_bytes_le();
+  G::generator().serialize_compressed(&mut data).unwrap();
+  hash(b"Spartan curve", &data)
+}
+
+fn commitment_digest<G: CurveGroup>(comm: &ComputationCommitment<G>) -> [u8; 32] {
+  let mut data = vec![];
+  comm.serialize_compressed(&mut data).unwrap();
+  hash(b"Spartan computation commitment", &data)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{NIZKGens, SNARKGens};
+  use ark_bls12_381::G1Projective;
+  use merlin::Transcript;
+
+  fn produce_snark<G: CurveGroup>(
+    num_vars: usize,
+  ) -> (SNARK<G>, ComputationCommitment<G>, Instance<G::ScalarField>) {
+    let (num_cons, num_inputs) = (num_vars, 2);
+    let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+    let mut transcript = Transcript::new(b"example");
+    let proof = SNARK::prove(&inst, &comm, &decomm, vars, &inputs, &gens, &mut transcript);
+    (proof, comm, inst)
+  }
+
+  #[test]
+  fn check_envelope() {
+    check_envelope_helper::<G1Projective>();
+  }
+
+  fn check_envelope_helper<G: CurveGroup>() {
+    let (proof, comm, inst) = produce_snark::<G>(16);
+    let bytes = ProofEnvelope::<SNARK<G>>::new(proof, &comm).to_bytes();
+    assert_eq!(&bytes[..4], b"SPRT");
+    assert!(ProofEnvelope::<SNARK<G>>::read(&bytes[..], &comm).is_ok());
+
+    // the proof is rejected for another computation
+    let (_, comm_other, _) = produce_snark::<G>(32);
+    assert!(matches!(
+      ProofEnvelope::<SNARK<G>>::read(&bytes[..], &comm_other),
+      Err(EnvelopeError::InstanceMismatch)
+    ));
+
+    // the proof is rejected as a NIZK
+    assert!(matches!(
+      ProofEnvelope::<NIZK<G>>::read(&bytes[..], &inst),
+      Err(EnvelopeError::KindMismatch {
+        expected: ProofKind::NIZK,
+        found: ProofKind::SNARK
+      })
+    ));
+
+    // the proof is rejected over another curve
+    let (_, comm_bn254, _) = produce_snark::<ark_bn254::G1Projective>(16);
+    assert!(matches!(
+      ProofEnvelope::<SNARK<ark_bn254::G1Projective>>::read(&bytes[..], &comm_bn254),
+      Err(EnvelopeError::CurveMismatch)
+    ));
+
+    // corrupted headers are rejected
+    let mut corrupted = bytes.clone();
+    corrupted[0] ^= 1;
+    assert!(matches!(
+      ProofEnvelope::<SNARK<G>>::read(&corrupted[..], &comm),
+      Err(EnvelopeError::InvalidMagic)
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[4] = 1;
+    assert!(matches!(
+      ProofEnvelope::<SNARK<G>>::read(&corrupted[..], &comm),
+      Err(EnvelopeError::UnsupportedVersion(1))
+    ));
+    assert!(matches!(
+      ProofEnvelope::<SNARK<G>>::read(&bytes[..bytes.len() - 1], &comm),
+      Err(EnvelopeError::Serialization(_))
+    ));
+  }
+
+  #[test]
+  fn check_nizk_envelope() {
+    check_nizk_envelope_helper::<G1Projective>();
+  }
+
+  fn check_nizk_envelope_helper<G: CurveGroup>() {
+    let (num_cons, num_vars, num_inputs) = (16, 16, 2);
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let bytes = ProofEnvelope::<NIZK<G>>::new(proof, &inst).to_bytes();
+    let proof = ProofEnvelope::<NIZK<G>>::read(&bytes[..], &inst)
+      .unwrap()
+      .into_proof();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    let (inst_other, _, _) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs + 1);
+    assert!(matches!(
+      ProofEnvelope::<NIZK<G>>::read(&bytes[..], &inst_other),
+      Err(EnvelopeError::InstanceMismatch)
+    ));
+  }
+}
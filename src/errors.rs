@@ -1,18 +1,53 @@
+use super::envelope::ProofKind;
 use ark_serialize::SerializationError;
 use core::fmt::Debug;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Default)]
 pub enum ProofVerifyError {
   #[error("Proof verification failed")]
+  #[default]
   InternalError,
   #[error("Compressed group element failed to decompress: {0:?}")]
   DecompressionError([u8; 32]),
 }
 
-impl Default for ProofVerifyError {
-  fn default() -> Self {
-    ProofVerifyError::InternalError
+#[derive(Error, Debug)]
+pub enum SumcheckError {
+  #[error("Sum-check has no polynomials")]
+  NoPolynomials,
+  #[error("Sum-check has no rounds")]
+  NoRounds,
+  #[error("Polynomials have different numbers of variables")]
+  MismatchedPolynomials,
+  #[error("Sum-check has more coefficients than generators")]
+  InsufficientGenerators,
+}
+
+#[derive(Error, Debug)]
+pub enum EnvelopeError {
+  #[error("Not a Spartan proof envelope")]
+  InvalidMagic,
+  #[error("Unsupported proof envelope version {0}")]
+  UnsupportedVersion(u16),
+  #[error("Proof is over a different curve")]
+  CurveMismatch,
+  #[error("Unknown proof kind {0}")]
+  UnknownKind(u8),
+  #[error("Expected a {expected:?} proof, found a {found:?} proof")]
+  KindMismatch {
+    expected: ProofKind,
+    found: ProofKind,
+  },
+  #[error("Proof was produced for a different instance")]
+  InstanceMismatch,
+  #[error("Malformed proof: {0}")]
+  Serialization(#[from] SerializationError),
+}
+
+impl From<std::io::Error> for EnvelopeError {
+  fn from(e: std::io::Error) -> Self {
+    Self::Serialization(SerializationError::IoError(e))
   }
 }
 
@@ -30,8 +65,16 @@ pub enum R1CSError {
   InvalidScalar,
   /// returned if the supplied row or col in (row,col,val) tuple is out of range
   InvalidIndex,
+  /// returned if a CCS multiset is empty, refers to a non-existent matrix, or has no matching constant
+  InvalidMultiset,
+  /// returned if witness rounds exceed the variables or inputs, do not end at a row boundary
+  /// of the commitment to the variables, or the last round has challenges
+  InvalidWitnessRounds,
   /// Ark serialization error
   ArkSerializationError(SerializationError),
+  /// returned if JSON does not parse into an assignment of field elements
+  #[cfg(feature = "serde")]
+  InvalidJson(serde_json::Error),
 }
 
 impl From<SerializationError> for R1CSError {
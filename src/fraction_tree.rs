@@ -0,0 +1,583 @@
+#![allow(clippy::type_complexity)]
+//! Layered circuits that add up fractions `p_i / q_i`, used by the logUp variant of Spark.
+//! Each layer maps two fractions `(p_l, q_l)` and `(p_r, q_r)` to `(p_l * q_r + p_r * q_l, q_l * q_r)`,
+//! so the root holds the (unreduced) sum of all leaf fractions.
+use super::dense_mlpoly::DensePolynomial;
+use super::dense_mlpoly::EqPolynomial;
+use super::errors::ProofVerifyError;
+use super::math::Math;
+use super::product_tree::DotProductCircuit;
+use super::sumcheck::SumcheckInstanceProof;
+use super::transcript::ProofTranscript;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use merlin::Transcript;
+
+#[derive(Debug)]
+pub struct FractionalSumCircuit<F> {
+  left_p_vec: Vec<DensePolynomial<F>>,
+  right_p_vec: Vec<DensePolynomial<F>>,
+  left_q_vec: Vec<DensePolynomial<F>>,
+  right_q_vec: Vec<DensePolynomial<F>>,
+}
+
+impl<F: PrimeField> FractionalSumCircuit<F> {
+  fn compute_layer(
+    inp_left_p: &DensePolynomial<F>,
+    inp_right_p: &DensePolynomial<F>,
+    inp_left_q: &DensePolynomial<F>,
+    inp_right_q: &DensePolynomial<F>,
+  ) -> (
+    DensePolynomial<F>,
+    DensePolynomial<F>,
+    DensePolynomial<F>,
+    DensePolynomial<F>,
+  ) {
+    let len = inp_left_p.len() + inp_right_p.len();
+    let outp_p = (0..len / 2)
+      .map(|i| inp_left_p[i] * inp_right_q[i] + inp_right_p[i] * inp_left_q[i])
+      .collect::<Vec<F>>();
+    let outp_q = (0..len / 2)
+      .map(|i| inp_left_q[i] * inp_right_q[i])
+      .collect::<Vec<F>>();
+
+    let (outp_left_p, outp_right_p) = DensePolynomial::new(outp_p).split(len / 4);
+    let (outp_left_q, outp_right_q) = DensePolynomial::new(outp_q).split(len / 4);
+    (outp_left_p, outp_right_p, outp_left_q, outp_right_q)
+  }
+
+  pub fn new(p: &DensePolynomial<F>, q: &DensePolynomial<F>) -> Self {
+    assert_eq!(p.len(), q.len());
+    assert!(p.len() >= 2);
+
+    let num_layers = p.len().log_2();
+    let (outp_left_p, outp_right_p) = p.split(p.len() / 2);
+    let (outp_left_q, outp_right_q) = q.split(q.len() / 2);
+
+    let mut left_p_vec = vec![outp_left_p];
+    let mut right_p_vec = vec![outp_right_p];
+    let mut left_q_vec = vec![outp_left_q];
+    let mut right_q_vec = vec![outp_right_q];
+
+    for i in 0..num_layers - 1 {
+      let (outp_left_p, outp_right_p, outp_left_q, outp_right_q) =
+        FractionalSumCircuit::compute_layer(
+          &left_p_vec[i],
+          &right_p_vec[i],
+          &left_q_vec[i],
+          &right_q_vec[i],
+        );
+      left_p_vec.push(outp_left_p);
+      right_p_vec.push(outp_right_p);
+      left_q_vec.push(outp_left_q);
+      right_q_vec.push(outp_right_q);
+    }
+
+    FractionalSumCircuit {
+      left_p_vec,
+      right_p_vec,
+      left_q_vec,
+      right_q_vec,
+    }
+  }
+
+  /// returns the numerator and the denominator of the sum of all leaf fractions
+  pub fn evaluate(&self) -> (F, F) {
+    let len = self.left_p_vec.len();
+    assert_eq!(self.left_p_vec[len - 1].get_num_vars(), 0);
+    assert_eq!(self.right_p_vec[len - 1].get_num_vars(), 0);
+    let (p_l, p_r) = (self.left_p_vec[len - 1][0], self.right_p_vec[len - 1][0]);
+    let (q_l, q_r) = (self.left_q_vec[len - 1][0], self.right_q_vec[len - 1][0]);
+    (p_l * q_r + p_r * q_l, q_l * q_r)
+  }
+}
+
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FractionLayerProofBatched<F: PrimeField> {
+  pub proof: SumcheckInstanceProof<F>,
+  pub claims_p_left: Vec<F>,
+  pub claims_p_right: Vec<F>,
+  pub claims_q_left: Vec<F>,
+  pub claims_q_right: Vec<F>,
+}
+
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FractionalSumCircuitEvalProofBatched<F: PrimeField> {
+  proof: Vec<FractionLayerProofBatched<F>>,
+  claims_dotp: (Vec<F>, Vec<F>, Vec<F>),
+}
+
+impl<F: PrimeField> FractionalSumCircuitEvalProofBatched<F> {
+  pub fn prove<G>(
+    circuit_vec: &mut Vec<&mut FractionalSumCircuit<F>>,
+    dotp_circuit_vec: &mut Vec<&mut DotProductCircuit<F>>,
+    transcript: &mut Transcript,
+  ) -> (Self, Vec<F>)
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
+    assert!(!circuit_vec.is_empty());
+
+    let mut claims_dotp_final = (Vec::new(), Vec::new(), Vec::new());
+
+    let mut proof_layers: Vec<FractionLayerProofBatched<F>> = Vec::new();
+    let num_layers = circuit_vec[0].left_p_vec.len();
+    let num_circuits = circuit_vec.len();
+    let mut claims_to_verify = circuit_vec
+      .iter()
+      .flat_map(|circuit| {
+        let (p, q) = circuit.evaluate();
+        [p, q]
+      })
+      .collect::<Vec<F>>();
+    let mut rand = Vec::new();
+    for layer_id in (0..num_layers).rev() {
+      let len = circuit_vec[0].left_p_vec[layer_id].len() * 2;
+
+      let mut poly_C_par = DensePolynomial::new(EqPolynomial::<F>::new(rand.clone()).evals());
+      assert_eq!(poly_C_par.len(), len / 2);
+
+      let num_rounds = poly_C_par.len().log_2();
+      let comb_func = |poly_A_comp: &F, poly_B_comp: &F, poly_C_comp: &F| -> F {
+        *poly_A_comp * *poly_B_comp * *poly_C_comp
+      };
+
+      // each circuit contributes three products that share poly_C:
+      // p_left * q_right and p_right * q_left for the numerator, and q_left * q_right for the denominator;
+      // the denominator uses its own copies since every polynomial in a batch is bound independently
+      let mut q_left_copies = circuit_vec
+        .iter()
+        .map(|circuit| circuit.left_q_vec[layer_id].clone())
+        .collect::<Vec<DensePolynomial<F>>>();
+      let mut q_right_copies = circuit_vec
+        .iter()
+        .map(|circuit| circuit.right_q_vec[layer_id].clone())
+        .collect::<Vec<DensePolynomial<F>>>();
+
+      let mut poly_A_batched_par: Vec<&mut DensePolynomial<F>> = Vec::new();
+      let mut poly_B_batched_par: Vec<&mut DensePolynomial<F>> = Vec::new();
+      for ((circuit, q_left_copy), q_right_copy) in circuit_vec
+        .iter_mut()
+        .zip(q_left_copies.iter_mut())
+        .zip(q_right_copies.iter_mut())
+      {
+        let FractionalSumCircuit {
+          left_p_vec,
+          right_p_vec,
+          left_q_vec,
+          right_q_vec,
+        } = &mut **circuit;
+        poly_A_batched_par.push(&mut left_p_vec[layer_id]);
+        poly_B_batched_par.push(&mut right_q_vec[layer_id]);
+        poly_A_batched_par.push(&mut right_p_vec[layer_id]);
+        poly_B_batched_par.push(&mut left_q_vec[layer_id]);
+        poly_A_batched_par.push(q_left_copy);
+        poly_B_batched_par.push(q_right_copy);
+      }
+      let poly_vec_par = (
+        &mut poly_A_batched_par,
+        &mut poly_B_batched_par,
+        &mut poly_C_par,
+      );
+
+      // prepare sequential instances that don't share poly_C
+      let mut poly_A_batched_seq: Vec<&mut DensePolynomial<F>> = Vec::new();
+      let mut poly_B_batched_seq: Vec<&mut DensePolynomial<F>> = Vec::new();
+      let mut poly_C_batched_seq: Vec<&mut DensePolynomial<F>> = Vec::new();
+      if layer_id == 0 && !dotp_circuit_vec.is_empty() {
+        // add additional claims
+        for item in dotp_circuit_vec.iter() {
+          claims_to_verify.push(item.evaluate());
+          assert_eq!(len / 2, item.left.len());
+          assert_eq!(len / 2, item.right.len());
+          assert_eq!(len / 2, item.weight.len());
+        }
+
+        for dotp_circuit in dotp_circuit_vec.iter_mut() {
+          poly_A_batched_seq.push(&mut dotp_circuit.left);
+          poly_B_batched_seq.push(&mut dotp_circuit.right);
+          poly_C_batched_seq.push(&mut dotp_circuit.weight);
+        }
+      }
+      let poly_vec_seq = (
+        &mut poly_A_batched_seq,
+        &mut poly_B_batched_seq,
+        &mut poly_C_batched_seq,
+      );
+
+      // produce a fresh set of coeffs and a joint claim
+      let coeff_vec = <Transcript as ProofTranscript<G>>::challenge_vector(
+        transcript,
+        b"rand_coeffs_next_layer",
+        claims_to_verify.len(),
+      );
+      let claim = (0..claims_to_verify.len())
+        .map(|i| claims_to_verify[i] * coeff_vec[i])
+        .sum();
+
+      // the numerator coefficient is shared by both of its products
+      let coeffs_expanded = (0..num_circuits)
+        .flat_map(|i| [coeff_vec[2 * i], coeff_vec[2 * i], coeff_vec[2 * i + 1]])
+        .chain(coeff_vec[2 * num_circuits..].iter().cloned())
+        .collect::<Vec<F>>();
+
+      let (proof, rand_prod, claims_prod, claims_dotp) =
+        SumcheckInstanceProof::<F>::prove_cubic_batched::<_, G>(
+          &claim,
+          num_rounds,
+          poly_vec_par,
+          poly_vec_seq,
+          &coeffs_expanded,
+          comb_func,
+          transcript,
+        );
+
+      let (claims_A, claims_B, _claims_eq) = claims_prod;
+      let claims_p_left = (0..num_circuits)
+        .map(|i| claims_A[3 * i])
+        .collect::<Vec<F>>();
+      let claims_p_right = (0..num_circuits)
+        .map(|i| claims_A[3 * i + 1])
+        .collect::<Vec<F>>();
+      let claims_q_left = (0..num_circuits)
+        .map(|i| claims_B[3 * i + 1])
+        .collect::<Vec<F>>();
+      let claims_q_right = (0..num_circuits)
+        .map(|i| claims_B[3 * i])
+        .collect::<Vec<F>>();
+      for i in 0..num_circuits {
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_p_left",
+          &claims_p_left[i],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_p_right",
+          &claims_p_right[i],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_q_left",
+          &claims_q_left[i],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_q_right",
+          &claims_q_right[i],
+        );
+      }
+
+      if layer_id == 0 && !dotp_circuit_vec.is_empty() {
+        let (claims_dotp_left, claims_dotp_right, claims_dotp_weight) = claims_dotp;
+        for i in 0..dotp_circuit_vec.len() {
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_left",
+            &claims_dotp_left[i],
+          );
+
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_right",
+            &claims_dotp_right[i],
+          );
+
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_weight",
+            &claims_dotp_weight[i],
+          );
+        }
+        claims_dotp_final = (claims_dotp_left, claims_dotp_right, claims_dotp_weight);
+      }
+
+      // produce a random challenge to condense two claims into a single claim
+      let r_layer =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_r_layer");
+
+      claims_to_verify = (0..num_circuits)
+        .flat_map(|i| {
+          [
+            claims_p_left[i] + r_layer * (claims_p_right[i] - claims_p_left[i]),
+            claims_q_left[i] + r_layer * (claims_q_right[i] - claims_q_left[i]),
+          ]
+        })
+        .collect::<Vec<F>>();
+
+      let mut ext = vec![r_layer];
+      ext.extend(rand_prod);
+      rand = ext;
+
+      proof_layers.push(FractionLayerProofBatched {
+        proof,
+        claims_p_left,
+        claims_p_right,
+        claims_q_left,
+        claims_q_right,
+      });
+    }
+
+    (
+      FractionalSumCircuitEvalProofBatched {
+        proof: proof_layers,
+        claims_dotp: claims_dotp_final,
+      },
+      rand,
+    )
+  }
+
+  /// Verifies the claimed (numerator, denominator) pairs of a batch of fractional-sum circuits
+  /// with `len` leaves each. Returns the claimed (numerator, denominator) of every circuit's leaves
+  /// at the returned random point, along with the reduced claims of the dot-product circuits.
+  pub fn verify<G>(
+    &self,
+    claims_frac_vec: &[(F, F)],
+    claims_dotp_vec: &[F],
+    len: usize,
+    transcript: &mut Transcript,
+  ) -> Result<(Vec<(F, F)>, Vec<F>, Vec<F>), ProofVerifyError>
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
+    let num_layers = len.log_2();
+    let num_circuits = claims_frac_vec.len();
+    let mut rand: Vec<F> = Vec::new();
+    if self.proof.len() != num_layers {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    let mut claims_to_verify = claims_frac_vec
+      .iter()
+      .flat_map(|(p, q)| [*p, *q])
+      .collect::<Vec<F>>();
+    let mut claims_to_verify_dotp: Vec<F> = Vec::new();
+    for (num_rounds, i) in (0..num_layers).enumerate() {
+      if i == num_layers - 1 {
+        claims_to_verify.extend(claims_dotp_vec);
+      }
+
+      // produce random coefficients, one for each claim
+      let coeff_vec = <Transcript as ProofTranscript<G>>::challenge_vector(
+        transcript,
+        b"rand_coeffs_next_layer",
+        claims_to_verify.len(),
+      );
+
+      // produce a joint claim
+      let claim = (0..claims_to_verify.len())
+        .map(|i| claims_to_verify[i] * coeff_vec[i])
+        .sum();
+
+      let (claim_last, rand_prod) = self.proof[i]
+        .proof
+        .verify::<G>(claim, num_rounds, 3, transcript)?;
+
+      let layer = &self.proof[i];
+      if layer.claims_p_left.len() != num_circuits
+        || layer.claims_p_right.len() != num_circuits
+        || layer.claims_q_left.len() != num_circuits
+        || layer.claims_q_right.len() != num_circuits
+      {
+        return Err(ProofVerifyError::InternalError);
+      }
+
+      for j in 0..num_circuits {
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_p_left",
+          &layer.claims_p_left[j],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_p_right",
+          &layer.claims_p_right[j],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_q_left",
+          &layer.claims_q_left[j],
+        );
+        <Transcript as ProofTranscript<G>>::append_scalar(
+          transcript,
+          b"claim_q_right",
+          &layer.claims_q_right[j],
+        );
+      }
+
+      let eq: F = (0..rand.len())
+        .map(|i| rand[i] * rand_prod[i] + (F::one() - rand[i]) * (F::one() - rand_prod[i]))
+        .product();
+      let mut claim_expected: F = (0..num_circuits)
+        .map(|j| {
+          let (p_l, p_r) = (layer.claims_p_left[j], layer.claims_p_right[j]);
+          let (q_l, q_r) = (layer.claims_q_left[j], layer.claims_q_right[j]);
+          (coeff_vec[2 * j] * (p_l * q_r + p_r * q_l) + coeff_vec[2 * j + 1] * q_l * q_r) * eq
+        })
+        .sum();
+
+      // add claims from the dotp instances
+      if i == num_layers - 1 {
+        let (claims_dotp_left, claims_dotp_right, claims_dotp_weight) = &self.claims_dotp;
+        if claims_dotp_left.len() != claims_dotp_vec.len()
+          || claims_dotp_right.len() != claims_dotp_vec.len()
+          || claims_dotp_weight.len() != claims_dotp_vec.len()
+        {
+          return Err(ProofVerifyError::InternalError);
+        }
+        for j in 0..claims_dotp_left.len() {
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_left",
+            &claims_dotp_left[j],
+          );
+
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_right",
+            &claims_dotp_right[j],
+          );
+
+          <Transcript as ProofTranscript<G>>::append_scalar(
+            transcript,
+            b"claim_dotp_weight",
+            &claims_dotp_weight[j],
+          );
+
+          claim_expected += coeff_vec[j + 2 * num_circuits]
+            * claims_dotp_left[j]
+            * claims_dotp_right[j]
+            * claims_dotp_weight[j];
+        }
+      }
+
+      if claim_expected != claim_last {
+        return Err(ProofVerifyError::InternalError);
+      }
+
+      // produce a random challenge
+      let r_layer =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_r_layer");
+
+      claims_to_verify = (0..num_circuits)
+        .flat_map(|j| {
+          let (p_l, p_r) = (layer.claims_p_left[j], layer.claims_p_right[j]);
+          let (q_l, q_r) = (layer.claims_q_left[j], layer.claims_q_right[j]);
+          [p_l + r_layer * (p_r - p_l), q_l + r_layer * (q_r - q_l)]
+        })
+        .collect::<Vec<F>>();
+
+      // add claims to verify for dotp circuit
+      if i == num_layers - 1 {
+        let (claims_dotp_left, claims_dotp_right, claims_dotp_weight) = &self.claims_dotp;
+
+        for j in 0..claims_dotp_vec.len() / 2 {
+          // combine left claims
+          let claim_left = claims_dotp_left[2 * j]
+            + r_layer * (claims_dotp_left[2 * j + 1] - claims_dotp_left[2 * j]);
+
+          let claim_right = claims_dotp_right[2 * j]
+            + r_layer * (claims_dotp_right[2 * j + 1] - claims_dotp_right[2 * j]);
+
+          let claim_weight = claims_dotp_weight[2 * j]
+            + r_layer * (claims_dotp_weight[2 * j + 1] - claims_dotp_weight[2 * j]);
+          claims_to_verify_dotp.push(claim_left);
+          claims_to_verify_dotp.push(claim_right);
+          claims_to_verify_dotp.push(claim_weight);
+        }
+      }
+
+      let mut ext = vec![r_layer];
+      ext.extend(rand_prod);
+      rand = ext;
+    }
+
+    let claims_frac = (0..num_circuits)
+      .map(|j| (claims_to_verify[2 * j], claims_to_verify[2 * j + 1]))
+      .collect::<Vec<(F, F)>>();
+    Ok((claims_frac, claims_to_verify_dotp, rand))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_ff::Field;
+  use ark_std::test_rng;
+  use ark_std::UniformRand;
+
+  #[test]
+  fn check_fractional_sum_circuit() {
+    check_fractional_sum_circuit_helper::<G1Projective>()
+  }
+
+  fn check_fractional_sum_circuit_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let len = 64;
+
+    let mut circuits = (0..3)
+      .map(|_| {
+        let p = (0..len)
+          .map(|_| G::ScalarField::rand(&mut prng))
+          .collect::<Vec<_>>();
+        let q = (0..len)
+          .map(|_| G::ScalarField::rand(&mut prng))
+          .collect::<Vec<_>>();
+        (p, q)
+      })
+      .collect::<Vec<_>>();
+
+    let mut circuit_vec = circuits
+      .iter_mut()
+      .map(|(p, q)| {
+        let circuit = FractionalSumCircuit::new(
+          &DensePolynomial::new(p.clone()),
+          &DensePolynomial::new(q.clone()),
+        );
+        let (num, den) = circuit.evaluate();
+        let expected: G::ScalarField = (0..len).map(|i| p[i] * q[i].inverse().unwrap()).sum();
+        assert_eq!(num * den.inverse().unwrap(), expected);
+        circuit
+      })
+      .collect::<Vec<_>>();
+    let claims = circuit_vec
+      .iter()
+      .map(|circuit| circuit.evaluate())
+      .collect::<Vec<_>>();
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, rand_prover) = FractionalSumCircuitEvalProofBatched::prove::<G>(
+      &mut circuit_vec.iter_mut().collect(),
+      &mut Vec::new(),
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (claims_leaves, _, rand_verifier) = proof
+      .verify::<G>(&claims, &[], len, &mut verifier_transcript)
+      .unwrap();
+    assert_eq!(rand_prover, rand_verifier);
+
+    // the reduced claims must match the leaves evaluated at the random point
+    for ((p, q), (claim_p, claim_q)) in circuits.iter().zip(claims_leaves.iter()) {
+      assert_eq!(
+        DensePolynomial::new(p.clone()).evaluate::<G>(&rand_verifier),
+        *claim_p
+      );
+      assert_eq!(
+        DensePolynomial::new(q.clone()).evaluate::<G>(&rand_verifier),
+        *claim_q
+      );
+    }
+
+    // a wrong root claim must be rejected
+    let mut bad_claims = claims;
+    bad_claims[1].0 += G::ScalarField::from(1u64);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify::<G>(&bad_claims, &[], len, &mut verifier_transcript)
+      .is_err());
+  }
+}
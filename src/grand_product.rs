@@ -0,0 +1,479 @@
+//! Grand-product arguments.
+//!
+//! `GrandProductProof` proves the products of the entries of vectors with the layered product
+//! circuits (a GKR protocol) that Spark uses for memory checking. Verifying it reduces the claimed
+//! products to claims about the evaluations of the vectors, viewed as multilinear polynomials, at
+//! a random point, which the caller checks by other means, e.g., with the `pcs` module.
+//!
+//! `MultisetEqualityProof` builds on it to prove that two vectors committed with the `pcs` module
+//! hold the same multiset of values, or that one is a given permutation of the other. Neither proof
+//! is zero-knowledge.
+//!
+//! ```
+//! # use ark_bls12_381::{Fr, G1Projective};
+//! # use libspartan::grand_product::GrandProductProof;
+//! # use libspartan::pcs::MultilinearPolynomial;
+//! # use merlin::Transcript;
+//! let poly = MultilinearPolynomial::new((1..=8u64).map(Fr::from).collect());
+//!
+//! let mut prover_transcript = Transcript::new(b"grand_product_example");
+//! let (proof, products, r, evals) =
+//!   GrandProductProof::prove::<G1Projective>(&[&poly], &mut prover_transcript);
+//! assert_eq!(products, vec![Fr::from(40320u64)]);
+//!
+//! let mut verifier_transcript = Transcript::new(b"grand_product_example");
+//! let (claims, r_verifier) = proof
+//!   .verify::<G1Projective>(&products, 8, &mut verifier_transcript)
+//!   .unwrap();
+//! assert_eq!(r, r_verifier);
+//! // the claims are checked against the vector, here directly
+//! assert_eq!(claims, vec![poly.evaluate(&r)]);
+//! # assert_eq!(claims, evals);
+//! ```
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::type_complexity)]
+use super::dense_mlpoly::{DensePolynomial, EqPolynomial, IdentityPolynomial};
+use super::errors::ProofVerifyError;
+use super::pcs::{BatchedEvalProof, Commitment, Decommitment, MultilinearPolynomial, PCSGens};
+use super::product_tree::{DotProductCircuit, ProductCircuit, ProductCircuitEvalProofBatched};
+use super::transcript::ProofTranscript;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::Zero;
+use merlin::Transcript;
+
+/// `GrandProductProof` proves the products of the entries of one or more vectors of the same
+/// length, which must be a power of two and at least two
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GrandProductProof<F: PrimeField> {
+  proof: ProductCircuitEvalProofBatched<F>,
+}
+
+impl<F: PrimeField> GrandProductProof<F> {
+  fn protocol_name() -> &'static [u8] {
+    b"grand product proof"
+  }
+
+  /// Proves the products of the evaluations of `polys`. Returns the proof, the products, and a
+  /// random point along with the evaluations of `polys` at it.
+  pub fn prove<G>(
+    polys: &[&MultilinearPolynomial<F>],
+    transcript: &mut Transcript,
+  ) -> (Self, Vec<F>, Vec<F>, Vec<F>)
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      GrandProductProof::<F>::protocol_name(),
+    );
+
+    assert!(!polys.is_empty());
+    let num_vars = polys[0].num_vars();
+    assert!(num_vars > 0);
+    assert!(polys.iter().all(|poly| poly.num_vars() == num_vars));
+
+    let mut circuits = polys
+      .iter()
+      .map(|poly| ProductCircuit::new(&poly.poly))
+      .collect::<Vec<ProductCircuit<F>>>();
+    let products = circuits
+      .iter()
+      .map(|circuit| circuit.evaluate())
+      .collect::<Vec<F>>();
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"products", &products);
+
+    let (proof, r) = ProductCircuitEvalProofBatched::prove::<G>(
+      &mut circuits.iter_mut().collect::<Vec<&mut ProductCircuit<F>>>(),
+      &mut Vec::<&mut DotProductCircuit<F>>::new(),
+      transcript,
+    );
+    let evals = polys.iter().map(|poly| poly.evaluate(&r)).collect();
+
+    (GrandProductProof { proof }, products, r, evals)
+  }
+
+  /// Verifies the claimed `products` of vectors of length `len`. Returns the claimed evaluations
+  /// of the vectors at a random point and the point itself, which the caller checks against the
+  /// vectors.
+  pub fn verify<G>(
+    &self,
+    products: &[F],
+    len: usize,
+    transcript: &mut Transcript,
+  ) -> Result<(Vec<F>, Vec<F>), ProofVerifyError>
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      GrandProductProof::<F>::protocol_name(),
+    );
+
+    if products.is_empty() || len < 2 || !len.is_power_of_two() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"products", products);
+
+    let (claims, _claims_dotp, r) = self.proof.verify::<G>(products, &[], len, transcript)?;
+    Ok((claims, r))
+  }
+}
+
+/// `MultisetEqualityProof` proves that two vectors, committed under the same `PCSGens`, hold the
+/// same multiset of values, or that one is a given permutation of the other. The vectors are
+/// fingerprinted with a random challenge `gamma` as `prod_i (gamma - a_i)`, and a
+/// `GrandProductProof` shows that the fingerprints are equal; the claimed evaluations of the
+/// vectors that it produces are then proven against the commitments.
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultisetEqualityProof<G: CurveGroup> {
+  product: G::ScalarField,
+  proof_prod: GrandProductProof<G::ScalarField>,
+  proof_eval: BatchedEvalProof<G>,
+}
+
+impl<G: CurveGroup> MultisetEqualityProof<G> {
+  fn protocol_name(permutation: bool) -> &'static [u8] {
+    if permutation {
+      b"permutation proof"
+    } else {
+      b"multiset equality proof"
+    }
+  }
+
+  /// Proves that `b` is a permutation of `a`, i.e., that they hold the same multiset of values;
+  /// the decommitments must be provided if the commitments are hiding
+  pub fn prove<R: RngCore + CryptoRng>(
+    a: &MultilinearPolynomial<G::ScalarField>,
+    comm_a: &Commitment<G>,
+    b: &MultilinearPolynomial<G::ScalarField>,
+    comm_b: &Commitment<G>,
+    decomms: Option<(&Decommitment<G::ScalarField>, &Decommitment<G::ScalarField>)>,
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Self {
+    Self::prove_helper(a, comm_a, b, comm_b, decomms, None, gens, transcript, rng)
+  }
+
+  /// Verifies that the vectors committed to in `comm_a` and `comm_b` hold the same multiset of
+  /// values
+  pub fn verify(
+    &self,
+    comm_a: &Commitment<G>,
+    comm_b: &Commitment<G>,
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_helper(comm_a, comm_b, None, gens, transcript)
+  }
+
+  /// Proves that `b[j] = a[sigma[j]]` for every `j`, for a public permutation `sigma`
+  pub fn prove_permutation<R: RngCore + CryptoRng>(
+    a: &MultilinearPolynomial<G::ScalarField>,
+    comm_a: &Commitment<G>,
+    b: &MultilinearPolynomial<G::ScalarField>,
+    comm_b: &Commitment<G>,
+    decomms: Option<(&Decommitment<G::ScalarField>, &Decommitment<G::ScalarField>)>,
+    sigma: &[usize],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Self {
+    Self::prove_helper(
+      a,
+      comm_a,
+      b,
+      comm_b,
+      decomms,
+      Some(sigma),
+      gens,
+      transcript,
+      rng,
+    )
+  }
+
+  /// Verifies that the vector committed to in `comm_b` is the vector committed to in `comm_a`
+  /// permuted by `sigma`
+  pub fn verify_permutation(
+    &self,
+    comm_a: &Commitment<G>,
+    comm_b: &Commitment<G>,
+    sigma: &[usize],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_helper(comm_a, comm_b, Some(sigma), gens, transcript)
+  }
+
+  // derives gamma and, for permutations, the challenge beta with which the position of each
+  // value is bound to it
+  fn challenges(
+    comm_a: &Commitment<G>,
+    comm_b: &Commitment<G>,
+    sigma: Option<&[usize]>,
+    transcript: &mut Transcript,
+  ) -> (G::ScalarField, G::ScalarField) {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      MultisetEqualityProof::<G>::protocol_name(sigma.is_some()),
+    );
+    comm_a.append_to_transcript(b"comm_a", transcript);
+    comm_b.append_to_transcript(b"comm_b", transcript);
+    if let Some(sigma) = sigma {
+      let sigma = sigma
+        .iter()
+        .map(|&s| G::ScalarField::from(s as u64))
+        .collect::<Vec<G::ScalarField>>();
+      <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"sigma", &sigma);
+    }
+    let gamma =
+      <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_gamma");
+    let beta = match sigma {
+      Some(_) => {
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_beta")
+      }
+      None => G::ScalarField::zero(),
+    };
+    (gamma, beta)
+  }
+
+  // returns gamma - (v_i + beta * pos_i) for every i
+  fn fingerprints(
+    v: &MultilinearPolynomial<G::ScalarField>,
+    pos: &[G::ScalarField],
+    gamma: &G::ScalarField,
+    beta: &G::ScalarField,
+  ) -> MultilinearPolynomial<G::ScalarField> {
+    let evals = (0..v.poly.len())
+      .map(|i| *gamma - (v.poly[i] + *beta * pos[i]))
+      .collect();
+    MultilinearPolynomial {
+      poly: DensePolynomial::new(evals),
+    }
+  }
+
+  // the positions of the values of a and b: (i, sigma(i)) for permutations, and zero otherwise
+  fn positions(len: usize, sigma: Option<&[usize]>) -> (Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    match sigma {
+      Some(sigma) => (
+        (0..len).map(|i| G::ScalarField::from(i as u64)).collect(),
+        sigma
+          .iter()
+          .map(|&s| G::ScalarField::from(s as u64))
+          .collect(),
+      ),
+      None => (
+        vec![G::ScalarField::zero(); len],
+        vec![G::ScalarField::zero(); len],
+      ),
+    }
+  }
+
+  fn prove_helper<R: RngCore + CryptoRng>(
+    a: &MultilinearPolynomial<G::ScalarField>,
+    comm_a: &Commitment<G>,
+    b: &MultilinearPolynomial<G::ScalarField>,
+    comm_b: &Commitment<G>,
+    decomms: Option<(&Decommitment<G::ScalarField>, &Decommitment<G::ScalarField>)>,
+    sigma: Option<&[usize]>,
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Self {
+    let len = a.poly.len();
+    assert_eq!(b.poly.len(), len);
+    if let Some(sigma) = sigma {
+      assert_eq!(sigma.len(), len);
+    }
+
+    let (gamma, beta) = Self::challenges(comm_a, comm_b, sigma, transcript);
+    let (pos_a, pos_b) = Self::positions(len, sigma);
+    let fingerprints_a = Self::fingerprints(a, &pos_a, &gamma, &beta);
+    let fingerprints_b = Self::fingerprints(b, &pos_b, &gamma, &beta);
+
+    let (proof_prod, products, r, _evals) =
+      GrandProductProof::prove::<G>(&[&fingerprints_a, &fingerprints_b], transcript);
+    assert_eq!(products[0], products[1]);
+
+    let decomms = decomms.map(|(decomm_a, decomm_b)| [decomm_a, decomm_b]);
+    let (proof_eval, _evals) = BatchedEvalProof::prove(
+      &[a, b],
+      &[comm_a, comm_b],
+      decomms.as_ref().map(|decomms| &decomms[..]),
+      &[r.clone(), r],
+      gens,
+      transcript,
+      rng,
+    );
+
+    MultisetEqualityProof {
+      product: products[0],
+      proof_prod,
+      proof_eval,
+    }
+  }
+
+  fn verify_helper(
+    &self,
+    comm_a: &Commitment<G>,
+    comm_b: &Commitment<G>,
+    sigma: Option<&[usize]>,
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    let num_vars = gens.num_vars();
+    let len = 1 << num_vars;
+    if sigma.is_some_and(|sigma| sigma.len() != len) {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    let (gamma, beta) = Self::challenges(comm_a, comm_b, sigma, transcript);
+    let (claims, r) =
+      self
+        .proof_prod
+        .verify::<G>(&[self.product, self.product], len, transcript)?;
+
+    // the evaluations of the positions at r
+    let (pos_a, pos_b) = match sigma {
+      Some(sigma) => (
+        IdentityPolynomial::new(num_vars).evaluate(&r),
+        EqPolynomial::new(r.clone())
+          .evals()
+          .iter()
+          .zip(sigma.iter())
+          .map(|(eq, &s)| *eq * G::ScalarField::from(s as u64))
+          .sum(),
+      ),
+      None => (G::ScalarField::zero(), G::ScalarField::zero()),
+    };
+    let evals = [
+      gamma - beta * pos_a - claims[0],
+      gamma - beta * pos_b - claims[1],
+    ];
+
+    self
+      .proof_eval
+      .verify(&[comm_a, comm_b], &[r.clone(), r], &evals, gens, transcript)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_std::rand::{rngs::StdRng, SeedableRng};
+  use ark_std::{test_rng, One, UniformRand};
+
+  #[test]
+  fn check_grand_product() {
+    check_grand_product_helper::<G1Projective>()
+  }
+
+  fn check_grand_product_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let len = 32;
+    let polys = (0..3)
+      .map(|_| {
+        MultilinearPolynomial::new((0..len).map(|_| G::ScalarField::rand(&mut prng)).collect())
+      })
+      .collect::<Vec<_>>();
+    let polys = polys.iter().collect::<Vec<_>>();
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, products, r, evals) = GrandProductProof::prove::<G>(&polys, &mut prover_transcript);
+    for (poly, product) in polys.iter().zip(products.iter()) {
+      assert_eq!(
+        (0..len).map(|i| poly.poly[i]).product::<G::ScalarField>(),
+        *product
+      );
+    }
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (claims, r_verifier) = proof
+      .verify::<G>(&products, len, &mut verifier_transcript)
+      .unwrap();
+    assert_eq!(r, r_verifier);
+    assert_eq!(claims, evals);
+
+    // a wrong product is rejected
+    let mut wrong_products = products.clone();
+    wrong_products[1] += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify::<G>(&wrong_products, len, &mut verifier_transcript)
+      .is_err());
+  }
+
+  #[test]
+  fn check_multiset_equality() {
+    check_multiset_equality_helper::<G1Projective>()
+  }
+
+  fn check_multiset_equality_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let num_vars = 5;
+    let len = 1 << num_vars;
+    let gens = PCSGens::<G>::new(num_vars, b"test-multiset");
+
+    let values = (0..len)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    // sigma rotates the values by 3 positions
+    let sigma = (0..len).map(|j| (j + 3) % len).collect::<Vec<usize>>();
+    let a = MultilinearPolynomial::new(values.clone());
+    let b = MultilinearPolynomial::new(sigma.iter().map(|&s| values[s]).collect());
+    let (comm_a, decomm_a) = Commitment::commit_hiding(&a, &gens, &mut prng);
+    let (comm_b, decomm_b) = Commitment::commit_hiding(&b, &gens, &mut prng);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = MultisetEqualityProof::prove(
+      &a,
+      &comm_a,
+      &b,
+      &comm_b,
+      Some((&decomm_a, &decomm_b)),
+      &gens,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm_a, &comm_b, &gens, &mut verifier_transcript)
+      .is_ok());
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = MultisetEqualityProof::prove_permutation(
+      &a,
+      &comm_a,
+      &b,
+      &comm_b,
+      Some((&decomm_a, &decomm_b)),
+      &sigma,
+      &gens,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_permutation(&comm_a, &comm_b, &sigma, &gens, &mut verifier_transcript)
+      .is_ok());
+
+    // the proof is rejected for another permutation
+    let other = (0..len).map(|j| (j + 5) % len).collect::<Vec<usize>>();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_permutation(&comm_a, &comm_b, &other, &gens, &mut verifier_transcript)
+      .is_err());
+
+    // and for another vector
+    let c = MultilinearPolynomial::new((0..len).map(|_| G::ScalarField::rand(&mut prng)).collect());
+    let comm_c = Commitment::commit(&c, &gens);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_permutation(&comm_a, &comm_c, &sigma, &gens, &mut verifier_transcript)
+      .is_err());
+  }
+}
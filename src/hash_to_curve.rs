@@ -0,0 +1,243 @@
+//! Hashing to elliptic curves as specified in [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380),
+//! with which the generators of the public parameters can be derived so that any other
+//! implementation of the RFC reproduces them.
+//!
+//! The following suites are supported:
+//! - `BLS12381G1_XMD:SHA-256_SSWU_RO_` for the G1 group of BLS12-381, as specified in
+//!   Section 8.8.1 of the RFC;
+//! - `BN254G1_XMD:SHA-256_SVDW_RO_` for the G1 group of BN254, which combines
+//!   `expand_message_xmd` with SHA-256, the Shallue-van de Woestijne map of Section 6.6.1 with
+//!   `Z = 1` (the value selected by `find_z_svdw`) and `k = 128`, as implemented in
+//!   gnark-crypto; the cofactor of this group is one.
+use ark_ec::hashing::curve_maps::wb::WBMap;
+use ark_ec::hashing::map_to_curve_hasher::MapToCurve;
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField};
+use sha2::{Digest, Sha256};
+
+// the output and input block sizes of SHA-256, in bytes
+const SHA256_B_IN_BYTES: usize = 32;
+const SHA256_S_IN_BYTES: usize = 64;
+
+// the security parameter of the supported suites, in bits
+const SECURITY_PARAMETER: usize = 128;
+
+/// A group into which messages can be hashed with a suite of RFC 9380
+pub trait HashToCurveGroup: CurveGroup {
+  /// the identifier of the suite, with which domain separation tags should end
+  const SUITE_ID: &'static [u8];
+
+  /// hashes `msg` to a uniformly distributed point of the prime-order subgroup, under the
+  /// domain separation tag `dst` of at most 255 bytes (`hash_to_curve` in the RFC)
+  fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self;
+}
+
+// implemented for `Projective<Config>` rather than the `G1Projective` aliases, whose associated
+// type projections keep the two implementations from being told apart
+impl HashToCurveGroup for Projective<ark_bls12_381::g1::Config> {
+  const SUITE_ID: &'static [u8] = b"BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+  fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+    let u = hash_to_field::<ark_bls12_381::Fq>(msg, dst, 2);
+    // the simplified SWU map to a curve isogenous to G1, followed by the isogeny
+    let map = WBMap::<ark_bls12_381::g1::Config>::new().unwrap();
+    let q = map.map_to_curve(u[0]).unwrap() + map.map_to_curve(u[1]).unwrap();
+    q.into_affine().clear_cofactor().into_group()
+  }
+}
+
+impl HashToCurveGroup for Projective<ark_bn254::g1::Config> {
+  const SUITE_ID: &'static [u8] = b"BN254G1_XMD:SHA-256_SVDW_RO_";
+
+  fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+    let u = hash_to_field::<ark_bn254::Fq>(msg, dst, 2);
+    // the cofactor is one, so the sum is already in the prime-order subgroup
+    map_to_curve_svdw_bn254(u[0]) + map_to_curve_svdw_bn254(u[1])
+  }
+}
+
+// `expand_message_xmd` with SHA-256 (Section 5.3.1)
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+  let ell = len_in_bytes.div_ceil(SHA256_B_IN_BYTES);
+  assert!(ell <= 255 && len_in_bytes <= 65535 && dst.len() <= 255);
+  let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+  let b_0 = Sha256::new()
+    .chain_update([0u8; SHA256_S_IN_BYTES])
+    .chain_update(msg)
+    .chain_update((len_in_bytes as u16).to_be_bytes())
+    .chain_update([0u8])
+    .chain_update(&dst_prime)
+    .finalize();
+  let mut b_i = Sha256::new()
+    .chain_update(b_0)
+    .chain_update([1u8])
+    .chain_update(&dst_prime)
+    .finalize();
+
+  let mut uniform_bytes = b_i.to_vec();
+  for i in 2..=ell {
+    let xor = b_0
+      .iter()
+      .zip(b_i.iter())
+      .map(|(b_0, b_i)| b_0 ^ b_i)
+      .collect::<Vec<u8>>();
+    b_i = Sha256::new()
+      .chain_update(xor)
+      .chain_update([i as u8])
+      .chain_update(&dst_prime)
+      .finalize();
+    uniform_bytes.extend_from_slice(&b_i);
+  }
+  uniform_bytes.truncate(len_in_bytes);
+  uniform_bytes
+}
+
+// `hash_to_field` for a prime field (Section 5.2)
+fn hash_to_field<F: PrimeField>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F> {
+  let len_per_elem = (F::MODULUS_BIT_SIZE as usize + SECURITY_PARAMETER).div_ceil(8);
+  expand_message_xmd(msg, dst, count * len_per_elem)
+    .chunks(len_per_elem)
+    .map(F::from_be_bytes_mod_order)
+    .collect()
+}
+
+// `sgn0` for a prime field (Section 4.1)
+fn sgn0<F: PrimeField>(x: &F) -> bool {
+  x.into_bigint().is_odd()
+}
+
+// the Shallue-van de Woestijne map (Section 6.6.1) to `y^2 = x^3 + 3` with `Z = 1`
+fn map_to_curve_svdw_bn254(u: ark_bn254::Fq) -> ark_bn254::G1Projective {
+  use ark_bn254::{g1, Fq, G1Affine};
+
+  // A = 0
+  let g = |x: Fq| x.square() * x + g1::Config::COEFF_B;
+  let is_square = |x: &Fq| !x.legendre().is_qnr();
+
+  // the constants of the map for Z = 1, where 3 * Z^2 + 4 * A = 3
+  let z = Fq::ONE;
+  let three = Fq::from(3u64);
+  let c1 = g(z);
+  let c2 = -z / Fq::from(2u64);
+  let c3 = {
+    let c3 = (-c1 * three).sqrt().unwrap();
+    if sgn0(&c3) {
+      -c3
+    } else {
+      c3
+    }
+  };
+  let c4 = -c1 * Fq::from(4u64) / three;
+
+  let tv1 = u.square() * c1;
+  let tv2 = Fq::ONE + tv1;
+  let tv1 = Fq::ONE - tv1;
+  // `inv0`, which maps zero to zero
+  let tv3 = (tv1 * tv2).inverse().unwrap_or(Fq::ZERO);
+  let tv4 = u * tv1 * tv3 * c3;
+
+  let x1 = c2 - tv4;
+  let x2 = c2 + tv4;
+  let x = if is_square(&g(x1)) {
+    x1
+  } else if is_square(&g(x2)) {
+    x2
+  } else {
+    (tv2.square() * tv3).square() * c4 + z
+  };
+
+  let y = g(x).sqrt().unwrap();
+  let y = if sgn0(&u) == sgn0(&y) { y } else { -y };
+  G1Affine::new(x, y).into_group()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_ff::UniformRand;
+  use ark_std::{test_rng, Zero};
+
+  fn from_hex<F: PrimeField>(hex: &str) -> F {
+    let bytes = (0..hex.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+      .collect::<Vec<u8>>();
+    F::from_be_bytes_mod_order(&bytes)
+  }
+
+  #[test]
+  fn check_expand_message_xmd() {
+    // the test vector of Appendix K.1 for an empty message
+    let uniform_bytes = expand_message_xmd(b"", b"QUUX-V01-CS02-with-expander-SHA256-128", 0x20);
+    let expected = "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235";
+    let hex = uniform_bytes
+      .iter()
+      .map(|b| format!("{:02x}", b))
+      .collect::<String>();
+    assert_eq!(hex, expected);
+  }
+
+  #[test]
+  fn check_hash_to_curve_bls12_381() {
+    // the test vectors of Appendix J.9.1
+    let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+    let vectors = [
+      (
+        &b""[..],
+        "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+        "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+      ),
+      (
+        &b"abc"[..],
+        "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+        "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+      ),
+    ];
+    for (msg, x, y) in vectors {
+      let p = ark_bls12_381::G1Projective::hash_to_curve(msg, dst).into_affine();
+      assert_eq!(p, ark_bls12_381::G1Affine::new(from_hex(x), from_hex(y)));
+    }
+  }
+
+  #[test]
+  fn check_hash_to_curve_bn254() {
+    use ark_bn254::{g1, Fq};
+
+    // Z = 1 meets the criteria of Section H.1: g(Z) != 0, -(3 * Z^2 + 4 * A) / (4 * g(Z)) is a
+    // nonzero square, and g(Z) is a square
+    let g_z = Fq::ONE + g1::Config::COEFF_B;
+    assert!(!g_z.is_zero());
+    assert!((-Fq::from(3u64) / (Fq::from(4u64) * g_z))
+      .legendre()
+      .is_qr());
+    assert!(g_z.legendre().is_qr());
+
+    // the map sends every field element to the curve, including the exceptional ones
+    let mut prng = test_rng();
+    let exceptional = [Fq::ZERO, Fq::from(2u64).inverse().unwrap()];
+    for u in exceptional
+      .into_iter()
+      .chain(exceptional.map(|u| -u))
+      .chain((0..20).map(|_| Fq::rand(&mut prng)))
+    {
+      let p = map_to_curve_svdw_bn254(u).into_affine();
+      assert!(p.is_on_curve());
+      assert_eq!(sgn0(&u), sgn0(&p.y));
+    }
+
+    // the test vector of gnark-crypto's implementation of the suite for an empty message
+    let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+    let p = ark_bn254::G1Projective::hash_to_curve(b"", dst).into_affine();
+    let x = "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86";
+    let y = "02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5";
+    assert_eq!(p, ark_bn254::G1Affine::new(from_hex(x), from_hex(y)));
+
+    // hashing is deterministic and separated by the domain separation tag
+    let p = ark_bn254::G1Projective::hash_to_curve(b"abc", dst);
+    assert_eq!(p, ark_bn254::G1Projective::hash_to_curve(b"abc", dst));
+    assert_ne!(p, ark_bn254::G1Projective::hash_to_curve(b"abd", dst));
+    assert_ne!(p, ark_bn254::G1Projective::hash_to_curve(b"abc", b"other"));
+  }
+}
@@ -12,37 +12,78 @@ extern crate sha3;
 #[cfg(feature = "multicore")]
 extern crate rayon;
 
+pub mod bulletproofs;
+mod ccsinstance;
+mod ccsproof;
+pub mod circom_reader;
 mod commitments;
 mod dense_mlpoly;
+#[cfg(feature = "serde")]
+mod encoding;
+mod envelope;
 mod errors;
+mod fraction_tree;
+pub mod grand_product;
+pub mod hash_to_curve;
 mod math;
 mod nizk;
+mod optimizer;
+pub mod pcs;
 mod product_tree;
 mod r1csinstance;
 mod r1csproof;
 mod random;
+mod relaxed_r1cs;
+pub mod sigma;
 mod sparse_mlpoly;
-mod sumcheck;
+pub mod sumcheck;
 mod timer;
 mod transcript;
 mod unipoly;
-pub mod circom_reader;
 
 use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_serialize::*;
+use ccsproof::CCSProof;
+use commitments::GensDerivation;
 use core::cmp::max;
-use errors::{ProofVerifyError, R1CSError};
+use errors::R1CSError;
 use merlin::Transcript;
 use r1csinstance::{
   R1CSCommitment, R1CSCommitmentGens, R1CSDecommitment, R1CSEvalProof, R1CSInstance,
 };
-use r1csproof::{R1CSGens, R1CSProof};
+use r1csproof::{R1CSGens, R1CSPlainProof, R1CSProof, RelaxedR1CSProof};
 use random::RandomTape;
+use sparse_mlpoly::SparseMatPolyEvaluator;
+use std::sync::OnceLock;
 use timer::Timer;
 use transcript::{AppendToTranscript, ProofTranscript};
 
+pub use ccsinstance::CCSInstance;
+pub use commitments::{Commitments, MultiCommitGens};
+#[cfg(feature = "serde")]
+pub use encoding::ASSIGNMENT_JSON_SCHEMA;
+pub use envelope::{ProofEnvelope, ProofKind};
+pub use errors::{EnvelopeError, ProofVerifyError, SumcheckError};
+pub use hash_to_curve::HashToCurveGroup;
+pub use optimizer::{Optimization, OptimizationReport};
+pub use r1csinstance::{Constraint, WitnessRound};
+pub use r1csproof::WitnessSegment;
+pub use relaxed_r1cs::{FoldingGens, RelaxedR1CSInstance, RelaxedR1CSWitness, NIFS};
+pub use sparse_mlpoly::SparkMode;
+
+// the non-pairing curves that `libspartan` is tested over, for use without a direct dependency
+#[cfg(feature = "pallas")]
+pub use ark_pallas;
+#[cfg(feature = "secp256k1")]
+pub use ark_secp256k1;
+#[cfg(feature = "secq256k1")]
+pub use ark_secq256k1;
+#[cfg(feature = "vesta")]
+pub use ark_vesta;
+
 /// `ComputationCommitment` holds a public preprocessed NP statement (e.g., R1CS)
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct ComputationCommitment<G: CurveGroup> {
   comm: R1CSCommitment<G>,
 }
@@ -76,20 +117,13 @@ impl<F: PrimeField> Assignment<F> {
     })
   }
 
-  /// pads Assignment to the specified length
-  fn pad(&self, len: usize) -> VarsAssignment<F> {
-    // check that the new length is higher than current length
-    assert!(len > self.assignment.len());
-
-    let padded_assignment = {
-      let mut padded_assignment = self.assignment.clone();
-      padded_assignment.extend(vec![F::zero(); len - self.assignment.len()]);
-      padded_assignment
-    };
-
-    VarsAssignment {
-      assignment: padded_assignment,
+  /// pads Assignment with zeros to the specified length if it is shorter
+  fn pad(self, len: usize) -> VarsAssignment<F> {
+    let mut assignment = self.assignment;
+    if assignment.len() < len {
+      assignment.resize(len, F::zero());
     }
+    VarsAssignment { assignment }
   }
 }
 
@@ -101,8 +135,17 @@ pub type InputsAssignment<F> = Assignment<F>;
 
 /// `Instance` holds the description of R1CS matrices
 pub struct Instance<F: PrimeField> {
-  // inst is the inner R1CSInstance
-  pub inst: R1CSInstance<F>,
+  // inst is the inner R1CSInstance, which is only mutated through inst_mut so that the digest
+  // stays in sync with it
+  inst: R1CSInstance<F>,
+  // the digest of inst, computed on first use
+  digest: OnceLock<[u8; 32]>,
+}
+
+impl<G: CurveGroup> AppendToTranscript<G> for Instance<G::ScalarField> {
+  fn append_to_transcript(&self, _label: &'static [u8], transcript: &mut Transcript) {
+    transcript.append_message(b"R1CSInstance", &self.digest());
+  }
 }
 
 impl<F: PrimeField> Instance<F> {
@@ -138,8 +181,8 @@ impl<F: PrimeField> Instance<F> {
         }
 
         // ensure that num_cons_padded is power of 2
-        if num_cons.next_power_of_two() != num_cons {
-          num_cons_padded = num_cons.next_power_of_two();
+        if num_cons_padded.next_power_of_two() != num_cons_padded {
+          num_cons_padded = num_cons_padded.next_power_of_two();
         }
         num_cons_padded
       };
@@ -203,12 +246,345 @@ impl<F: PrimeField> Instance<F> {
       &C_scalar.unwrap(),
     );
 
-    Ok(Instance { inst })
+    Ok(Instance::from_r1cs_instance(inst))
+  }
+
+  /// Constructs a new `Instance` whose variables are supplied over several rounds, with
+  /// verifier challenges derived after each round (e.g., for lookups or permutation checks).
+  /// The challenges of all rounds are the last inputs of the instance, in order, so constraints
+  /// refer to them like any other input. Every round but the last must end at a multiple of
+  /// the row length of the commitment to the variables, which is
+  /// `2^(ceil(log2(num_vars_padded) / 2))`, and the last round must have no challenges.
+  pub fn new_multiround(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    rounds: &[WitnessRound],
+    A: &[(usize, usize, F)],
+    B: &[(usize, usize, F)],
+    C: &[(usize, usize, F)],
+  ) -> Result<Self, R1CSError> {
+    let mut inst = Instance::new(num_cons, num_vars, num_inputs, A, B, C)?;
+    inst.inst_mut().set_rounds(rounds)?;
+    Ok(inst)
   }
 
   pub fn from_r1cs_instance(inst: R1CSInstance<F>) -> Instance<F> {
-    Instance { inst }
-  }  
+    Instance {
+      inst,
+      digest: OnceLock::new(),
+    }
+  }
+
+  /// A digest of the instance, which identifies the circuit: the SHA3-256 hash of
+  /// `"Spartan instance"` followed by the compressed serialization of the (padded) instance.
+  /// It is computed on first use and then cached, and it is what proofs absorb into their
+  /// transcripts in place of the instance.
+  pub fn digest(&self) -> [u8; 32] {
+    *self.digest.get_or_init(|| self.inst.digest())
+  }
+
+  // the inner R1CSInstance for mutation, which discards the cached digest
+  fn inst_mut(&mut self) -> &mut R1CSInstance<F> {
+    self.digest = OnceLock::new();
+    &mut self.inst
+  }
+
+  /// Returns the (padded) number of constraints
+  pub fn get_num_cons(&self) -> usize {
+    self.inst.get_num_cons()
+  }
+
+  /// Returns the (padded) number of variables
+  pub fn get_num_vars(&self) -> usize {
+    self.inst.get_num_vars()
+  }
+
+  /// Returns the number of inputs, including the challenges of multi-round instances
+  pub fn get_num_inputs(&self) -> usize {
+    self.inst.get_num_inputs()
+  }
+
+  /// Returns the largest number of non-zero entries in any of A, B, and C, as expected by
+  /// `SNARKGens`
+  pub fn get_num_nz_entries(&self) -> usize {
+    self.inst.get_num_nz_entries()
+  }
+
+  // pads the variables of every copy and proves the satisfiability of the copies
+  #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+  fn prove_sat<G: CurveGroup<ScalarField = F>>(
+    &self,
+    vars: Vec<VarsAssignment<F>>,
+    inputs: &[InputsAssignment<F>],
+    segments: &[WitnessSegment<G>],
+    blinds: &[F],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<F>, Vec<F>) {
+    // challenges can only be derived by the multi-round prover
+    assert_eq!(self.inst.get_num_challenges(), 0);
+
+    let padded_vars = vars
+      .into_iter()
+      .map(|vars| self.pad_vars(vars).assignment)
+      .collect::<Vec<Vec<F>>>();
+    let inputs = inputs
+      .iter()
+      .map(|input| input.assignment.as_slice())
+      .collect::<Vec<&[F]>>();
+
+    R1CSProof::prove_with_segments(
+      &self.inst,
+      padded_vars,
+      &inputs,
+      segments,
+      blinds,
+      gens,
+      transcript,
+      random_tape,
+    )
+  }
+
+  // proves the satisfiability of the instance with variables supplied by `witness` in rounds
+  #[allow(clippy::type_complexity)]
+  fn prove_sat_multiround<G: CurveGroup<ScalarField = F>>(
+    &self,
+    input: &InputsAssignment<F>,
+    mut witness: impl FnMut(usize, &[F]) -> VarsAssignment<F>,
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<F>, Vec<F>) {
+    R1CSProof::prove_multiround(
+      &self.inst,
+      &input.assignment,
+      |i, challenges| witness(i, challenges).assignment,
+      gens,
+      transcript,
+      random_tape,
+    )
+  }
+
+  /// pads a variables assignment to the (padded) number of variables of the instance
+  fn pad_vars(&self, vars: VarsAssignment<F>) -> VarsAssignment<F> {
+    vars.pad(self.inst.get_num_vars())
+  }
+
+  /// Converts the R1CS instance into an equivalent `CCSInstance`
+  pub fn to_ccs(&self) -> CCSInstance<F> {
+    CCSInstance::from_r1cs(&self.inst)
+  }
+
+  /// The constraints of the instance, one per (padded) constraint. Their columns index
+  /// `z = (vars, 1, inputs)` as in `Instance::new`, with the (padded) number of variables
+  /// `self.inst.get_num_vars()`.
+  pub fn constraints(&self) -> Vec<Constraint<F>> {
+    self.inst.constraints()
+  }
+
+  /// Constructs a new `Instance` from its constraints, whose columns index
+  /// `z = (vars, 1, inputs)` as in `Instance::new`
+  pub fn from_constraints(
+    num_vars: usize,
+    num_inputs: usize,
+    constraints: &[Constraint<F>],
+  ) -> Result<Self, R1CSError> {
+    let (mut A, mut B, mut C) = (Vec::new(), Vec::new(), Vec::new());
+    for (row, constraint) in constraints.iter().enumerate() {
+      A.extend(constraint.A.iter().map(|&(col, val)| (row, col, val)));
+      B.extend(constraint.B.iter().map(|&(col, val)| (row, col, val)));
+      C.extend(constraint.C.iter().map(|&(col, val)| (row, col, val)));
+    }
+    Instance::new(constraints.len(), num_vars, num_inputs, &A, &B, &C)
+  }
+
+  /// Constructs a new `Instance` with the constraints of this one followed by `constraints`,
+  /// over the same variables and inputs. The rounds of a multi-round instance are kept.
+  pub fn add_constraints(&self, constraints: &[Constraint<F>]) -> Result<Self, R1CSError> {
+    let constraints = self
+      .constraints()
+      .into_iter()
+      .filter(|constraint| !constraint.is_empty())
+      .chain(constraints.iter().cloned())
+      .collect::<Vec<Constraint<F>>>();
+    self.with_constraints(&constraints)
+  }
+
+  /// Extracts the sub-instance made of the constraints at `rows`, in the given order, over the
+  /// same variables and inputs. The rounds of a multi-round instance are kept.
+  pub fn sub_instance(&self, rows: &[usize]) -> Result<Self, R1CSError> {
+    let all_constraints = self.constraints();
+    let constraints = rows
+      .iter()
+      .map(|&row| all_constraints.get(row).cloned())
+      .collect::<Option<Vec<Constraint<F>>>>()
+      .ok_or(R1CSError::InvalidIndex)?;
+    self.with_constraints(&constraints)
+  }
+
+  // an instance with the variables, inputs and rounds of this one, but with other constraints
+  fn with_constraints(&self, constraints: &[Constraint<F>]) -> Result<Self, R1CSError> {
+    let mut inst = Instance::from_constraints(
+      self.inst.get_num_vars(),
+      self.inst.get_num_inputs(),
+      constraints,
+    )?;
+    inst.inst_mut().set_rounds(self.inst.get_rounds())?;
+    Ok(inst)
+  }
+
+  /// Constructs a new `Instance` with `num_vars` variables, in which variable `i` of this
+  /// instance is renamed to variable `map[i]`; `map` covers the (padded) variables of this
+  /// instance, and variables renamed to the same index are identified. A permutation of the
+  /// variables is a `map` that is a permutation of `0..num_vars`. The constant and the inputs
+  /// are unchanged. The variables of a multi-round instance cannot be renamed.
+  pub fn rename_vars(&self, num_vars: usize, map: &[usize]) -> Result<Self, R1CSError> {
+    if !self.inst.get_rounds().is_empty() {
+      return Err(R1CSError::InvalidWitnessRounds);
+    }
+    if map.len() != self.inst.get_num_vars() {
+      return Err(R1CSError::InvalidNumberOfVars);
+    }
+    if map.iter().any(|&i| i >= num_vars) {
+      return Err(R1CSError::InvalidIndex);
+    }
+
+    let num_inputs = self.inst.get_num_inputs();
+    let constraints = self.relabel_constraints(|col| match map.get(col) {
+      Some(&i) => i,
+      None => col - map.len() + num_vars,
+    });
+    Instance::from_constraints(num_vars, num_inputs, &constraints)
+  }
+
+  /// Composes this instance with `other` into an `Instance` with the constraints of both, in
+  /// which variable `j` of `other` is identified with variable `i` of this instance for each
+  /// `(i, j)` in `shared`. The (padded) variables of this instance come first, followed by the
+  /// variables of `other` that are not shared, in order, and the inputs of this instance are
+  /// followed by those of `other`. Returns the composed instance and, for each (padded)
+  /// variable of `other`, its index in the composed instance. Multi-round instances cannot be
+  /// composed.
+  pub fn compose(
+    &self,
+    other: &Instance<F>,
+    shared: &[(usize, usize)],
+  ) -> Result<(Self, Vec<usize>), R1CSError> {
+    if !self.inst.get_rounds().is_empty() || !other.inst.get_rounds().is_empty() {
+      return Err(R1CSError::InvalidWitnessRounds);
+    }
+
+    let (num_vars_self, num_vars_other) = (self.inst.get_num_vars(), other.inst.get_num_vars());
+    let mut shared_map = vec![None; num_vars_other];
+    for &(i, j) in shared {
+      if i >= num_vars_self || j >= num_vars_other || shared_map[j].is_some() {
+        return Err(R1CSError::InvalidIndex);
+      }
+      shared_map[j] = Some(i);
+    }
+
+    let mut num_vars = num_vars_self;
+    let map = shared_map
+      .into_iter()
+      .map(|i| {
+        i.unwrap_or_else(|| {
+          num_vars += 1;
+          num_vars - 1
+        })
+      })
+      .collect::<Vec<usize>>();
+
+    // the constant moves to the end of the variables, and the inputs of other after those of self
+    let num_inputs_self = self.inst.get_num_inputs();
+    let constraints = self
+      .relabel_constraints(|col| {
+        if col < num_vars_self {
+          col
+        } else {
+          col - num_vars_self + num_vars
+        }
+      })
+      .into_iter()
+      .chain(other.relabel_constraints(|col| match map.get(col) {
+        Some(&i) => i,
+        None if col == num_vars_other => num_vars,
+        None => col - num_vars_other + num_vars + num_inputs_self,
+      }))
+      .collect::<Vec<Constraint<F>>>();
+
+    let num_inputs = num_inputs_self + other.inst.get_num_inputs();
+    let inst = Instance::from_constraints(num_vars, num_inputs, &constraints)?;
+    Ok((inst, map))
+  }
+
+  /// Optimizes the instance: linear constraints (those in which A or B is a constant) are
+  /// removed by substituting for one of their variables in the other constraints, duplicate
+  /// constraints are removed, and the variables that are no longer used are dropped, so that the
+  /// padded number of constraints or variables may shrink. Linear constraints over the inputs
+  /// alone are kept, as are those whose substitution would increase the number of non-zero
+  /// entries, which would make SNARKs costlier. The remaining variables keep their order and the
+  /// inputs are unchanged; `Optimization::map_vars` maps a satisfying assignment of this
+  /// instance to one of the optimized instance, and `Optimization::report` summarizes the
+  /// savings. The variables of a multi-round instance cannot be optimized.
+  pub fn optimize(&self) -> Result<(Self, Optimization), R1CSError> {
+    self.optimize_with_max_fill_in(0)
+  }
+
+  /// Optimizes the instance like `Instance::optimize`, but substitutes every linear constraint
+  /// whose substitution adds at most `max_fill_in` non-zero entries, which removes more
+  /// constraints at the cost of denser matrices; `usize::MAX` substitutes them all
+  pub fn optimize_with_max_fill_in(
+    &self,
+    max_fill_in: usize,
+  ) -> Result<(Self, Optimization), R1CSError> {
+    if !self.inst.get_rounds().is_empty() {
+      return Err(R1CSError::InvalidWitnessRounds);
+    }
+
+    let constraints = self.constraints();
+    let num_vars = self.inst.get_num_vars();
+    let num_inputs = self.inst.get_num_inputs();
+    let optimized = optimizer::optimize(num_vars, max_fill_in, &constraints);
+    let inst =
+      Instance::from_constraints(optimized.vars.len(), num_inputs, &optimized.constraints)?;
+
+    let report = OptimizationReport {
+      num_cons_before: constraints.iter().filter(|c| !c.is_empty()).count(),
+      num_cons_after: optimized.constraints.len(),
+      num_vars_before: num_vars,
+      num_vars_after: optimized.vars.len(),
+      num_cons_padded: (self.inst.get_num_cons(), inst.inst.get_num_cons()),
+      num_vars_padded: (num_vars, inst.inst.get_num_vars()),
+      num_linear_removed: optimized.num_linear_removed,
+      num_duplicates_removed: optimized.num_duplicates_removed,
+    };
+    let optimization = Optimization {
+      vars: optimized.vars,
+      report,
+    };
+    Ok((inst, optimization))
+  }
+
+  // the non-empty constraints of the instance, with every column relabeled by `relabel`
+  fn relabel_constraints(&self, relabel: impl Fn(usize) -> usize) -> Vec<Constraint<F>> {
+    let relabel_terms = |terms: Vec<(usize, F)>| {
+      terms
+        .into_iter()
+        .map(|(col, val)| (relabel(col), val))
+        .collect::<Vec<(usize, F)>>()
+    };
+    self
+      .constraints()
+      .into_iter()
+      .filter(|constraint| !constraint.is_empty())
+      .map(|constraint| Constraint {
+        A: relabel_terms(constraint.A),
+        B: relabel_terms(constraint.B),
+        C: relabel_terms(constraint.C),
+      })
+      .collect()
+  }
 
   /// Checks if a given R1CSInstance is satisfiable with a given variables and inputs assignments
   pub fn is_sat(
@@ -224,16 +600,7 @@ impl<F: PrimeField> Instance<F> {
       return Err(R1CSError::InvalidNumberOfInputs);
     }
 
-    // we might need to pad variables
-    let padded_vars = {
-      let num_padded_vars = self.inst.get_num_vars();
-      let num_vars = vars.assignment.len();
-      if num_padded_vars > num_vars {
-        vars.pad(num_padded_vars)
-      } else {
-        vars.clone()
-      }
-    };
+    let padded_vars = self.pad_vars(vars.clone());
 
     Ok(
       self
@@ -250,7 +617,7 @@ impl<F: PrimeField> Instance<F> {
   ) -> (Instance<F>, VarsAssignment<F>, InputsAssignment<F>) {
     let (inst, vars, inputs) = R1CSInstance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
     (
-      Instance { inst },
+      Instance::from_r1cs_instance(inst),
       VarsAssignment { assignment: vars },
       InputsAssignment { assignment: inputs },
     )
@@ -258,7 +625,7 @@ impl<F: PrimeField> Instance<F> {
 }
 
 /// `SNARKGens` holds public parameters for producing and verifying proofs with the Spartan SNARK
-pub struct SNARKGens<G> {
+pub struct SNARKGens<G: CurveGroup> {
   gens_r1cs_sat: R1CSGens<G>,
   gens_r1cs_eval: R1CSCommitmentGens<G>,
 }
@@ -267,21 +634,85 @@ impl<G: CurveGroup> SNARKGens<G> {
   /// Constructs a new `SNARKGens` given the size of the R1CS statement
   /// `num_nz_entries` specifies the maximum number of non-zero entries in any of the three R1CS matrices
   pub fn new(num_cons: usize, num_vars: usize, num_inputs: usize, num_nz_entries: usize) -> Self {
-    let num_vars_padded = {
-      let mut num_vars_padded = max(num_vars, num_inputs + 1);
-      if num_vars_padded != num_vars_padded.next_power_of_two() {
-        num_vars_padded = num_vars_padded.next_power_of_two();
-      }
-      num_vars_padded
-    };
+    SNARKGens::new_with_spark_mode(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_nz_entries,
+      SparkMode::default(),
+    )
+  }
+
+  /// Constructs a new `SNARKGens` whose proofs of R1CS matrix evaluations use the
+  /// memory-checking argument selected by `mode`
+  pub fn new_with_spark_mode(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+    mode: SparkMode,
+  ) -> Self {
+    SNARKGens::new_with_derivation(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_nz_entries,
+      1,
+      mode,
+      MultiCommitGens::new,
+    )
+  }
+
+  /// Constructs a new `SNARKGens` for proving `num_copies` copies of the same R1CS statement
+  /// with `SNARK::prove_uniform`. Only the witness commitment grows with `num_copies`;
+  /// the parameters for the computation commitment are those of a single copy.
+  /// `num_copies` must be a power of two.
+  pub fn new_uniform(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+    num_copies: usize,
+  ) -> Self {
+    SNARKGens::new_with_derivation(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_nz_entries,
+      num_copies,
+      SparkMode::default(),
+      MultiCommitGens::new,
+    )
+  }
+
+  // the parameters for `num_copies` copies of an R1CS statement, whose generators are derived
+  // with `derive`
+  fn new_with_derivation(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+    num_copies: usize,
+    mode: SparkMode,
+    derive: GensDerivation<G>,
+  ) -> Self {
+    assert_eq!(num_copies.next_power_of_two(), num_copies);
+    let num_vars_padded = max(num_vars, num_inputs + 1).next_power_of_two();
 
-    let gens_r1cs_sat = R1CSGens::<G>::new(b"gens_r1cs_sat", num_cons, num_vars_padded);
-    let gens_r1cs_eval = R1CSCommitmentGens::new(
+    let gens_r1cs_sat = R1CSGens::<G>::new_with_derivation(
+      b"gens_r1cs_sat",
+      num_cons,
+      num_copies * num_vars_padded,
+      derive,
+    );
+    let gens_r1cs_eval = R1CSCommitmentGens::new_with_derivation(
       b"gens_r1cs_eval",
       num_cons,
       num_vars_padded,
       num_inputs,
       num_nz_entries,
+      mode,
+      derive,
     );
     SNARKGens {
       gens_r1cs_sat,
@@ -290,6 +721,28 @@ impl<G: CurveGroup> SNARKGens<G> {
   }
 }
 
+impl<G: HashToCurveGroup> SNARKGens<G> {
+  /// Constructs a new `SNARKGens` like `SNARKGens::new`, but derives every generator with the
+  /// RFC 9380 hash-to-curve suite of the group (see `MultiCommitGens::new_hash_to_curve`), so
+  /// that the parameters can be reproduced independently from the size of the R1CS statement
+  pub fn new_hash_to_curve(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+  ) -> Self {
+    SNARKGens::new_with_derivation(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_nz_entries,
+      1,
+      SparkMode::default(),
+      MultiCommitGens::new_hash_to_curve,
+    )
+  }
+}
+
 /// `SNARK` holds a proof produced by Spartan SNARK
 #[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
 pub struct SNARK<G: CurveGroup> {
@@ -329,6 +782,123 @@ impl<G: CurveGroup> SNARK<G> {
     inputs: &InputsAssignment<G::ScalarField>,
     gens: &SNARKGens<G>,
     transcript: &mut Transcript,
+  ) -> Self {
+    SNARK::prove_uniform(
+      inst,
+      comm,
+      decomm,
+      vec![vars],
+      core::slice::from_ref(inputs),
+      gens,
+      transcript,
+    )
+  }
+
+  /// A method to produce a SNARK proof of the satisfiability of `vars.len()` copies of an
+  /// R1CS instance (data-parallel mode), given one assignment of variables and inputs per copy.
+  /// The computation commitment is that of a single copy, and `gens` must be created with
+  /// `SNARKGens::new_uniform` for the same number of copies, which must be a power of two.
+  pub fn prove_uniform(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    vars: Vec<VarsAssignment<G::ScalarField>>,
+    inputs: &[InputsAssignment<G::ScalarField>],
+    gens: &SNARKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    SNARK::prove_inner(
+      inst,
+      comm,
+      decomm,
+      gens,
+      transcript,
+      |transcript, random_tape| {
+        inst.prove_sat(
+          vars,
+          inputs,
+          &[],
+          &[],
+          &gens.gens_r1cs_sat,
+          transcript,
+          random_tape,
+        )
+      },
+    )
+  }
+
+  /// A method to produce a SNARK proof of the satisfiability of an R1CS instance that also
+  /// proves that each of `segments` is a commitment to the corresponding variables in `vars`.
+  /// `blinds` holds the blinds with which the commitments of `segments` were created.
+  #[allow(clippy::too_many_arguments)]
+  pub fn prove_with_segments(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    inputs: &InputsAssignment<G::ScalarField>,
+    segments: &[WitnessSegment<G>],
+    blinds: &[G::ScalarField],
+    gens: &SNARKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    SNARK::prove_inner(
+      inst,
+      comm,
+      decomm,
+      gens,
+      transcript,
+      |transcript, random_tape| {
+        inst.prove_sat(
+          vec![vars],
+          core::slice::from_ref(inputs),
+          segments,
+          blinds,
+          &gens.gens_r1cs_sat,
+          transcript,
+          random_tape,
+        )
+      },
+    )
+  }
+
+  /// A method to produce a SNARK proof of the satisfiability of an R1CS instance created with
+  /// `Instance::new_multiround`. In round `i`, `witness(i, challenges)` returns the variables
+  /// of the round given the challenges derived in the previous rounds. `input` excludes the
+  /// challenges.
+  pub fn prove_multiround(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    witness: impl FnMut(usize, &[G::ScalarField]) -> VarsAssignment<G::ScalarField>,
+    gens: &SNARKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    SNARK::prove_inner(
+      inst,
+      comm,
+      decomm,
+      gens,
+      transcript,
+      |transcript, random_tape| {
+        inst.prove_sat_multiround(input, witness, &gens.gens_r1cs_sat, transcript, random_tape)
+      },
+    )
+  }
+
+  // produces the proof, where `prove_sat` proves the satisfiability of the instance
+  #[allow(clippy::type_complexity)]
+  fn prove_inner(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    gens: &SNARKGens<G>,
+    transcript: &mut Transcript,
+    prove_sat: impl FnOnce(
+      &mut Transcript,
+      &mut RandomTape<G>,
+    ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>),
   ) -> Self {
     let timer_prove = Timer::new("SNARK::prove");
 
@@ -342,27 +912,7 @@ impl<G: CurveGroup> SNARK<G> {
     comm.comm.append_to_transcript(b"comm", transcript);
 
     let (r1cs_sat_proof, rx, ry) = {
-      let (proof, rx, ry) = {
-        // we might need to pad variables
-        let padded_vars = {
-          let num_padded_vars = inst.inst.get_num_vars();
-          let num_vars = vars.assignment.len();
-          if num_padded_vars > num_vars {
-            vars.pad(num_padded_vars)
-          } else {
-            vars
-          }
-        };
-
-        R1CSProof::prove(
-          &inst.inst,
-          padded_vars.assignment,
-          &inputs.assignment,
-          &gens.gens_r1cs_sat,
-          transcript,
-          &mut random_tape,
-        )
-      };
+      let (proof, rx, ry) = prove_sat(transcript, &mut random_tape);
 
       let mut proof_encoded = vec![];
       proof.serialize_compressed(&mut proof_encoded).unwrap();
@@ -417,6 +967,99 @@ impl<G: CurveGroup> SNARK<G> {
     input: &InputsAssignment<G::ScalarField>,
     transcript: &mut Transcript,
     gens: &SNARKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_uniform(comm, core::slice::from_ref(input), transcript, gens)
+  }
+
+  /// A method to verify a SNARK proof produced by `prove_uniform`, given the inputs of every copy
+  pub fn verify_uniform(
+    &self,
+    comm: &ComputationCommitment<G>,
+    inputs: &[InputsAssignment<G::ScalarField>],
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_inner(comm, transcript, gens, |proof, evals, transcript| {
+      proof.verify_with_segments(
+        comm.comm.get_num_vars(),
+        comm.comm.get_num_cons(),
+        &input_slices(inputs, comm.comm.get_num_inputs(), comm.comm.get_rounds())?,
+        &[],
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  /// A method to verify a SNARK proof produced by `prove_with_segments`, given the same `segments`
+  pub fn verify_with_segments(
+    &self,
+    comm: &ComputationCommitment<G>,
+    input: &InputsAssignment<G::ScalarField>,
+    segments: &[WitnessSegment<G>],
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_inner(comm, transcript, gens, |proof, evals, transcript| {
+      proof.verify_with_segments(
+        comm.comm.get_num_vars(),
+        comm.comm.get_num_cons(),
+        &input_slices(
+          core::slice::from_ref(input),
+          comm.comm.get_num_inputs(),
+          comm.comm.get_rounds(),
+        )?,
+        segments,
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  /// A method to verify a SNARK proof produced by `prove_multiround`, where `input` excludes
+  /// the challenges
+  pub fn verify_multiround(
+    &self,
+    comm: &ComputationCommitment<G>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let rounds = comm.comm.get_rounds();
+    let num_challenges = rounds
+      .iter()
+      .map(|round| round.num_challenges)
+      .sum::<usize>();
+    if input.assignment.len() + num_challenges != comm.comm.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    self.verify_inner(comm, transcript, gens, |proof, evals, transcript| {
+      proof.verify_multiround(
+        comm.comm.get_num_vars(),
+        comm.comm.get_num_cons(),
+        rounds,
+        &input.assignment,
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  // verifies the proof, where `verify_sat` verifies the proof of satisfiability of the instance
+  #[allow(clippy::type_complexity)]
+  fn verify_inner(
+    &self,
+    comm: &ComputationCommitment<G>,
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+    verify_sat: impl FnOnce(
+      &R1CSProof<G>,
+      &(G::ScalarField, G::ScalarField, G::ScalarField),
+      &mut Transcript,
+    ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError>,
   ) -> Result<(), ProofVerifyError> {
     let timer_verify = Timer::new("SNARK::verify");
     <Transcript as ProofTranscript<G>>::append_protocol_name(
@@ -428,15 +1071,7 @@ impl<G: CurveGroup> SNARK<G> {
     comm.comm.append_to_transcript(b"comm", transcript);
 
     let timer_sat_proof = Timer::new("verify_sat_proof");
-    assert_eq!(input.assignment.len(), comm.comm.get_num_inputs());
-    let (rx, ry) = self.r1cs_sat_proof.verify(
-      comm.comm.get_num_vars(),
-      comm.comm.get_num_cons(),
-      &input.assignment,
-      &self.inst_evals,
-      transcript,
-      &gens.gens_r1cs_sat,
-    )?;
+    let (rx, ry) = verify_sat(&self.r1cs_sat_proof, &self.inst_evals, transcript)?;
     timer_sat_proof.stop();
 
     let timer_eval_proof = Timer::new("verify_eval_proof");
@@ -458,24 +1093,133 @@ impl<G: CurveGroup> SNARK<G> {
   }
 }
 
+// the inputs of every copy of an instance whose variables are supplied at once; an instance
+// with challenges must instead be proven with the multi-round methods
+fn input_slices<'a, F: PrimeField>(
+  inputs: &'a [InputsAssignment<F>],
+  num_inputs: usize,
+  rounds: &[WitnessRound],
+) -> Result<Vec<&'a [F]>, ProofVerifyError> {
+  if rounds.iter().any(|round| round.num_challenges > 0) {
+    return Err(ProofVerifyError::InternalError);
+  }
+  inputs
+    .iter()
+    .map(|input| {
+      if input.assignment.len() != num_inputs {
+        return Err(ProofVerifyError::InternalError);
+      }
+      Ok(input.assignment.as_slice())
+    })
+    .collect()
+}
+
 /// `NIZKGens` holds public parameters for producing and verifying proofs with the Spartan NIZK
-pub struct NIZKGens<G> {
+pub struct NIZKGens<G: CurveGroup> {
   gens_r1cs_sat: R1CSGens<G>,
 }
 
 impl<G: CurveGroup> NIZKGens<G> {
   /// Constructs a new `NIZKGens` given the size of the R1CS statement
   pub fn new(num_cons: usize, num_vars: usize, num_inputs: usize) -> Self {
-    let num_vars_padded = {
-      let mut num_vars_padded = max(num_vars, num_inputs + 1);
-      if num_vars_padded != num_vars_padded.next_power_of_two() {
-        num_vars_padded = num_vars_padded.next_power_of_two();
-      }
-      num_vars_padded
-    };
-
-    let gens_r1cs_sat = R1CSGens::<G>::new(b"gens_r1cs_sat", num_cons, num_vars_padded);
-    NIZKGens { gens_r1cs_sat }
+    NIZKGens::new_with_derivation(num_cons, num_vars, num_inputs, 1, MultiCommitGens::new)
+  }
+
+  /// Constructs a new `NIZKGens` for proving `num_copies` copies of the same R1CS statement
+  /// with `NIZK::prove_uniform`. `num_copies` must be a power of two.
+  pub fn new_uniform(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_copies: usize,
+  ) -> Self {
+    NIZKGens::new_with_derivation(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_copies,
+      MultiCommitGens::new,
+    )
+  }
+
+  // the parameters for `num_copies` copies of an R1CS statement, whose generators are derived
+  // with `derive`
+  fn new_with_derivation(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_copies: usize,
+    derive: GensDerivation<G>,
+  ) -> Self {
+    assert_eq!(num_copies.next_power_of_two(), num_copies);
+    let num_vars_padded = max(num_vars, num_inputs + 1).next_power_of_two();
+
+    let gens_r1cs_sat = R1CSGens::<G>::new_with_derivation(
+      b"gens_r1cs_sat",
+      num_cons,
+      num_copies * num_vars_padded,
+      derive,
+    );
+    NIZKGens { gens_r1cs_sat }
+  }
+}
+
+impl<G: HashToCurveGroup> NIZKGens<G> {
+  /// Constructs a new `NIZKGens` like `NIZKGens::new`, but derives every generator with the
+  /// RFC 9380 hash-to-curve suite of the group (see `MultiCommitGens::new_hash_to_curve`)
+  pub fn new_hash_to_curve(num_cons: usize, num_vars: usize, num_inputs: usize) -> Self {
+    NIZKGens::new_with_derivation(
+      num_cons,
+      num_vars,
+      num_inputs,
+      1,
+      MultiCommitGens::new_hash_to_curve,
+    )
+  }
+}
+
+/// `NIZKVerifierKey` holds an `Instance` preprocessed for verifying NIZK proofs with
+/// `NIZK::verify_with_key`: its digest, its matrices in a form that evaluates them without
+/// tables of the size of the instance, and the public parameters, whose generators are cached in
+/// affine form. It makes repeated verification of proofs for the same instance faster.
+pub struct NIZKVerifierKey<G: CurveGroup> {
+  num_cons: usize,
+  num_vars: usize,
+  num_inputs: usize,
+  rounds: Vec<WitnessRound>,
+  digest: [u8; 32],
+  evaluator: SparseMatPolyEvaluator<G::ScalarField>,
+  gens: NIZKGens<G>,
+}
+
+impl<G: CurveGroup> NIZKVerifierKey<G> {
+  /// Preprocesses `inst` for verifying proofs under `gens`
+  pub fn new(inst: &Instance<G::ScalarField>, gens: NIZKGens<G>) -> Self {
+    NIZKVerifierKey {
+      num_cons: inst.inst.get_num_cons(),
+      num_vars: inst.inst.get_num_vars(),
+      num_inputs: inst.inst.get_num_inputs(),
+      rounds: inst.inst.get_rounds().to_vec(),
+      digest: inst.digest(),
+      evaluator: SparseMatPolyEvaluator::new(&inst.inst.get_matrices()),
+      gens,
+    }
+  }
+
+  /// The digest of the instance, as `Instance::digest`
+  pub fn digest(&self) -> [u8; 32] {
+    self.digest
+  }
+
+  /// The public parameters under which proofs are verified
+  pub fn gens(&self) -> &NIZKGens<G> {
+    &self.gens
+  }
+}
+
+impl<G: CurveGroup> AppendToTranscript<G> for NIZKVerifierKey<G> {
+  fn append_to_transcript(&self, _label: &'static [u8], transcript: &mut Transcript) {
+    transcript.append_message(b"R1CSInstance", &self.digest);
   }
 }
 
@@ -498,6 +1242,89 @@ impl<G: CurveGroup> NIZK<G> {
     input: &InputsAssignment<G::ScalarField>,
     gens: &NIZKGens<G>,
     transcript: &mut Transcript,
+  ) -> Self {
+    NIZK::prove_uniform(
+      inst,
+      vec![vars],
+      core::slice::from_ref(input),
+      gens,
+      transcript,
+    )
+  }
+
+  /// A method to produce a NIZK proof of the satisfiability of `vars.len()` copies of an
+  /// R1CS instance (data-parallel mode), given one assignment of variables and inputs per copy.
+  /// `gens` must be created with `NIZKGens::new_uniform` for the same number of copies.
+  pub fn prove_uniform(
+    inst: &Instance<G::ScalarField>,
+    vars: Vec<VarsAssignment<G::ScalarField>>,
+    inputs: &[InputsAssignment<G::ScalarField>],
+    gens: &NIZKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    NIZK::prove_inner(inst, transcript, |transcript, random_tape| {
+      inst.prove_sat(
+        vars,
+        inputs,
+        &[],
+        &[],
+        &gens.gens_r1cs_sat,
+        transcript,
+        random_tape,
+      )
+    })
+  }
+
+  /// A method to produce a NIZK proof of the satisfiability of an R1CS instance that also
+  /// proves that each of `segments` is a commitment to the corresponding variables in `vars`.
+  /// `blinds` holds the blinds with which the commitments of `segments` were created.
+  pub fn prove_with_segments(
+    inst: &Instance<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    segments: &[WitnessSegment<G>],
+    blinds: &[G::ScalarField],
+    gens: &NIZKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    NIZK::prove_inner(inst, transcript, |transcript, random_tape| {
+      inst.prove_sat(
+        vec![vars],
+        core::slice::from_ref(input),
+        segments,
+        blinds,
+        &gens.gens_r1cs_sat,
+        transcript,
+        random_tape,
+      )
+    })
+  }
+
+  /// A method to produce a NIZK proof of the satisfiability of an R1CS instance created with
+  /// `Instance::new_multiround`. In round `i`, `witness(i, challenges)` returns the variables
+  /// of the round given the challenges derived in the previous rounds. `input` excludes the
+  /// challenges.
+  pub fn prove_multiround(
+    inst: &Instance<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    witness: impl FnMut(usize, &[G::ScalarField]) -> VarsAssignment<G::ScalarField>,
+    gens: &NIZKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    NIZK::prove_inner(inst, transcript, |transcript, random_tape| {
+      inst.prove_sat_multiround(input, witness, &gens.gens_r1cs_sat, transcript, random_tape)
+    })
+  }
+
+  // produces the proof, where `prove_sat` proves the satisfiability of the instance
+  #[allow(clippy::type_complexity)]
+  fn prove_inner(
+    inst: &Instance<G::ScalarField>,
+    transcript: &mut Transcript,
+    prove_sat: impl FnOnce(
+      &mut Transcript,
+      &mut RandomTape<G>,
+    ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>),
   ) -> Self {
     let timer_prove = Timer::new("NIZK::prove");
     // we create a Transcript object seeded with a random F
@@ -508,30 +1335,12 @@ impl<G: CurveGroup> NIZK<G> {
       transcript,
       NIZK::<G>::protocol_name(),
     );
-    <R1CSInstance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
-      &inst.inst, b"inst", transcript,
+    <Instance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+      inst, b"inst", transcript,
     );
 
     let (r1cs_sat_proof, rx, ry) = {
-      // we might need to pad variables
-      let padded_vars = {
-        let num_padded_vars = inst.inst.get_num_vars();
-        let num_vars = vars.assignment.len();
-        if num_padded_vars > num_vars {
-          vars.pad(num_padded_vars)
-        } else {
-          vars
-        }
-      };
-
-      let (proof, rx, ry) = R1CSProof::prove(
-        &inst.inst,
-        padded_vars.assignment,
-        &input.assignment,
-        &gens.gens_r1cs_sat,
-        transcript,
-        &mut random_tape,
-      );
+      let (proof, rx, ry) = prove_sat(transcript, &mut random_tape);
 
       let mut proof_encoded = vec![];
       proof.serialize_compressed(&mut proof_encoded).unwrap();
@@ -554,6 +1363,130 @@ impl<G: CurveGroup> NIZK<G> {
     input: &InputsAssignment<G::ScalarField>,
     transcript: &mut Transcript,
     gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    self.verify_uniform(inst, core::slice::from_ref(input), transcript, gens)
+  }
+
+  /// A method to verify a NIZK proof produced by `prove_uniform`, given the inputs of every copy
+  pub fn verify_uniform(
+    &self,
+    inst: &Instance<G::ScalarField>,
+    inputs: &[InputsAssignment<G::ScalarField>],
+    transcript: &mut Transcript,
+    gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let evaluate =
+      |rx: &[G::ScalarField], ry: &[G::ScalarField]| inst.inst.evaluate_claimed(rx, ry);
+    self.verify_inner(inst, evaluate, transcript, |proof, evals, transcript| {
+      proof.verify_with_segments(
+        inst.inst.get_num_vars(),
+        inst.inst.get_num_cons(),
+        &input_slices(inputs, inst.inst.get_num_inputs(), inst.inst.get_rounds())?,
+        &[],
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  /// A method to verify a NIZK proof produced by `prove_with_segments`, given the same `segments`
+  pub fn verify_with_segments(
+    &self,
+    inst: &Instance<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    segments: &[WitnessSegment<G>],
+    transcript: &mut Transcript,
+    gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let evaluate =
+      |rx: &[G::ScalarField], ry: &[G::ScalarField]| inst.inst.evaluate_claimed(rx, ry);
+    self.verify_inner(inst, evaluate, transcript, |proof, evals, transcript| {
+      proof.verify_with_segments(
+        inst.inst.get_num_vars(),
+        inst.inst.get_num_cons(),
+        &input_slices(
+          core::slice::from_ref(input),
+          inst.inst.get_num_inputs(),
+          inst.inst.get_rounds(),
+        )?,
+        segments,
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  /// A method to verify a NIZK proof produced by `prove_multiround`, where `input` excludes
+  /// the challenges
+  pub fn verify_multiround(
+    &self,
+    inst: &Instance<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+    gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    if input.assignment.len() + inst.inst.get_num_challenges() != inst.inst.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let evaluate =
+      |rx: &[G::ScalarField], ry: &[G::ScalarField]| inst.inst.evaluate_claimed(rx, ry);
+    self.verify_inner(inst, evaluate, transcript, |proof, evals, transcript| {
+      proof.verify_multiround(
+        inst.inst.get_num_vars(),
+        inst.inst.get_num_cons(),
+        inst.inst.get_rounds(),
+        &input.assignment,
+        evals,
+        transcript,
+        &gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  /// A method to verify a NIZK proof against a `NIZKVerifierKey` for the instance, which is
+  /// faster than `verify` when proofs for the same instance are verified repeatedly
+  pub fn verify_with_key(
+    &self,
+    key: &NIZKVerifierKey<G>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    let evaluate = |rx: &[G::ScalarField], ry: &[G::ScalarField]| {
+      let evals = key.evaluator.evaluate(rx, ry)?;
+      Ok((evals[0], evals[1], evals[2]))
+    };
+    self.verify_inner(key, evaluate, transcript, |proof, evals, transcript| {
+      proof.verify_with_segments(
+        key.num_vars,
+        key.num_cons,
+        &input_slices(core::slice::from_ref(input), key.num_inputs, &key.rounds)?,
+        &[],
+        evals,
+        transcript,
+        &key.gens.gens_r1cs_sat,
+      )
+    })
+  }
+
+  // verifies the proof for the instance `inst`, whose matrices `evaluate` evaluates, where
+  // `verify_sat` verifies the proof of satisfiability of the instance
+  #[allow(clippy::type_complexity)]
+  fn verify_inner(
+    &self,
+    inst: &impl AppendToTranscript<G>,
+    evaluate: impl FnOnce(
+      &[G::ScalarField],
+      &[G::ScalarField],
+    )
+      -> Result<(G::ScalarField, G::ScalarField, G::ScalarField), ProofVerifyError>,
+    transcript: &mut Transcript,
+    verify_sat: impl FnOnce(
+      &R1CSProof<G>,
+      &(G::ScalarField, G::ScalarField, G::ScalarField),
+      &mut Transcript,
+    ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError>,
   ) -> Result<(), ProofVerifyError> {
     let timer_verify = Timer::new("NIZK::verify");
 
@@ -561,31 +1494,22 @@ impl<G: CurveGroup> NIZK<G> {
       transcript,
       NIZK::<G>::protocol_name(),
     );
-    <R1CSInstance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
-      &inst.inst, b"inst", transcript,
-    );
+    inst.append_to_transcript(b"inst", transcript);
 
     // We send evaluations of A, B, C at r = (rx, ry) as claims
     // to enable the verifier complete the first sum-check
     let timer_eval = Timer::new("eval_sparse_polys");
     let (claimed_rx, claimed_ry) = &self.r;
-    let inst_evals = inst.inst.evaluate(claimed_rx, claimed_ry);
+    let inst_evals = evaluate(claimed_rx, claimed_ry)?;
     timer_eval.stop();
 
     let timer_sat_proof = Timer::new("verify_sat_proof");
-    assert_eq!(input.assignment.len(), inst.inst.get_num_inputs());
-    let (rx, ry) = self.r1cs_sat_proof.verify(
-      inst.inst.get_num_vars(),
-      inst.inst.get_num_cons(),
-      &input.assignment,
-      &inst_evals,
-      transcript,
-      &gens.gens_r1cs_sat,
-    )?;
+    let (rx, ry) = verify_sat(&self.r1cs_sat_proof, &inst_evals, transcript)?;
 
     // verify if claimed rx and ry are correct
-    assert_eq!(rx, *claimed_rx);
-    assert_eq!(ry, *claimed_ry);
+    if rx != *claimed_rx || ry != *claimed_ry {
+      return Err(ProofVerifyError::InternalError);
+    }
     timer_sat_proof.stop();
     timer_verify.stop();
 
@@ -593,48 +1517,1430 @@ impl<G: CurveGroup> NIZK<G> {
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use ark_bls12_381::{Fr, G1Projective};
-  use ark_std::One;
-  use ark_std::Zero;
+/// `PlainSNARK` holds a proof produced by the Spartan SNARK without zero-knowledge.
+/// It is smaller and faster to produce than `SNARK`, but reveals information about the witness,
+/// so it is only suitable when the witness is public (e.g., verifiable computation).
+/// It uses the same public parameters and computation commitment as `SNARK`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct PlainSNARK<G: CurveGroup> {
+  r1cs_sat_proof: R1CSPlainProof<G>,
+  inst_evals: (G::ScalarField, G::ScalarField, G::ScalarField),
+  r1cs_eval_proof: R1CSEvalProof<G>,
+}
 
-  #[test]
-  pub fn check_snark() {
-    check_snark_helper::<G1Projective>()
+impl<G: CurveGroup> PlainSNARK<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Spartan plain SNARK proof"
   }
-  pub fn check_snark_helper<G: CurveGroup>() {
-    let num_vars = 256;
-    let num_cons = num_vars;
-    let num_inputs = 10;
 
-    // produce public generators
-    let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+  /// A method to produce a non-zero-knowledge SNARK proof of the satisfiability of an R1CS instance
+  pub fn prove(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    inputs: &InputsAssignment<G::ScalarField>,
+    gens: &SNARKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    let timer_prove = Timer::new("PlainSNARK::prove");
 
-    // produce a synthetic R1CSInstance
-    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let mut random_tape = RandomTape::<G>::new(b"proof");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PlainSNARK::<G>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
 
-    // create a commitment to R1CSInstance
-    let (comm, decomm) = SNARK::encode(&inst, &gens);
+    let (r1cs_sat_proof, rx, ry) = {
+      let padded_vars = inst.pad_vars(vars);
 
-    // produce a proof
-    let mut prover_transcript = Transcript::new(b"example");
-    let proof = SNARK::prove(
-      &inst,
-      &comm,
-      &decomm,
-      vars,
-      &inputs,
-      &gens,
-      &mut prover_transcript,
+      R1CSPlainProof::prove(
+        &inst.inst,
+        padded_vars.assignment,
+        &inputs.assignment,
+        &gens.gens_r1cs_sat,
+        transcript,
+        &mut random_tape,
+      )
+    };
+
+    let timer_eval = Timer::new("eval_sparse_polys");
+    let inst_evals = {
+      let (Ar, Br, Cr) = inst.inst.evaluate(&rx, &ry);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Ar);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Br);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Cr);
+      (Ar, Br, Cr)
+    };
+    timer_eval.stop();
+
+    let r1cs_eval_proof = R1CSEvalProof::prove(
+      &decomm.decomm,
+      &rx,
+      &ry,
+      &inst_evals,
+      &gens.gens_r1cs_eval,
+      transcript,
+      &mut random_tape,
     );
 
-    // verify the proof
-    let mut verifier_transcript = Transcript::new(b"example");
-    assert!(proof
-      .verify(&comm, &inputs, &mut verifier_transcript, &gens)
-      .is_ok());
+    timer_prove.stop();
+    PlainSNARK {
+      r1cs_sat_proof,
+      inst_evals,
+      r1cs_eval_proof,
+    }
+  }
+
+  /// A method to verify a non-zero-knowledge SNARK proof of the satisfiability of an R1CS instance
+  pub fn verify(
+    &self,
+    comm: &ComputationCommitment<G>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("PlainSNARK::verify");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PlainSNARK::<G>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+
+    assert_eq!(input.assignment.len(), comm.comm.get_num_inputs());
+    let (rx, ry) = self.r1cs_sat_proof.verify(
+      comm.comm.get_num_vars(),
+      comm.comm.get_num_cons(),
+      &input.assignment,
+      &self.inst_evals,
+      transcript,
+      &gens.gens_r1cs_sat,
+    )?;
+
+    let (Ar, Br, Cr) = &self.inst_evals;
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Ar);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Br);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Cr);
+    self.r1cs_eval_proof.verify(
+      &comm.comm,
+      &rx,
+      &ry,
+      &self.inst_evals,
+      &gens.gens_r1cs_eval,
+      transcript,
+    )?;
+    timer_verify.stop();
+    Ok(())
+  }
+}
+
+/// `RelaxedSNARK` holds a proof of satisfiability of a relaxed R1CS instance, e.g., one obtained
+/// by folding many steps of a computation with `NIFS`. It uses the computation commitment of the
+/// underlying R1CS `Instance` produced by `SNARK::encode`, and the commitments to the witness
+/// and error vector in the `RelaxedR1CSInstance`. Like `PlainSNARK`, it is not zero-knowledge.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct RelaxedSNARK<G: CurveGroup> {
+  r1cs_sat_proof: RelaxedR1CSProof<G>,
+  inst_evals: (G::ScalarField, G::ScalarField, G::ScalarField),
+  r1cs_eval_proof: R1CSEvalProof<G>,
+}
+
+impl<G: CurveGroup> RelaxedSNARK<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Spartan relaxed SNARK proof"
+  }
+
+  /// A method to produce a SNARK proof of the satisfiability of a relaxed R1CS instance
+  #[allow(clippy::too_many_arguments)]
+  pub fn prove(
+    inst: &Instance<G::ScalarField>,
+    comm: &ComputationCommitment<G>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    U: &RelaxedR1CSInstance<G>,
+    W: &RelaxedR1CSWitness<G::ScalarField>,
+    gens: &SNARKGens<G>,
+    folding_gens: &FoldingGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    let timer_prove = Timer::new("RelaxedSNARK::prove");
+
+    let mut random_tape = RandomTape::<G>::new(b"proof");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RelaxedSNARK::<G>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+
+    let (r1cs_sat_proof, rx, ry) =
+      RelaxedR1CSProof::prove(&inst.inst, U, W, folding_gens, transcript, &mut random_tape);
+
+    let timer_eval = Timer::new("eval_sparse_polys");
+    let inst_evals = {
+      let (Ar, Br, Cr) = inst.inst.evaluate(&rx, &ry);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Ar);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Br);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Cr);
+      (Ar, Br, Cr)
+    };
+    timer_eval.stop();
+
+    let r1cs_eval_proof = R1CSEvalProof::prove(
+      &decomm.decomm,
+      &rx,
+      &ry,
+      &inst_evals,
+      &gens.gens_r1cs_eval,
+      transcript,
+      &mut random_tape,
+    );
+
+    timer_prove.stop();
+    RelaxedSNARK {
+      r1cs_sat_proof,
+      inst_evals,
+      r1cs_eval_proof,
+    }
+  }
+
+  /// A method to verify a SNARK proof of the satisfiability of a relaxed R1CS instance
+  pub fn verify(
+    &self,
+    comm: &ComputationCommitment<G>,
+    U: &RelaxedR1CSInstance<G>,
+    transcript: &mut Transcript,
+    gens: &SNARKGens<G>,
+    folding_gens: &FoldingGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("RelaxedSNARK::verify");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RelaxedSNARK::<G>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+
+    if U.get_inputs().len() != comm.comm.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let (rx, ry) = self.r1cs_sat_proof.verify(
+      comm.comm.get_num_vars(),
+      comm.comm.get_num_cons(),
+      U,
+      &self.inst_evals,
+      transcript,
+      folding_gens,
+    )?;
+
+    let (Ar, Br, Cr) = &self.inst_evals;
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Ar);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Br);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Cr);
+    self.r1cs_eval_proof.verify(
+      &comm.comm,
+      &rx,
+      &ry,
+      &self.inst_evals,
+      &gens.gens_r1cs_eval,
+      transcript,
+    )?;
+    timer_verify.stop();
+    Ok(())
+  }
+}
+
+/// `PlainNIZK` holds a proof produced by the Spartan NIZK without zero-knowledge.
+/// It is smaller and faster to produce than `NIZK`, but reveals information about the witness,
+/// so it is only suitable when the witness is public (e.g., verifiable computation).
+/// It uses the same public parameters as `NIZK`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct PlainNIZK<G: CurveGroup> {
+  pub r1cs_sat_proof: R1CSPlainProof<G>,
+  pub r: (Vec<G::ScalarField>, Vec<G::ScalarField>),
+}
+
+impl<G: CurveGroup> PlainNIZK<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Spartan plain NIZK proof"
+  }
+
+  /// A method to produce a non-zero-knowledge NIZK proof of the satisfiability of an R1CS instance
+  pub fn prove(
+    inst: &Instance<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    gens: &NIZKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    let timer_prove = Timer::new("PlainNIZK::prove");
+    let mut random_tape = RandomTape::new(b"proof");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PlainNIZK::<G>::protocol_name(),
+    );
+    <Instance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+      inst, b"inst", transcript,
+    );
+
+    let padded_vars = inst.pad_vars(vars);
+
+    let (r1cs_sat_proof, rx, ry) = R1CSPlainProof::prove(
+      &inst.inst,
+      padded_vars.assignment,
+      &input.assignment,
+      &gens.gens_r1cs_sat,
+      transcript,
+      &mut random_tape,
+    );
+
+    timer_prove.stop();
+    PlainNIZK {
+      r1cs_sat_proof,
+      r: (rx, ry),
+    }
+  }
+
+  /// A method to verify a non-zero-knowledge NIZK proof of the satisfiability of an R1CS instance
+  pub fn verify(
+    &self,
+    inst: &Instance<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+    gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("PlainNIZK::verify");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      PlainNIZK::<G>::protocol_name(),
+    );
+    <Instance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+      inst, b"inst", transcript,
+    );
+
+    let (claimed_rx, claimed_ry) = &self.r;
+    let inst_evals = inst.inst.evaluate_claimed(claimed_rx, claimed_ry)?;
+
+    if input.assignment.len() != inst.inst.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let (rx, ry) = self.r1cs_sat_proof.verify(
+      inst.inst.get_num_vars(),
+      inst.inst.get_num_cons(),
+      &input.assignment,
+      &inst_evals,
+      transcript,
+      &gens.gens_r1cs_sat,
+    )?;
+
+    // verify if claimed rx and ry are correct
+    if rx != *claimed_rx || ry != *claimed_ry {
+      return Err(ProofVerifyError::InternalError);
+    }
+    timer_verify.stop();
+
+    Ok(())
+  }
+}
+
+/// `CCSPlainNIZK` holds a SuperSpartan proof of the satisfiability of a `CCSInstance`.
+/// Like `PlainNIZK`, it is not zero-knowledge, and it uses the same public parameters as `NIZK`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct CCSPlainNIZK<G: CurveGroup> {
+  pub ccs_sat_proof: CCSProof<G>,
+  pub r: (Vec<G::ScalarField>, Vec<G::ScalarField>),
+}
+
+impl<G: CurveGroup> CCSPlainNIZK<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"SuperSpartan CCS plain NIZK proof"
+  }
+
+  /// A method to produce a NIZK proof of the satisfiability of a CCS instance
+  pub fn prove(
+    inst: &CCSInstance<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    gens: &NIZKGens<G>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    let timer_prove = Timer::new("CCSPlainNIZK::prove");
+    let mut random_tape = RandomTape::new(b"proof");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      CCSPlainNIZK::<G>::protocol_name(),
+    );
+    <CCSInstance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+      inst, b"inst", transcript,
+    );
+
+    let padded_vars = vars.pad(inst.get_num_vars());
+
+    let (ccs_sat_proof, rx, ry) = CCSProof::prove(
+      inst,
+      padded_vars.assignment,
+      &input.assignment,
+      &gens.gens_r1cs_sat,
+      transcript,
+      &mut random_tape,
+    );
+
+    timer_prove.stop();
+    CCSPlainNIZK {
+      ccs_sat_proof,
+      r: (rx, ry),
+    }
+  }
+
+  /// A method to verify a NIZK proof of the satisfiability of a CCS instance
+  pub fn verify(
+    &self,
+    inst: &CCSInstance<G::ScalarField>,
+    input: &InputsAssignment<G::ScalarField>,
+    transcript: &mut Transcript,
+    gens: &NIZKGens<G>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("CCSPlainNIZK::verify");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      CCSPlainNIZK::<G>::protocol_name(),
+    );
+    <CCSInstance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+      inst, b"inst", transcript,
+    );
+
+    let (claimed_rx, claimed_ry) = &self.r;
+    let inst_evals = inst.evaluate_claimed(claimed_rx, claimed_ry)?;
+
+    if input.assignment.len() != inst.get_num_inputs() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let (rx, ry) = self.ccs_sat_proof.verify(
+      inst,
+      &input.assignment,
+      &inst_evals,
+      transcript,
+      &gens.gens_r1cs_sat,
+    )?;
+
+    // verify if claimed rx and ry are correct
+    if rx != *claimed_rx || ry != *claimed_ry {
+      return Err(ProofVerifyError::InternalError);
+    }
+    timer_verify.stop();
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::{Fr, G1Projective};
+  use ark_std::One;
+  use ark_std::UniformRand;
+  use ark_std::Zero;
+
+  #[test]
+  pub fn check_snark() {
+    check_snark_helper::<G1Projective>()
+  }
+  pub fn check_snark_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    // produce public generators
+    let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+
+    // produce a synthetic R1CSInstance
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    // create a commitment to R1CSInstance
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    // produce a proof
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    // verify the proof
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // inputs of the wrong length are rejected
+    let short_inputs = InputsAssignment::new(&inputs.assignment[1..]).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &short_inputs, &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_snark_logup() {
+    check_snark_logup_helper::<G1Projective>();
+    check_snark_logup_helper::<ark_pallas::Projective>();
+  }
+
+  pub fn check_snark_logup_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = SNARKGens::<G>::new_with_spark_mode(
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_cons,
+      SparkMode::LogUp,
+    );
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // the proof does not verify under generators for the product-tree argument
+    let gens_product_tree = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens_product_tree)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_snark_hash_to_curve() {
+    check_snark_hash_to_curve_helper::<G1Projective>();
+    check_snark_hash_to_curve_helper::<ark_bn254::G1Projective>();
+  }
+
+  pub fn check_snark_hash_to_curve_helper<G: HashToCurveGroup>() {
+    let num_vars = 16;
+    let num_cons = num_vars;
+    let num_inputs = 2;
+
+    let gens = SNARKGens::<G>::new_hash_to_curve(num_cons, num_vars, num_inputs, num_cons);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars.clone(),
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // the proof does not verify under the default generators
+    let gens_default = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens_default)
+      .is_err());
+
+    let gens = NIZKGens::<G>::new_hash_to_curve(num_cons, num_vars, num_inputs);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_snark_non_pairing_curves() {
+    check_snark_helper::<ark_secp256k1::Projective>();
+    check_snark_helper::<ark_secq256k1::Projective>();
+    check_snark_helper::<ark_pallas::Projective>();
+    check_snark_helper::<ark_vesta::Projective>();
+  }
+
+  #[test]
+  pub fn check_nizk() {
+    check_nizk_helper::<G1Projective>();
+    check_nizk_helper::<ark_bn254::G1Projective>();
+    check_nizk_helper::<ark_secq256k1::Projective>();
+    check_nizk_helper::<ark_pallas::Projective>();
+  }
+
+  pub fn check_nizk_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // inputs of the wrong length are rejected
+    let short_inputs = InputsAssignment::new(&inputs.assignment[1..]).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &short_inputs, &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_nizk_verifier_key() {
+    check_nizk_verifier_key_helper::<G1Projective>();
+    check_nizk_verifier_key_helper::<ark_bn254::G1Projective>();
+  }
+
+  pub fn check_nizk_verifier_key_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let key = NIZKVerifierKey::new(&inst, gens);
+    assert_eq!(key.digest(), inst.digest());
+
+    // the key evaluates the matrices as the instance does
+    let (rx, ry) = &proof.r;
+    let evals = key.evaluator.evaluate(rx, ry).unwrap();
+    assert_eq!((evals[0], evals[1], evals[2]), inst.inst.evaluate(rx, ry));
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_key(&key, &inputs, &mut verifier_transcript)
+      .is_ok());
+
+    // the key agrees with `verify` on a proof for other inputs
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs.assignment[0] += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_key(&key, &wrong_inputs, &mut verifier_transcript)
+      .is_err());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &wrong_inputs, &mut verifier_transcript, key.gens())
+      .is_err());
+
+    // a proof with a malformed evaluation point is rejected rather than crashing the verifier
+    let mut proof = proof;
+    proof.r.0.pop();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_key(&key, &inputs, &mut verifier_transcript)
+      .is_err());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, key.gens())
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_instance_digest() {
+    use sha3::{Digest, Sha3_256};
+
+    let (inst, vars, inputs) = Instance::<Fr>::produce_synthetic_r1cs(16, 16, 2);
+
+    // the streamed digest matches the hash of the buffered serialization
+    let mut data = b"Spartan instance".to_vec();
+    inst.inst.serialize_compressed(&mut data).unwrap();
+    assert_eq!(inst.digest().as_slice(), Sha3_256::digest(&data).as_slice());
+    assert_eq!(inst.digest(), inst.digest());
+
+    // the digest identifies the instance
+    let (same, _, _) = Instance::<Fr>::produce_synthetic_r1cs(16, 16, 2);
+    let (other, _, _) = Instance::<Fr>::produce_synthetic_r1cs(16, 16, 3);
+    assert_eq!(inst.digest(), same.digest());
+    assert_ne!(inst.digest(), other.digest());
+
+    // mutating the inner instance discards a cached digest
+    let (mut edited, _, _) = Instance::<Fr>::produce_synthetic_r1cs(16, 16, 2);
+    assert_eq!(edited.digest(), inst.digest());
+    *edited.inst_mut() = Instance::<Fr>::produce_synthetic_r1cs(16, 16, 3).0.inst;
+    assert_eq!(edited.digest(), other.digest());
+
+    // proofs absorb the digest
+    let gens = NIZKGens::<G1Projective>::new(16, 16, 2);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&same, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_instance_editing() {
+    check_instance_editing_helper::<G1Projective>()
+  }
+
+  pub fn check_instance_editing_helper<G: CurveGroup>() {
+    let (inst, vars, inputs) = Instance::<G::ScalarField>::produce_synthetic_r1cs(16, 16, 2);
+    let num_vars = inst.inst.get_num_vars();
+    let one = G::ScalarField::one();
+
+    // an instance is rebuilt from its constraints
+    let constraints = inst.constraints();
+    assert_eq!(constraints.len(), 16);
+    let rebuilt = Instance::from_constraints(num_vars, 2, &constraints).unwrap();
+    assert_eq!(rebuilt.constraints(), constraints);
+    assert!(rebuilt.is_sat(&vars, &inputs).unwrap());
+
+    // added constraints must hold as well: z[0] * 1 = z[0], then z[0] * z[0] = 0
+    let holds = Constraint {
+      A: vec![(0, one)],
+      B: vec![(num_vars, one)],
+      C: vec![(0, one)],
+    };
+    let fails = Constraint {
+      A: vec![(0, one)],
+      B: vec![(0, one)],
+      C: vec![],
+    };
+    let extended = inst.add_constraints(std::slice::from_ref(&holds)).unwrap();
+    assert!(extended.is_sat(&vars, &inputs).unwrap());
+    let extended = extended.add_constraints(&[fails]).unwrap();
+    assert!(!extended.is_sat(&vars, &inputs).unwrap());
+    assert_eq!(extended.constraints()[16], holds);
+
+    // sub-instances without the failing constraint are satisfied
+    let sub = extended.sub_instance(&[16, 0, 3]).unwrap();
+    assert_eq!(sub.constraints()[0], holds);
+    assert!(sub.is_sat(&vars, &inputs).unwrap());
+    assert!(!extended
+      .sub_instance(&[17])
+      .unwrap()
+      .is_sat(&vars, &inputs)
+      .unwrap());
+    assert!(extended.sub_instance(&[32]).is_err());
+
+    // permuting the variables permutes the assignment
+    let map = (0..num_vars).rev().collect::<Vec<usize>>();
+    let permuted = inst.rename_vars(num_vars, &map).unwrap();
+    let mut permuted_vars = vars.clone();
+    permuted_vars.assignment.reverse();
+    assert!(permuted.is_sat(&permuted_vars, &inputs).unwrap());
+    assert!(!permuted.is_sat(&vars, &inputs).unwrap());
+    assert!(inst.rename_vars(num_vars, &map[1..]).is_err());
+    assert!(inst.rename_vars(num_vars - 1, &map).is_err());
+
+    // composing instances without shared variables concatenates the assignments
+    let (other, other_vars, other_inputs) =
+      Instance::<G::ScalarField>::produce_synthetic_r1cs(8, 8, 1);
+    let (composed, map) = inst.compose(&other, &[]).unwrap();
+    assert_eq!(map, (num_vars..num_vars + 8).collect::<Vec<usize>>());
+    let composed_vars =
+      Assignment::new(&[vars.assignment.clone(), other_vars.assignment].concat()).unwrap();
+    let composed_inputs =
+      Assignment::new(&[inputs.assignment.clone(), other_inputs.assignment].concat()).unwrap();
+    assert!(composed.is_sat(&composed_vars, &composed_inputs).unwrap());
+
+    // composing an instance with itself over shared variables keeps the variables
+    let shared = (0..num_vars).map(|i| (i, i)).collect::<Vec<_>>();
+    let (doubled, map) = inst.compose(&inst, &shared).unwrap();
+    assert_eq!(map, (0..num_vars).collect::<Vec<usize>>());
+    assert_eq!(doubled.inst.get_num_vars(), num_vars);
+    let doubled_inputs =
+      Assignment::new(&[inputs.assignment.clone(), inputs.assignment.clone()].concat()).unwrap();
+    assert!(doubled.is_sat(&vars, &doubled_inputs).unwrap());
+    assert!(inst.compose(&inst, &[(0, 0), (1, 0)]).is_err());
+
+    // composed instances are proved like any other
+    let gens = NIZKGens::<G>::new(
+      composed.inst.get_num_cons(),
+      composed.inst.get_num_vars(),
+      composed.inst.get_num_inputs(),
+    );
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(
+      &composed,
+      composed_vars,
+      &composed_inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&composed, &composed_inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_plain_snark() {
+    check_plain_snark_helper::<G1Projective>()
+  }
+
+  pub fn check_plain_snark_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = SNARKGens::<G>::new(num_cons, num_vars, num_inputs, num_cons);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = PlainSNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars.clone(),
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // the plain proof is smaller than the zero-knowledge one
+    let mut prover_transcript = Transcript::new(b"example");
+    let zk_proof = SNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+    assert!(proof.compressed_size() < zk_proof.compressed_size());
+  }
+
+  #[test]
+  pub fn check_plain_nizk() {
+    check_plain_nizk_helper::<G1Projective>()
+  }
+
+  pub fn check_plain_nizk_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = PlainNIZK::prove(&inst, vars.clone(), &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // the proof is bound to the verifier's transcript
+    let mut verifier_transcript = Transcript::new(b"other");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_err());
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let vars_plain = vars.clone();
+    let zk_proof = NIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+    assert!(proof.compressed_size() < zk_proof.compressed_size());
+    assert_ne!(PlainNIZK::<G>::protocol_name(), NIZK::<G>::protocol_name());
+
+    // the verifier binds the transcript to its own protocol name: the same proof steps carried
+    // out under the name of the zero-knowledge variant are rejected
+    let prove_as = |name: &'static [u8]| {
+      let mut prover_transcript = Transcript::new(b"example");
+      <Transcript as ProofTranscript<G>>::append_protocol_name(&mut prover_transcript, name);
+      <Instance<G::ScalarField> as AppendToTranscript<G>>::append_to_transcript(
+        &inst,
+        b"inst",
+        &mut prover_transcript,
+      );
+      let (r1cs_sat_proof, rx, ry) = R1CSPlainProof::prove(
+        &inst.inst,
+        vars_plain.assignment.clone(),
+        &inputs.assignment,
+        &gens.gens_r1cs_sat,
+        &mut prover_transcript,
+        &mut RandomTape::new(b"proof"),
+      );
+      PlainNIZK {
+        r1cs_sat_proof,
+        r: (rx, ry),
+      }
+    };
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(prove_as(PlainNIZK::<G>::protocol_name())
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(prove_as(NIZK::<G>::protocol_name())
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_ccs_plain_nizk() {
+    check_ccs_plain_nizk_helper::<G1Projective>()
+  }
+
+  pub fn check_ccs_plain_nizk_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = NIZKGens::<G>::new(num_cons, num_vars, num_inputs);
+
+    // a degree-3 instance
+    let (inst, vars, inputs) = CCSInstance::produce_synthetic_ccs(num_cons, num_vars, num_inputs);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = CCSPlainNIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // a proof is rejected against different public inputs
+    let mut bad_inputs = inputs.clone();
+    bad_inputs.assignment[0] += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &bad_inputs, &mut verifier_transcript, &gens)
+      .is_err());
+
+    // an R1CS instance converted to CCS
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let inst = inst.to_ccs();
+    assert_eq!(inst.get_degree(), 2);
+    assert!(inst.is_sat(&vars, &inputs).unwrap());
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = CCSPlainNIZK::prove(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  // the R1CS for x^3 + x + 5 = y, and satisfying assignments for x = 1, 2, ...
+  fn produce_cubic_r1cs<F: PrimeField>(
+    num_copies: usize,
+  ) -> (
+    Instance<F>,
+    Vec<VarsAssignment<F>>,
+    Vec<InputsAssignment<F>>,
+  ) {
+    let one = F::one();
+    let (num_cons, num_vars, num_inputs) = (4, 4, 1);
+    // constraints: Z0 * Z0 - Z1 = 0, Z1 * Z0 - Z2 = 0, (Z2 + Z0) * 1 - Z3 = 0, (Z3 + 5) * 1 - I0 = 0
+    let A = vec![
+      (0, 0, one),
+      (1, 1, one),
+      (2, 2, one),
+      (2, 0, one),
+      (3, 3, one),
+      (3, num_vars, F::from(5u64)),
+    ];
+    let B = vec![
+      (0, 0, one),
+      (1, 0, one),
+      (2, num_vars, one),
+      (3, num_vars, one),
+    ];
+    let C = vec![
+      (0, 1, one),
+      (1, 2, one),
+      (2, 3, one),
+      (3, num_vars + 1, one),
+    ];
+    let inst = Instance::new(num_cons, num_vars, num_inputs, &A, &B, &C).unwrap();
+
+    let (mut vars, mut inputs) = (Vec::new(), Vec::new());
+    for k in 0..num_copies {
+      let z0 = F::from(k as u64 + 1);
+      let (z1, z2) = (z0 * z0, z0 * z0 * z0);
+      let z3 = z2 + z0;
+      vars.push(VarsAssignment::new(&[z0, z1, z2, z3]).unwrap());
+      inputs.push(InputsAssignment::new(&[z3 + F::from(5u64)]).unwrap());
+      assert!(inst.is_sat(&vars[k], &inputs[k]).unwrap());
+    }
+    (inst, vars, inputs)
+  }
+
+  #[test]
+  pub fn check_snark_uniform() {
+    check_snark_uniform_helper::<G1Projective>()
+  }
+
+  pub fn check_snark_uniform_helper<G: CurveGroup>() {
+    let num_copies = 8;
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(num_copies);
+
+    // the computation commitment is that of a single copy
+    let gens = SNARKGens::<G>::new_uniform(4, 4, 1, 6, num_copies);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove_uniform(
+      &inst,
+      &comm,
+      &decomm,
+      vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_uniform(&comm, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // changing the public inputs of a single copy makes verification fail
+    let mut bad_inputs = inputs.clone();
+    bad_inputs[3].assignment[0] += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_uniform(&comm, &bad_inputs, &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_nizk_uniform() {
+    check_nizk_uniform_helper::<G1Projective>()
+  }
+
+  pub fn check_nizk_uniform_helper<G: CurveGroup>() {
+    let num_copies = 4;
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(num_copies);
+    let gens = NIZKGens::<G>::new_uniform(4, 4, 1, num_copies);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove_uniform(&inst, vars, &inputs, &gens, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_uniform(&inst, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // proofs are bound to the number of copies
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_uniform(&inst, &inputs[..3], &mut verifier_transcript, &gens)
+      .is_err());
+
+    // a single copy is the same as the non-uniform NIZK
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(1);
+    let gens = NIZKGens::<G>::new(4, 4, 1);
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove_uniform(&inst, vars, &inputs, &gens, &mut prover_transcript);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&inst, &inputs[0], &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_snark_segments() {
+    check_snark_segments_helper::<G1Projective>()
+  }
+
+  pub fn check_snark_segments_helper<G: CurveGroup>() {
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(1);
+    let gens = SNARKGens::<G>::new(4, 4, 1, 6);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    // an external Pedersen commitment to the variables Z2 and Z3
+    let mut prng = ark_std::test_rng();
+    let gens_segment = MultiCommitGens::<G>::new(2, b"external");
+    let blind = G::ScalarField::rand(&mut prng);
+    let segment_vals = &vars[0].assignment[2..4];
+    let segment = WitnessSegment {
+      start: 2,
+      gens: &gens_segment,
+      comm: Commitments::batch_commit(segment_vals, &blind, &gens_segment),
+    };
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove_with_segments(
+      &inst,
+      &comm,
+      &decomm,
+      vars[0].clone(),
+      &inputs[0],
+      core::slice::from_ref(&segment),
+      &[blind],
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_segments(
+        &comm,
+        &inputs[0],
+        core::slice::from_ref(&segment),
+        &mut verifier_transcript,
+        &gens
+      )
+      .is_ok());
+
+    // the proof does not verify against a commitment to other values
+    let bad_segment = WitnessSegment {
+      start: 2,
+      gens: &gens_segment,
+      comm: Commitments::batch_commit(
+        &[segment_vals[0], segment_vals[1] + G::ScalarField::one()],
+        &blind,
+        &gens_segment,
+      ),
+    };
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_segments(
+        &comm,
+        &inputs[0],
+        core::slice::from_ref(&bad_segment),
+        &mut verifier_transcript,
+        &gens
+      )
+      .is_err());
+
+    // nor without the segment
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &inputs[0], &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_nizk_segments() {
+    check_nizk_segments_helper::<G1Projective>()
+  }
+
+  pub fn check_nizk_segments_helper<G: CurveGroup>() {
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(1);
+    let gens = NIZKGens::<G>::new(4, 4, 1);
+
+    // an external Pedersen commitment to the variables Z0 and Z1
+    let mut prng = ark_std::test_rng();
+    let gens_segment = MultiCommitGens::<G>::new(2, b"external");
+    let blind = G::ScalarField::rand(&mut prng);
+    let segment = WitnessSegment {
+      start: 0,
+      gens: &gens_segment,
+      comm: Commitments::batch_commit(&vars[0].assignment[0..2], &blind, &gens_segment),
+    };
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove_with_segments(
+      &inst,
+      vars[0].clone(),
+      &inputs[0],
+      core::slice::from_ref(&segment),
+      &[blind],
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_segments(
+        &inst,
+        &inputs[0],
+        core::slice::from_ref(&segment),
+        &mut verifier_transcript,
+        &gens
+      )
+      .is_ok());
+
+    // segments that are not aligned to their length are rejected
+    let misaligned_segment = WitnessSegment {
+      start: 1,
+      gens: &gens_segment,
+      comm: segment.comm,
+    };
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_with_segments(
+        &inst,
+        &inputs[0],
+        core::slice::from_ref(&misaligned_segment),
+        &mut verifier_transcript,
+        &gens
+      )
+      .is_err());
+  }
+
+  // an instance proving that (Z2, Z3) is a permutation of (Z0, Z1): after the prover commits to
+  // them in the first round, a challenge gamma is derived, and the second round supplies
+  // Z4 = (Z0 + gamma) * (Z1 + gamma) and Z5 = (Z2 + gamma) * (Z3 + gamma), which must be equal
+  fn produce_permutation_r1cs<F: PrimeField>() -> Instance<F> {
+    let one = F::one();
+    let (num_cons, num_vars, num_inputs) = (3, 6, 1);
+    let gamma = num_vars + 1;
+    let A = vec![
+      (0, 0, one),
+      (0, gamma, one),
+      (1, 2, one),
+      (1, gamma, one),
+      (2, 4, one),
+    ];
+    let B = vec![
+      (0, 1, one),
+      (0, gamma, one),
+      (1, 3, one),
+      (1, gamma, one),
+      (2, num_vars, one),
+    ];
+    let C = vec![(0, 4, one), (1, 5, one), (2, 5, one)];
+    let rounds = [
+      WitnessRound {
+        num_vars: 4,
+        num_challenges: 1,
+      },
+      WitnessRound {
+        num_vars: 2,
+        num_challenges: 0,
+      },
+    ];
+    Instance::new_multiround(num_cons, num_vars, num_inputs, &rounds, &A, &B, &C).unwrap()
+  }
+
+  fn permutation_witness<F: PrimeField>(
+    values: [u64; 4],
+  ) -> impl FnMut(usize, &[F]) -> VarsAssignment<F> {
+    let values = values.map(F::from);
+    move |round, challenges| {
+      if round == 0 {
+        VarsAssignment::new(&values).unwrap()
+      } else {
+        let gamma = challenges[0];
+        VarsAssignment::new(&[
+          (values[0] + gamma) * (values[1] + gamma),
+          (values[2] + gamma) * (values[3] + gamma),
+        ])
+        .unwrap()
+      }
+    }
+  }
+
+  #[test]
+  pub fn check_snark_multiround() {
+    check_snark_multiround_helper::<G1Projective>()
+  }
+
+  pub fn check_snark_multiround_helper<G: CurveGroup>() {
+    let inst = produce_permutation_r1cs::<G::ScalarField>();
+    let gens = SNARKGens::<G>::new(3, 6, 1, 5);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+    let input = InputsAssignment::new(&[]).unwrap();
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove_multiround(
+      &inst,
+      &comm,
+      &decomm,
+      &input,
+      permutation_witness([3, 5, 5, 3]),
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_multiround(&comm, &input, &mut verifier_transcript, &gens)
+      .is_ok());
+
+    // the challenges are not inputs the verifier may choose
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comm,
+        &InputsAssignment::new(&[G::ScalarField::one()]).unwrap(),
+        &mut verifier_transcript,
+        &gens
+      )
+      .is_err());
+
+    // (5, 4) is not a permutation of (3, 5)
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::prove_multiround(
+      &inst,
+      &comm,
+      &decomm,
+      &input,
+      permutation_witness([3, 5, 5, 4]),
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_multiround(&comm, &input, &mut verifier_transcript, &gens)
+      .is_err());
+  }
+
+  #[test]
+  pub fn check_nizk_multiround() {
+    check_nizk_multiround_helper::<G1Projective>()
+  }
+
+  pub fn check_nizk_multiround_helper<G: CurveGroup>() {
+    let inst = produce_permutation_r1cs::<G::ScalarField>();
+    let gens = NIZKGens::<G>::new(3, 6, 1);
+    let input = InputsAssignment::new(&[]).unwrap();
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove_multiround(
+      &inst,
+      &input,
+      permutation_witness([7, 2, 2, 7]),
+      &gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify_multiround(&inst, &input, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+
+  #[test]
+  pub fn check_multiround_instance() {
+    // with 8 variables, the commitment to the variables has rows of length 4, so the
+    // first round cannot end after 3 variables
+    let one = Fr::one();
+    let A = vec![(0, 0, one)];
+    let rounds = [
+      WitnessRound {
+        num_vars: 3,
+        num_challenges: 1,
+      },
+      WitnessRound {
+        num_vars: 3,
+        num_challenges: 0,
+      },
+    ];
+    assert!(Instance::new_multiround(1, 6, 1, &rounds, &A, &A, &A).is_err());
+
+    // the last round cannot have challenges
+    let rounds = [WitnessRound {
+      num_vars: 6,
+      num_challenges: 1,
+    }];
+    assert!(Instance::new_multiround(1, 6, 1, &rounds, &A, &A, &A).is_err());
+  }
+
+  #[test]
+  pub fn check_relaxed_snark() {
+    check_relaxed_snark_helper::<G1Projective>()
+  }
+
+  pub fn check_relaxed_snark_helper<G: CurveGroup>() {
+    let num_steps = 4;
+    let (inst, vars, inputs) = produce_cubic_r1cs::<G::ScalarField>(num_steps);
+
+    let gens = SNARKGens::<G>::new(4, 4, 1, 6);
+    let folding_gens = FoldingGens::<G>::new(4, 4, 1);
+    let (comm, decomm) = SNARK::encode(&inst, &gens);
+
+    // fold all steps into a single relaxed instance
+    let mut prover_transcript = Transcript::new(b"example");
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (mut U, mut W) =
+      RelaxedR1CSInstance::from_r1cs(&inst, vars[0].clone(), &inputs[0], &folding_gens).unwrap();
+    let mut U_verifier = U.clone();
+    for k in 1..num_steps {
+      let (U_k, W_k) =
+        RelaxedR1CSInstance::from_r1cs(&inst, vars[k].clone(), &inputs[k], &folding_gens).unwrap();
+      let (proof, U_next, W_next) = NIFS::prove(
+        &inst,
+        &U,
+        &W,
+        &U_k,
+        &W_k,
+        &folding_gens,
+        &mut prover_transcript,
+      );
+      U_verifier = proof
+        .verify(&U_verifier, &U_k, &mut verifier_transcript)
+        .unwrap();
+      U = U_next;
+      W = W_next;
+    }
+    assert_eq!(U, U_verifier);
+    assert!(U.is_sat(&inst, &W, &folding_gens).unwrap());
+
+    // compress the folded instance into a single SNARK
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = RelaxedSNARK::prove(
+      &inst,
+      &comm,
+      &decomm,
+      &U,
+      &W,
+      &gens,
+      &folding_gens,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &U, &mut verifier_transcript, &gens, &folding_gens)
+      .is_ok());
+
+    // the proof is bound to the relaxed instance
+    let (U_first, _W_first) =
+      RelaxedR1CSInstance::from_r1cs(&inst, vars[0].clone(), &inputs[0], &folding_gens).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comm,
+        &U_first,
+        &mut verifier_transcript,
+        &gens,
+        &folding_gens
+      )
+      .is_err());
   }
 
   #[test]
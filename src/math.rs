@@ -1,4 +1,5 @@
 pub trait Math {
+  #[allow(dead_code)]
   fn square_root(self) -> usize;
   fn pow2(self) -> usize;
   fn get_bits(self, num_bits: usize) -> Vec<bool>;
@@ -20,7 +21,7 @@ impl Math for usize {
   /// Returns the num_bits from n in a canonical order
   fn get_bits(self, num_bits: usize) -> Vec<bool> {
     (0..num_bits)
-      .map(|shift_amount| ((self & (1 << (num_bits - shift_amount - 1))) > 0))
+      .map(|shift_amount| (self & (1 << (num_bits - shift_amount - 1))) > 0)
       .collect::<Vec<bool>>()
   }
 
@@ -6,10 +6,10 @@
 use super::super::errors::ProofVerifyError;
 use super::super::math::Math;
 use super::super::transcript::ProofTranscript;
-use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::{Field, PrimeField};
 use ark_serialize::*;
-use ark_std::One;
+use ark_std::{One, Zero};
 use core::iter;
 use merlin::Transcript;
 
@@ -32,83 +32,113 @@ impl<G: CurveGroup> BulletReductionProof<G> {
   /// either 0 or a power of 2.
   pub fn prove(
     transcript: &mut Transcript,
-    Q: &G,
-    G_vec: &[G],
-    H: &G,
+    Q: &G::Affine,
+    G_vec: &[G::Affine],
+    H: &G::Affine,
     a_vec: &[G::ScalarField],
     b_vec: &[G::ScalarField],
     blind: &G::ScalarField,
     blinds_vec: &[(G::ScalarField, G::ScalarField)],
   ) -> (Self, G, G::ScalarField, G::ScalarField, G, G::ScalarField) {
+    let (proof, a, b, G_hat, blind_fin) = BulletReductionProof::fold(
+      transcript,
+      Q,
+      G_vec,
+      &[],
+      a_vec,
+      b_vec,
+      Some((H, blind, blinds_vec)),
+    );
+
+    let Gamma_hat = G_hat * a + *Q * (a * b) + *H * blind_fin;
+
+    (proof, Gamma_hat, a, b, G_hat.into_group(), blind_fin)
+  }
+
+  // The rounds of the reduction. The vector b is public when `H_vec` is empty, and is
+  // otherwise committed to under `H_vec`, which is folded along with it. With `blinding`,
+  // the cross terms are blinded under its base and the blind of the folded commitment is
+  // returned, which is zero otherwise.
+  fn fold(
+    transcript: &mut Transcript,
+    Q: &G::Affine,
+    G_vec: &[G::Affine],
+    H_vec: &[G::Affine],
+    a_vec: &[G::ScalarField],
+    b_vec: &[G::ScalarField],
+    blinding: Option<(
+      &G::Affine,
+      &G::ScalarField,
+      &[(G::ScalarField, G::ScalarField)],
+    )>,
+  ) -> (
+    Self,
+    G::ScalarField,
+    G::ScalarField,
+    G::Affine,
+    G::ScalarField,
+  ) {
     // Create slices G, H, a, b backed by their respective
     // vectors.  This lets us reslice as we compress the lengths
-    // of the vectors in the main loop below.
-    let mut G: &mut [G] = &mut G_vec.to_owned()[..];
+    // of the vectors in the main loop below. The generators stay in
+    // affine form, so that they are normalized once per round as they
+    // are folded rather than before every multiscalar multiplication.
+    let mut G: &mut [G::Affine] = &mut G_vec.to_owned()[..];
+    let mut H: &mut [G::Affine] = &mut H_vec.to_owned()[..];
     let mut a: &mut [G::ScalarField] = &mut a_vec.to_owned()[..];
     let mut b: &mut [G::ScalarField] = &mut b_vec.to_owned()[..];
 
     // All of the input vectors must have a length that is a power of two.
     let mut n = G.len();
     assert!(n.is_power_of_two());
-    let lg_n = n.log_2() as usize;
+    let lg_n = n.log_2();
 
     // All of the input vectors must have the same length.
     assert_eq!(G.len(), n);
     assert_eq!(a.len(), n);
     assert_eq!(b.len(), n);
-    assert_eq!(blinds_vec.len(), 2 * lg_n);
+    assert!(H.is_empty() || H.len() == n);
 
     let mut L_vec = Vec::with_capacity(lg_n);
     let mut R_vec = Vec::with_capacity(lg_n);
-    let mut blinds_iter = blinds_vec.iter();
-    let mut blind_fin = *blind;
+    let H_blind = blinding.map(|(H_blind, _, _)| H_blind);
+    let mut blind_fin = blinding.map_or(G::ScalarField::zero(), |(_, blind, _)| *blind);
+    let mut blinds_iter = blinding.map(|(_, _, blinds_vec)| {
+      assert_eq!(blinds_vec.len(), 2 * lg_n);
+      blinds_vec.iter()
+    });
 
     while n != 1 {
       n /= 2;
       let (a_L, a_R) = a.split_at_mut(n);
       let (b_L, b_R) = b.split_at_mut(n);
       let (G_L, G_R) = G.split_at_mut(n);
+      let (H_L, H_R) = H.split_at_mut(H.len() / 2);
 
       let c_L = inner_product(a_L, b_R);
       let c_R = inner_product(a_R, b_L);
 
-      let (blind_L, blind_R) = blinds_iter.next().unwrap();
-
-      let scalars = a_L
-        .iter()
-        .chain(iter::once(&c_L))
-        .chain(iter::once(blind_L))
-        .copied()
-        .collect::<Vec<_>>();
-
-      let bases = G_R
-        .iter()
-        .chain(iter::once(Q))
-        .chain(iter::once(H))
-        .copied()
-        .collect::<Vec<_>>();
-
-      let bases = G::normalize_batch(bases.as_ref());
-
-      let L = VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap();
-
-      let scalars = a_R
-        .iter()
-        .chain(iter::once(&c_R))
-        .chain(iter::once(blind_R))
-        .copied()
-        .collect::<Vec<_>>();
-
-      let bases = G_L
-        .iter()
-        .chain(iter::once(Q))
-        .chain(iter::once(H))
-        .copied()
-        .collect::<Vec<_>>();
-
-      let bases = G::normalize_batch(bases.as_ref());
-
-      let R = VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap();
+      let blinds = blinds_iter.as_mut().map(|iter| iter.next().unwrap());
+
+      // b only enters the cross terms when it is committed to under H
+      let L = cross_term::<G>(
+        a_L,
+        G_R,
+        &b_R[..H_L.len()],
+        H_L,
+        &c_L,
+        Q,
+        H_blind.zip(blinds.map(|(blind_L, _)| blind_L)),
+      );
+      let R = cross_term::<G>(
+        a_R,
+        G_L,
+        &b_L[..H_R.len()],
+        H_R,
+        &c_R,
+        Q,
+        H_blind.zip(blinds.map(|(_, blind_R)| blind_R)),
+      );
 
       <Transcript as ProofTranscript<G>>::append_point(transcript, b"L", &L);
       <Transcript as ProofTranscript<G>>::append_point(transcript, b"R", &R);
@@ -120,11 +150,13 @@ impl<G: CurveGroup> BulletReductionProof<G> {
       for i in 0..n {
         a_L[i] = a_L[i] * u + u_inv * a_R[i];
         b_L[i] = b_L[i] * u_inv + u * b_R[i];
-
-        G_L[i] = G_L[i] * u_inv + G_R[i] * u;
       }
+      G_L.copy_from_slice(&fold_bases::<G>(G_L, G_R, &u_inv, &u));
+      H_L.copy_from_slice(&fold_bases::<G>(H_L, H_R, &u, &u_inv));
 
-      blind_fin = blind_fin + *blind_L * u * u + *blind_R * u_inv * u_inv;
+      if let Some((blind_L, blind_R)) = blinds {
+        blind_fin = blind_fin + *blind_L * u * u + *blind_R * u_inv * u_inv;
+      }
 
       L_vec.push(L);
       R_vec.push(R);
@@ -132,13 +164,11 @@ impl<G: CurveGroup> BulletReductionProof<G> {
       a = a_L;
       b = b_L;
       G = G_L;
+      H = H_L;
     }
 
-    let Gamma_hat = G[0] * a[0] + *Q * a[0] * b[0] + *H * blind_fin;
-
     (
       BulletReductionProof { L_vec, R_vec },
-      Gamma_hat,
       a[0],
       b[0],
       G[0],
@@ -146,10 +176,10 @@ impl<G: CurveGroup> BulletReductionProof<G> {
     )
   }
 
-  /// Computes three vectors of verification scalars \\([u\_{i}^{2}]\\), \\([u\_{i}^{-2}]\\) and \\([s\_{i}]\\) for combined multiscalar multiplication
-  /// in a parent protocol. See [inner product protocol notes](index.html#verification-equation) for details.
-  /// The verifier must provide the input length \\(n\\) explicitly to avoid unbounded allocation within the inner product proof.
-  fn verification_scalars(
+  /// Computes the verification scalars \\([u\_{i}^{2}]\\), \\([u\_{i}^{-2}]\\) and \\([s\_{i}]\\),
+  /// so that the parent protocol can fold the checks of the reduction into its own
+  /// multiscalar multiplication.
+  pub fn verification_scalars(
     &self,
     n: usize,
     transcript: &mut Transcript,
@@ -161,92 +191,203 @@ impl<G: CurveGroup> BulletReductionProof<G> {
     ),
     ProofVerifyError,
   > {
-    let lg_n = self.L_vec.len();
-    if lg_n >= 32 {
-      // 4 billion multiplications should be enough for anyone
-      // and this check prevents overflow in 1<<lg_n below.
-      return Err(ProofVerifyError::InternalError);
-    }
-    if n != (1 << lg_n) {
-      return Err(ProofVerifyError::InternalError);
-    }
+    verification_scalars::<G>(&self.L_vec, &self.R_vec, n, transcript)
+  }
 
-    // 1. Recompute x_k,...,x_1 based on the proof transcript
-    let mut challenges = Vec::with_capacity(lg_n);
-    for (L, R) in self.L_vec.iter().zip(self.R_vec.iter()) {
-      <Transcript as ProofTranscript<G>>::append_point(transcript, b"L", L);
-      <Transcript as ProofTranscript<G>>::append_point(transcript, b"R", R);
-      challenges.push(<Transcript as ProofTranscript<G>>::challenge_scalar(
-        transcript, b"u",
-      ));
-    }
+  /// The cross terms \\(L\_i\\) of the rounds, in creation order
+  pub fn L_vec(&self) -> &[G] {
+    &self.L_vec
+  }
 
-    // 2. Compute 1/(u_k...u_1) and 1/u_k, ..., 1/u_1
-    // let mut challenges_inv = challenges.clone();
-    let mut challenges_inv = challenges
-      .iter()
-      .map(|x| x.inverse().unwrap())
-      .collect::<Vec<_>>();
-    let mut all_inv = G::ScalarField::one();
-    challenges_inv.iter().for_each(|c| all_inv *= *c);
+  /// The cross terms \\(R\_i\\) of the rounds, in creation order
+  pub fn R_vec(&self) -> &[G] {
+    &self.R_vec
+  }
+}
 
-    // 3. Compute u_i^2 and (1/u_i)^2
-    for i in 0..lg_n {
-      challenges[i] = challenges[i].square();
-      challenges_inv[i] = challenges_inv[i].square();
-    }
-    let challenges_sq = challenges;
-    let challenges_inv_sq = challenges_inv;
-
-    // 4. Compute s values inductively.
-    let mut s = vec![all_inv];
-    for i in 1..n {
-      let lg_i = (32 - 1 - (i as u32).leading_zeros()) as usize;
-      let k = 1 << lg_i;
-      // The challenges are stored in "creation order" as [u_k,...,u_1],
-      // so u_{lg(i)+1} = is indexed by (lg_n-1) - lg_i
-      let u_lg_i_sq = challenges_sq[(lg_n - 1) - lg_i];
-      s.push(s[i - k] * u_lg_i_sq);
-    }
+/// An inner-product argument in which both vectors are secret, as used by
+/// the range proof: it shows knowledge of \\(\mathbf{a}, \mathbf{b}\\) with
+/// \\(P = \langle \mathbf{a}, \mathbf{G} \rangle + \langle \mathbf{b}, \mathbf{H} \rangle + \langle \mathbf{a}, \mathbf{b} \rangle \cdot Q\\).
+/// It runs the rounds of `BulletReductionProof` with \\(\mathbf{b}\\) committed under \\(\mathbf{H}\\).
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BulletInnerProductProof<G: CurveGroup> {
+  reduction: BulletReductionProof<G>,
+  a: G::ScalarField,
+  b: G::ScalarField,
+}
 
-    Ok((challenges_sq, challenges_inv_sq, s))
+impl<G: CurveGroup> BulletInnerProductProof<G> {
+  /// Create an inner-product proof with respect to the bases \\(\mathbf{G}\\),
+  /// \\(\mathbf{H}\\) and \\(Q\\).
+  ///
+  /// The lengths of the vectors must all be the same and a power of 2.
+  pub fn prove(
+    transcript: &mut Transcript,
+    Q: &G::Affine,
+    G_vec: &[G::Affine],
+    H_vec: &[G::Affine],
+    a_vec: &[G::ScalarField],
+    b_vec: &[G::ScalarField],
+  ) -> Self {
+    assert_eq!(H_vec.len(), G_vec.len());
+    let (reduction, a, b, _G_hat, _blind_fin) =
+      BulletReductionProof::fold(transcript, Q, G_vec, H_vec, a_vec, b_vec, None);
+
+    BulletInnerProductProof { reduction, a, b }
   }
 
-  /// This method is for testing that proof generation work,
-  /// but for efficiency the actual protocols would use `verification_scalars`
-  /// method to combine inner product verification with other checks
-  /// in a single multiscalar multiplication.
+  /// Verifies the proof against \\(P\\) using a single multiscalar multiplication.
   pub fn verify(
     &self,
     n: usize,
-    a: &[G::ScalarField],
     transcript: &mut Transcript,
-    Gamma: &G,
-    G: &[G],
-  ) -> Result<(G, G, G::ScalarField), ProofVerifyError> {
-    let (u_sq, u_inv_sq, s) = self.verification_scalars(n, transcript)?;
+    P: &G,
+    Q: &G::Affine,
+    G_vec: &[G::Affine],
+    H_vec: &[G::Affine],
+  ) -> Result<(), ProofVerifyError> {
+    if G_vec.len() != n || H_vec.len() != n {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let (u_sq, u_inv_sq, s) = self.reduction.verification_scalars(n, transcript)?;
 
-    let group_element = G::normalize_batch(G);
+    // the folded H uses the inverses of the s values, which are s in reverse
+    let ab = self.a * self.b;
+    let scalars = s
+      .iter()
+      .map(|s_i| self.a * s_i)
+      .chain(s.iter().rev().map(|s_i| self.b * s_i))
+      .chain(iter::once(ab))
+      .chain(u_sq.iter().map(|u| -*u))
+      .chain(u_inv_sq.iter().map(|u| -*u))
+      .collect::<Vec<_>>();
+    let L_R = G::normalize_batch(&[self.reduction.L_vec(), self.reduction.R_vec()].concat());
+    let bases = G_vec
+      .iter()
+      .chain(H_vec.iter())
+      .chain(iter::once(Q))
+      .chain(L_R.iter())
+      .copied()
+      .collect::<Vec<_>>();
+    let expected: G = VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap();
 
-    let G_hat = VariableBaseMSM::msm(group_element.as_ref(), s.as_ref()).unwrap();
+    if expected == *P {
+      Ok(())
+    } else {
+      Err(ProofVerifyError::InternalError)
+    }
+  }
+}
 
-    let a_hat = inner_product(a, &s);
+// a cross term <a, G> + <b, H> + c * Q + blind * H_blind of a round of the reduction
+fn cross_term<G: CurveGroup>(
+  a: &[G::ScalarField],
+  G: &[G::Affine],
+  b: &[G::ScalarField],
+  H: &[G::Affine],
+  c: &G::ScalarField,
+  Q: &G::Affine,
+  blind: Option<(&G::Affine, &G::ScalarField)>,
+) -> G {
+  let scalars = a
+    .iter()
+    .chain(b.iter())
+    .chain(iter::once(c))
+    .chain(blind.map(|(_, blind)| blind))
+    .copied()
+    .collect::<Vec<_>>();
+
+  let bases = G
+    .iter()
+    .chain(H.iter())
+    .chain(iter::once(Q))
+    .chain(blind.map(|(H_blind, _)| H_blind))
+    .copied()
+    .collect::<Vec<_>>();
+
+  VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap()
+}
 
-    let bases = G::normalize_batch(
-      [self.L_vec.as_slice(), self.R_vec.as_slice(), &[*Gamma]]
-        .concat()
-        .as_ref(),
-    );
-    let scalars = u_sq
-      .into_iter()
-      .chain(u_inv_sq.into_iter())
-      .chain([G::ScalarField::one()])
-      .collect::<Vec<_>>();
+// folds the bases of a round into x * left + y * right, normalized in a single batch
+fn fold_bases<G: CurveGroup>(
+  left: &[G::Affine],
+  right: &[G::Affine],
+  x: &G::ScalarField,
+  y: &G::ScalarField,
+) -> Vec<G::Affine> {
+  let folded = left
+    .iter()
+    .zip(right.iter())
+    .map(|(l, r)| *l * x + *r * y)
+    .collect::<Vec<G>>();
+  G::normalize_batch(&folded)
+}
 
-    let Gamma_hat = VariableBaseMSM::msm(bases.as_ref(), scalars.as_ref()).unwrap();
+/// Computes three vectors of verification scalars \\([u\_{i}^{2}]\\), \\([u\_{i}^{-2}]\\) and \\([s\_{i}]\\) for combined multiscalar multiplication
+/// in a parent protocol. See [inner product protocol notes](index.html#verification-equation) for details.
+/// The verifier must provide the input length \\(n\\) explicitly to avoid unbounded allocation within the inner product proof.
+fn verification_scalars<G: CurveGroup>(
+  L_vec: &[G],
+  R_vec: &[G],
+  n: usize,
+  transcript: &mut Transcript,
+) -> Result<
+  (
+    Vec<G::ScalarField>,
+    Vec<G::ScalarField>,
+    Vec<G::ScalarField>,
+  ),
+  ProofVerifyError,
+> {
+  let lg_n = L_vec.len();
+  if lg_n >= 32 {
+    // 4 billion multiplications should be enough for anyone
+    // and this check prevents overflow in 1<<lg_n below.
+    return Err(ProofVerifyError::InternalError);
+  }
+  if n != (1 << lg_n) || R_vec.len() != lg_n {
+    return Err(ProofVerifyError::InternalError);
+  }
 
-    Ok((G_hat, Gamma_hat, a_hat))
+  // 1. Recompute x_k,...,x_1 based on the proof transcript
+  let mut challenges = Vec::with_capacity(lg_n);
+  for (L, R) in L_vec.iter().zip(R_vec.iter()) {
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"L", L);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"R", R);
+    challenges.push(<Transcript as ProofTranscript<G>>::challenge_scalar(
+      transcript, b"u",
+    ));
   }
+
+  // 2. Compute 1/(u_k...u_1) and 1/u_k, ..., 1/u_1
+  // let mut challenges_inv = challenges.clone();
+  let mut challenges_inv = challenges
+    .iter()
+    .map(|x| x.inverse().unwrap())
+    .collect::<Vec<_>>();
+  let mut all_inv = G::ScalarField::one();
+  challenges_inv.iter().for_each(|c| all_inv *= *c);
+
+  // 3. Compute u_i^2 and (1/u_i)^2
+  for i in 0..lg_n {
+    challenges[i] = challenges[i].square();
+    challenges_inv[i] = challenges_inv[i].square();
+  }
+  let challenges_sq = challenges;
+  let challenges_inv_sq = challenges_inv;
+
+  // 4. Compute s values inductively.
+  let mut s = vec![all_inv];
+  for i in 1..n {
+    let lg_i = (32 - 1 - (i as u32).leading_zeros()) as usize;
+    let k = 1 << lg_i;
+    // The challenges are stored in "creation order" as [u_k,...,u_1],
+    // so u_{lg(i)+1} = is indexed by (lg_n-1) - lg_i
+    let u_lg_i_sq = challenges_sq[(lg_n - 1) - lg_i];
+    s.push(s[i - k] * u_lg_i_sq);
+  }
+
+  Ok((challenges_sq, challenges_inv_sq, s))
 }
 
 /// Computes an inner product of two vectors
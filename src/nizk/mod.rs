@@ -1,11 +1,15 @@
 #![allow(clippy::too_many_arguments)]
-use super::commitments::{Commitments, MultiCommitGens};
+use super::commitments::{Commitments, GensDerivation, MultiCommitGens};
 use super::errors::ProofVerifyError;
 use super::math::Math;
 use super::random::RandomTape;
 use super::transcript::ProofTranscript;
-use ark_ec::CurveGroup;
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::UniformRand;
 use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::{One, Zero};
+pub use bullet::BulletInnerProductProof;
 use bullet::BulletReductionProof;
 use merlin::Transcript;
 mod bullet;
@@ -58,6 +62,19 @@ impl<G: CurveGroup> KnowledgeProof<G> {
     transcript: &mut Transcript,
     C: &G,
   ) -> Result<(), ProofVerifyError> {
+    let c = self.challenge(transcript, C);
+
+    let lhs = self.z1.commit(&self.z2, gens_n);
+    let rhs = *C * c + self.alpha;
+
+    if lhs == rhs {
+      Ok(())
+    } else {
+      Err(ProofVerifyError::InternalError)
+    }
+  }
+
+  fn challenge(&self, transcript: &mut Transcript, C: &G) -> G::ScalarField {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
       KnowledgeProof::<G>::protocol_name(),
@@ -66,16 +83,30 @@ impl<G: CurveGroup> KnowledgeProof<G> {
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"C", C);
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &self.alpha);
 
-    let c = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
-
-    let lhs = self.z1.commit(&self.z2, gens_n);
-    let rhs = *C * c + self.alpha;
+    <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c")
+  }
 
-    if lhs == rhs {
-      Ok(())
-    } else {
-      Err(ProofVerifyError::InternalError)
+  /// verifies proofs that were produced one after the other on the same transcript,
+  /// combining their checks with random weights into one multiscalar multiplication
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G)],
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    let mut bases = vec![gens_n.G()[0], gens_n.h()];
+    let mut scalars = vec![G::ScalarField::zero(); 2];
+    for (proof, C) in proofs {
+      let c = proof.challenge(transcript, C);
+      let w = G::ScalarField::rand(rng);
+      // w * (z1 * g + z2 * h - c * C - alpha) = 0
+      scalars[0] += w * proof.z1;
+      scalars[1] += w * proof.z2;
+      bases.extend([**C, proof.alpha]);
+      scalars.extend([-w * c, -w]);
     }
+
+    check_msm_is_zero(&bases, &scalars)
   }
 }
 
@@ -113,7 +144,7 @@ impl<G: CurveGroup> EqualityProof<G> {
     let C2 = v2.commit(s2, gens_n);
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"C2", &C2);
 
-    let alpha = gens_n.h * r;
+    let alpha = gens_n.mul_h(&r);
 
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &alpha);
 
@@ -131,23 +162,14 @@ impl<G: CurveGroup> EqualityProof<G> {
     C1: &G,
     C2: &G,
   ) -> Result<(), ProofVerifyError> {
-    <Transcript as ProofTranscript<G>>::append_protocol_name(
-      transcript,
-      EqualityProof::<G>::protocol_name(),
-    );
-
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"C1", C1);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"C2", C2);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &self.alpha);
-
-    let c = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
+    let c = self.challenge(transcript, C1, C2);
 
     let rhs = {
       let C = *C1 - *C2;
       C * c + self.alpha
     };
 
-    let lhs = gens_n.h * self.z;
+    let lhs = gens_n.mul_h(&self.z);
 
     if lhs == rhs {
       Ok(())
@@ -155,6 +177,41 @@ impl<G: CurveGroup> EqualityProof<G> {
       Err(ProofVerifyError::InternalError)
     }
   }
+
+  fn challenge(&self, transcript: &mut Transcript, C1: &G, C2: &G) -> G::ScalarField {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      EqualityProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"C1", C1);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"C2", C2);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &self.alpha);
+
+    <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c")
+  }
+
+  /// verifies proofs that were produced one after the other on the same transcript,
+  /// combining their checks with random weights into one multiscalar multiplication
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G, &G)],
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    let mut bases = vec![gens_n.h()];
+    let mut scalars = vec![G::ScalarField::zero()];
+    for (proof, C1, C2) in proofs {
+      let c = proof.challenge(transcript, C1, C2);
+      let w = G::ScalarField::rand(rng);
+      // w * (z * h - c * (C1 - C2) - alpha) = 0
+      scalars[0] += w * proof.z;
+      bases.extend([**C1, **C2, proof.alpha]);
+      scalars.extend([-w * c, w * c, -w]);
+    }
+
+    check_msm_is_zero(&bases, &scalars)
+  }
 }
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
@@ -209,11 +266,7 @@ impl<G: CurveGroup> ProductProof<G> {
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"beta", &beta);
 
     let delta = {
-      let gens_X = &MultiCommitGens {
-        n: 1,
-        G: vec![X],
-        h: gens_n.h,
-      };
+      let gens_X = &MultiCommitGens::from_generators(vec![X], gens_n.h());
       b3.commit(&b5, gens_X)
     };
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &delta);
@@ -262,25 +315,13 @@ impl<G: CurveGroup> ProductProof<G> {
     Y: &G,
     Z: &G,
   ) -> Result<(), ProofVerifyError> {
-    <Transcript as ProofTranscript<G>>::append_protocol_name(
-      transcript,
-      ProductProof::<G>::protocol_name(),
-    );
-
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"X", X);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"Y", Y);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"Z", Z);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &self.alpha);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"beta", &self.beta);
-    <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &self.delta);
-
     let z1 = self.z[0];
     let z2 = self.z[1];
     let z3 = self.z[2];
     let z4 = self.z[3];
     let z5 = self.z[4];
 
-    let c = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
+    let c = self.challenge(transcript, X, Y, Z);
 
     if ProductProof::check_equality(&self.alpha, X, &c, gens_n, &z1, &z2)
       && ProductProof::check_equality(&self.beta, Y, &c, gens_n, &z3, &z4)
@@ -288,11 +329,7 @@ impl<G: CurveGroup> ProductProof<G> {
         &self.delta,
         Z,
         &c,
-        &MultiCommitGens {
-          n: 1,
-          G: vec![*X],
-          h: gens_n.h,
-        },
+        &MultiCommitGens::from_generators(vec![*X], gens_n.h()),
         &z3,
         &z5,
       )
@@ -302,6 +339,51 @@ impl<G: CurveGroup> ProductProof<G> {
       Err(ProofVerifyError::InternalError)
     }
   }
+
+  fn challenge(&self, transcript: &mut Transcript, X: &G, Y: &G, Z: &G) -> G::ScalarField {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      ProductProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"X", X);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"Y", Y);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"Z", Z);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"alpha", &self.alpha);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"beta", &self.beta);
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &self.delta);
+
+    <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c")
+  }
+
+  /// verifies proofs that were produced one after the other on the same transcript,
+  /// combining their checks with random weights into one multiscalar multiplication
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G, &G, &G)],
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    let mut bases = vec![gens_n.G()[0], gens_n.h()];
+    let mut scalars = vec![G::ScalarField::zero(); 2];
+    for (proof, X, Y, Z) in proofs {
+      let c = proof.challenge(transcript, X, Y, Z);
+      let [z1, z2, z3, z4, z5] = proof.z;
+      let (w1, w2, w3) = (
+        G::ScalarField::rand(rng),
+        G::ScalarField::rand(rng),
+        G::ScalarField::rand(rng),
+      );
+      // w1 * (z1 * g + z2 * h - alpha - c * X) + w2 * (z3 * g + z4 * h - beta - c * Y)
+      //   + w3 * (z3 * X + z5 * h - delta - c * Z) = 0
+      scalars[0] += w1 * z1 + w2 * z3;
+      scalars[1] += w1 * z2 + w2 * z4 + w3 * z5;
+      bases.extend([**X, **Y, **Z, proof.alpha, proof.beta, proof.delta]);
+      scalars.extend([w3 * z3 - w1 * c, -w2 * c, -w3 * c, -w1, -w2, -w3]);
+    }
+
+    check_msm_is_zero(&bases, &scalars)
+  }
 }
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
@@ -341,8 +423,8 @@ impl<G: CurveGroup> DotProductProof<G> {
 
     let n = x_vec.len();
     assert_eq!(x_vec.len(), a_vec.len());
-    assert_eq!(gens_n.n, a_vec.len());
-    assert_eq!(gens_1.n, 1);
+    assert_eq!(gens_n.n(), a_vec.len());
+    assert_eq!(gens_1.n(), 1);
 
     // produce randomness for the proofs
     let d_vec = random_tape.random_vector(b"d_vec", n);
@@ -396,9 +478,31 @@ impl<G: CurveGroup> DotProductProof<G> {
     Cx: &G,
     Cy: &G,
   ) -> Result<(), ProofVerifyError> {
-    assert_eq!(gens_n.n, a.len());
-    assert_eq!(gens_1.n, 1);
+    assert_eq!(gens_n.n(), a.len());
+    assert_eq!(gens_1.n(), 1);
+
+    let c = self.challenge(transcript, a, Cx, Cy);
+
+    let mut result =
+      *Cx * c + self.delta == Commitments::batch_commit(self.z.as_ref(), &self.z_delta, gens_n);
+
+    let dotproduct_z_a = DotProductProof::<G>::compute_dotproduct(&self.z, a);
+    result &= *Cy * c + self.beta == dotproduct_z_a.commit(&self.z_beta, gens_1);
+
+    if result {
+      Ok(())
+    } else {
+      Err(ProofVerifyError::InternalError)
+    }
+  }
 
+  fn challenge(
+    &self,
+    transcript: &mut Transcript,
+    a: &[G::ScalarField],
+    Cx: &G,
+    Cy: &G,
+  ) -> G::ScalarField {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
       DotProductProof::<G>::protocol_name(),
@@ -411,15 +515,137 @@ impl<G: CurveGroup> DotProductProof<G> {
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &self.delta);
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"beta", &self.beta);
 
-    let c = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
+    <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c")
+  }
 
-    let mut result =
-      *Cx * c + self.delta == Commitments::batch_commit(self.z.as_ref(), &self.z_delta, gens_n);
+  /// verifies proofs that were produced one after the other on the same transcript,
+  /// combining their checks with random weights into one multiscalar multiplication
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &[G::ScalarField], &G, &G)],
+    gens_1: &MultiCommitGens<G>,
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    if gens_1.n() != 1 {
+      return Err(ProofVerifyError::InternalError);
+    }
 
-    let dotproduct_z_a = DotProductProof::<G>::compute_dotproduct(&self.z, a);
-    result &= *Cy * c + self.beta == dotproduct_z_a.commit(&self.z_beta, gens_1);
+    let n = gens_n.n();
+    let mut bases = [gens_n.G(), &[gens_n.h(), gens_1.G()[0], gens_1.h()]].concat();
+    let mut scalars = vec![G::ScalarField::zero(); n + 3];
+    for (proof, a, Cx, Cy) in proofs {
+      if a.len() != n || proof.z.len() != n {
+        return Err(ProofVerifyError::InternalError);
+      }
+      let c = proof.challenge(transcript, a, Cx, Cy);
+      let (w1, w2) = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+      // w1 * (<z, G> + z_delta * h - c * Cx - delta)
+      //   + w2 * (<z, a> * g + z_beta * h_1 - c * Cy - beta) = 0
+      for (scalar, z) in scalars.iter_mut().zip(proof.z.iter()) {
+        *scalar += w1 * z;
+      }
+      scalars[n] += w1 * proof.z_delta;
+      scalars[n + 1] += w2 * DotProductProof::<G>::compute_dotproduct(&proof.z, a);
+      scalars[n + 2] += w2 * proof.z_beta;
+      bases.extend([**Cx, proof.delta, **Cy, proof.beta]);
+      scalars.extend([-w1 * c, -w1, -w2 * c, -w2]);
+    }
 
-    if result {
+    check_msm_is_zero(&bases, &scalars)
+  }
+}
+
+/// A proof of knowledge of an opening of one of several commitments, without revealing which
+/// one, obtained by composing `KnowledgeProof`s with an OR: the prover simulates the proofs
+/// for the other commitments and splits the challenge among all of them.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct OrKnowledgeProof<G: CurveGroup> {
+  c: Vec<G::ScalarField>,
+  z1: Vec<G::ScalarField>,
+  z2: Vec<G::ScalarField>,
+}
+
+impl<G: CurveGroup> OrKnowledgeProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"or knowledge proof"
+  }
+
+  pub fn prove(
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+    comms: &[G],
+    index: usize,
+    x: &G::ScalarField,
+    r: &G::ScalarField,
+  ) -> Self {
+    let m = comms.len();
+    assert!(index < m);
+    assert_eq!(comms[index], x.commit(r, gens_n));
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      OrKnowledgeProof::<G>::protocol_name(),
+    );
+    <Transcript as ProofTranscript<G>>::append_points(transcript, b"C", comms);
+
+    // produce the randomness of the real proof and the simulated transcripts
+    let t1 = random_tape.random_scalar(b"t1");
+    let t2 = random_tape.random_scalar(b"t2");
+    let mut c = random_tape.random_vector(b"c", m);
+    let mut z1 = random_tape.random_vector(b"z1", m);
+    let mut z2 = random_tape.random_vector(b"z2", m);
+
+    let alpha = (0..m)
+      .map(|i| {
+        if i == index {
+          t1.commit(&t2, gens_n)
+        } else {
+          z1[i].commit(&z2[i], gens_n) - comms[i] * c[i]
+        }
+      })
+      .collect::<Vec<G>>();
+    <Transcript as ProofTranscript<G>>::append_points(transcript, b"alpha", &alpha);
+
+    let challenge = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
+
+    c[index] = challenge
+      - (0..m)
+        .filter(|i| *i != index)
+        .map(|i| c[i])
+        .sum::<G::ScalarField>();
+    z1[index] = *x * c[index] + t1;
+    z2[index] = *r * c[index] + t2;
+
+    OrKnowledgeProof { c, z1, z2 }
+  }
+
+  pub fn verify(
+    &self,
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    comms: &[G],
+  ) -> Result<(), ProofVerifyError> {
+    let m = comms.len();
+    if m == 0 || self.c.len() != m || self.z1.len() != m || self.z2.len() != m {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      OrKnowledgeProof::<G>::protocol_name(),
+    );
+    <Transcript as ProofTranscript<G>>::append_points(transcript, b"C", comms);
+
+    let alpha = (0..m)
+      .map(|i| self.z1[i].commit(&self.z2[i], gens_n) - comms[i] * self.c[i])
+      .collect::<Vec<G>>();
+    <Transcript as ProofTranscript<G>>::append_points(transcript, b"alpha", &alpha);
+
+    let challenge = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
+
+    if self.c.iter().sum::<G::ScalarField>() == challenge {
       Ok(())
     } else {
       Err(ProofVerifyError::InternalError)
@@ -427,16 +653,47 @@ impl<G: CurveGroup> DotProductProof<G> {
   }
 }
 
-pub struct DotProductProofGens<G> {
+fn check_msm_is_zero<G: CurveGroup>(
+  bases: &[G],
+  scalars: &[G::ScalarField],
+) -> Result<(), ProofVerifyError> {
+  let result: G = VariableBaseMSM::msm(G::normalize_batch(bases).as_ref(), scalars).unwrap();
+  if result.is_zero() {
+    Ok(())
+  } else {
+    Err(ProofVerifyError::InternalError)
+  }
+}
+
+pub struct DotProductProofGens<G: CurveGroup> {
   n: usize,
   pub gens_n: MultiCommitGens<G>,
   pub gens_1: MultiCommitGens<G>,
+  // `gens_n.G`, `gens_1.G[0]` and the blinding generator in affine form, normalized once here
+  // rather than in every proof
+  bases: Vec<G::Affine>,
 }
 
 impl<G: CurveGroup> DotProductProofGens<G> {
   pub fn new(n: usize, label: &[u8]) -> Self {
-    let (gens_n, gens_1) = MultiCommitGens::new(n + 1, label).split_at(n);
-    DotProductProofGens { n, gens_n, gens_1 }
+    DotProductProofGens::new_with_derivation(n, label, MultiCommitGens::new)
+  }
+
+  pub(crate) fn new_with_derivation(n: usize, label: &[u8], derive: GensDerivation<G>) -> Self {
+    let (gens_n, gens_1) = derive(n + 1, label).split_at(n);
+    DotProductProofGens::from_gens(gens_n, gens_1)
+  }
+
+  /// generators made of `gens_n` and `gens_1`, which must share the blinding generator
+  pub fn from_gens(gens_n: MultiCommitGens<G>, gens_1: MultiCommitGens<G>) -> Self {
+    assert_eq!(gens_n.h(), gens_1.h());
+    let bases = G::normalize_batch(&[gens_n.G(), &[gens_1.G()[0], gens_1.h()]].concat());
+    DotProductProofGens {
+      n: gens_n.n(),
+      gens_n,
+      gens_1,
+      bases,
+    }
   }
 }
 
@@ -476,7 +733,7 @@ impl<G: CurveGroup> DotProductProofLog<G> {
 
     let n = x_vec.len();
     assert_eq!(x_vec.len(), a_vec.len());
-    assert_eq!(gens.n, n);
+    assert!(gens.n >= n);
 
     // produce randomness for generating a proof
     let d = random_tape.random_scalar(b"d");
@@ -490,7 +747,15 @@ impl<G: CurveGroup> DotProductProofLog<G> {
         .collect::<Vec<(G::ScalarField, G::ScalarField)>>()
     };
 
-    let Cx = Commitments::batch_commit(x_vec, blind_x, &gens.gens_n);
+    // the proof uses the first n generators of gens_n
+    let (G_vec, g, h) = (
+      &gens.bases[..n],
+      &gens.bases[gens.n],
+      &gens.bases[gens.n + 1],
+    );
+
+    let Cx: G =
+      VariableBaseMSM::msm(&[G_vec, &[*h]].concat(), &[x_vec, &[*blind_x]].concat()).unwrap();
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"Cx", &Cx);
 
     let Cy = y.commit(blind_y, &gens.gens_1);
@@ -502,9 +767,9 @@ impl<G: CurveGroup> DotProductProofLog<G> {
     let (bullet_reduction_proof, _Gamma_hat, x_hat, a_hat, g_hat, rhat_Gamma) =
       BulletReductionProof::prove(
         transcript,
-        &gens.gens_1.G[0],
-        &gens.gens_n.G,
-        &gens.gens_n.h,
+        g,
+        G_vec,
+        h,
         x_vec,
         a_vec,
         &blind_Gamma,
@@ -513,11 +778,7 @@ impl<G: CurveGroup> DotProductProofLog<G> {
     let y_hat = x_hat * a_hat;
 
     let delta = {
-      let gens_hat = MultiCommitGens {
-        n: 1,
-        G: vec![g_hat],
-        h: gens.gens_1.h,
-      };
+      let gens_hat = MultiCommitGens::from_generators(vec![g_hat], gens.gens_1.h());
       d.commit(&r_delta, &gens_hat)
     };
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &delta);
@@ -552,7 +813,7 @@ impl<G: CurveGroup> DotProductProofLog<G> {
     Cx: &G,
     Cy: &G,
   ) -> Result<(), ProofVerifyError> {
-    assert_eq!(gens.n, n);
+    assert!(gens.n >= n);
     assert_eq!(a.len(), n);
 
     <Transcript as ProofTranscript<G>>::append_protocol_name(
@@ -563,31 +824,40 @@ impl<G: CurveGroup> DotProductProofLog<G> {
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"Cy", Cy);
     <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"a", a);
 
-    let Gamma = *Cx + *Cy;
-
-    let (g_hat, Gamma_hat, a_hat) =
-      self
-        .bullet_reduction_proof
-        .verify(n, a, transcript, &Gamma, &gens.gens_n.G)?;
+    let (u_sq, u_inv_sq, s) = self
+      .bullet_reduction_proof
+      .verification_scalars(n, transcript)?;
+    let a_hat = DotProductProofLog::<G>::compute_dotproduct(a, &s);
 
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"delta", &self.delta);
     <Transcript as ProofTranscript<G>>::append_point(transcript, b"beta", &self.beta);
 
     let c = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"c");
 
-    let c_s = &c;
-    let beta_s = self.beta;
-    let a_hat_s = &a_hat;
-    let delta_s = self.delta;
-    let z1_s = &self.z1;
-    let z2_s = &self.z2;
-
-    let lhs = (Gamma_hat * c_s + beta_s) * a_hat_s + delta_s;
-    let rhs = (g_hat + gens.gens_1.G[0] * a_hat_s) * z1_s + gens.gens_1.h * z2_s;
-
-    assert_eq!(lhs, rhs);
-
-    if lhs == rhs {
+    // (Gamma_hat * c + beta) * a_hat + delta = (g_hat + g * a_hat) * z1 + h * z2, where
+    // Gamma_hat = Cx + Cy + sum_i (u_i^2 * L_i + u_i^{-2} * R_i) and g_hat = <s, G>, is checked
+    // with a single multiscalar multiplication
+    let c_a_hat = c * a_hat;
+    let points = G::normalize_batch(
+      &[
+        self.bullet_reduction_proof.L_vec(),
+        self.bullet_reduction_proof.R_vec(),
+        &[*Cx, *Cy, self.beta, self.delta],
+      ]
+      .concat(),
+    );
+    let bases = [&gens.bases[..n], &gens.bases[gens.n..], &points].concat();
+    let scalars = s
+      .iter()
+      .map(|s_i| -(*s_i * self.z1))
+      .chain([-(a_hat * self.z1), -self.z2])
+      .chain(u_sq.iter().map(|u| c_a_hat * u))
+      .chain(u_inv_sq.iter().map(|u| c_a_hat * u))
+      .chain([c_a_hat, c_a_hat, a_hat, G::ScalarField::one()])
+      .collect::<Vec<_>>();
+
+    let result: G = VariableBaseMSM::msm(&bases, &scalars).unwrap();
+    if result.is_zero() {
       Ok(())
     } else {
       Err(ProofVerifyError::InternalError)
@@ -0,0 +1,440 @@
+//! An optimization pass over the constraints of an R1CS instance, which substitutes linear
+//! constraints into the others, removes duplicate constraints and drops the variables that are
+//! no longer used, so that the padded dimensions of the instance may shrink.
+use super::errors::R1CSError;
+use super::r1csinstance::Constraint;
+use super::VarsAssignment;
+use ark_ff::PrimeField;
+use core::fmt;
+use std::collections::{HashSet, VecDeque};
+
+/// `OptimizationReport` summarizes the savings of `Instance::optimize`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptimizationReport {
+  /// the number of non-empty constraints of the original instance
+  pub num_cons_before: usize,
+  /// the number of constraints of the optimized instance
+  pub num_cons_after: usize,
+  /// the (padded) number of variables of the original instance
+  pub num_vars_before: usize,
+  /// the number of variables of the optimized instance
+  pub num_vars_after: usize,
+  /// the padded number of constraints of the original and the optimized instances
+  pub num_cons_padded: (usize, usize),
+  /// the padded number of variables of the original and the optimized instances
+  pub num_vars_padded: (usize, usize),
+  /// the number of linear constraints that were substituted into the others or became trivial
+  pub num_linear_removed: usize,
+  /// the number of constraints that duplicated another one
+  pub num_duplicates_removed: usize,
+}
+
+impl fmt::Display for OptimizationReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "constraints: {} -> {} (padded {} -> {})",
+      self.num_cons_before, self.num_cons_after, self.num_cons_padded.0, self.num_cons_padded.1
+    )?;
+    writeln!(
+      f,
+      "variables: {} -> {} (padded {} -> {})",
+      self.num_vars_before, self.num_vars_after, self.num_vars_padded.0, self.num_vars_padded.1
+    )?;
+    writeln!(f, "linear constraints removed: {}", self.num_linear_removed)?;
+    write!(
+      f,
+      "duplicate constraints removed: {}",
+      self.num_duplicates_removed
+    )
+  }
+}
+
+/// `Optimization` describes how `Instance::optimize` transformed an instance
+#[derive(Clone, Debug)]
+pub struct Optimization {
+  /// for each variable of the optimized instance, the index of the variable of the original
+  /// instance that it is
+  pub vars: Vec<usize>,
+  pub report: OptimizationReport,
+}
+
+impl Optimization {
+  /// Maps an assignment to the variables of the original instance to an assignment to the
+  /// variables of the optimized instance, which is satisfying if the original one is; the
+  /// inputs are unchanged. Returns an error if the assignment misses a variable that the
+  /// optimized instance keeps.
+  pub fn map_vars<F: PrimeField>(
+    &self,
+    vars: &VarsAssignment<F>,
+  ) -> Result<VarsAssignment<F>, R1CSError> {
+    let assignment = self
+      .vars
+      .iter()
+      .map(|&i| vars.assignment.get(i).copied())
+      .collect::<Option<Vec<F>>>()
+      .ok_or(R1CSError::InvalidNumberOfVars)?;
+    Ok(VarsAssignment { assignment })
+  }
+}
+
+// a linear combination, sorted by column, with distinct columns and non-zero coefficients
+type LinearCombination<F> = Vec<(usize, F)>;
+
+// the constraints resulting from `optimize`, over the variables `vars` of the original
+// constraints followed by the constant and the inputs
+pub(crate) struct OptimizedConstraints<F> {
+  pub constraints: Vec<Constraint<F>>,
+  pub vars: Vec<usize>,
+  pub num_linear_removed: usize,
+  pub num_duplicates_removed: usize,
+}
+
+// optimizes constraints whose columns index `z = (vars, 1, inputs)` with `num_vars` variables
+pub(crate) fn optimize<F: PrimeField>(
+  num_vars: usize,
+  max_fill_in: usize,
+  constraints: &[Constraint<F>],
+) -> OptimizedConstraints<F> {
+  let mut constraints = constraints
+    .iter()
+    .filter(|constraint| !constraint.is_empty())
+    .map(|constraint| {
+      [&constraint.A, &constraint.B, &constraint.C].map(|terms| normalize(terms.clone()))
+    })
+    .collect::<Vec<[LinearCombination<F>; 3]>>();
+  let num_linear_removed = substitute_linear(num_vars, max_fill_in, &mut constraints);
+
+  // a constraint duplicates another if they agree up to the order of A and B
+  let num_cons = constraints.len();
+  let mut seen = HashSet::new();
+  constraints.retain(|[A, B, C]| {
+    let key = if A <= B {
+      (A.clone(), B.clone(), C.clone())
+    } else {
+      (B.clone(), A.clone(), C.clone())
+    };
+    seen.insert(key)
+  });
+  let num_duplicates_removed = num_cons - constraints.len();
+
+  // the variables still in use keep their order, followed by the constant and the inputs
+  let mut used = vec![false; num_vars];
+  for lc in constraints.iter().flatten() {
+    for &(col, _) in lc.iter().filter(|(col, _)| *col < num_vars) {
+      used[col] = true;
+    }
+  }
+  let vars = (0..num_vars).filter(|&i| used[i]).collect::<Vec<usize>>();
+  let mut new_col = vec![0; num_vars];
+  for (new, &old) in vars.iter().enumerate() {
+    new_col[old] = new;
+  }
+  let relabel = |lc: LinearCombination<F>| {
+    lc.into_iter()
+      .map(|(col, val)| match new_col.get(col) {
+        Some(&new) => (new, val),
+        None => (col - num_vars + vars.len(), val),
+      })
+      .collect::<Vec<(usize, F)>>()
+  };
+  let constraints = constraints
+    .into_iter()
+    .map(|[A, B, C]| Constraint {
+      A: relabel(A),
+      B: relabel(B),
+      C: relabel(C),
+    })
+    .collect();
+
+  OptimizedConstraints {
+    constraints,
+    vars,
+    num_linear_removed,
+    num_duplicates_removed,
+  }
+}
+
+// removes the linear constraints, substituting for one of their variables in the other
+// constraints, and returns the number of removed constraints. Constraints that become linear
+// are substituted in turn; linear constraints over the constant and the inputs alone are kept,
+// as are those whose substitution would add more than `max_fill_in` non-zero entries.
+fn substitute_linear<F: PrimeField>(
+  num_vars: usize,
+  max_fill_in: usize,
+  constraints: &mut Vec<[LinearCombination<F>; 3]>,
+) -> usize {
+  // the constraints in which each variable occurs, possibly with repetitions and constraints
+  // from which it has since been eliminated
+  let mut occurrences = vec![Vec::new(); num_vars];
+  for (i, constraint) in constraints.iter().enumerate() {
+    for &(col, _) in constraint.iter().flatten() {
+      if col < num_vars {
+        occurrences[col].push(i);
+      }
+    }
+  }
+
+  let mut removed = vec![false; constraints.len()];
+  let mut queued = vec![true; constraints.len()];
+  let mut queue = (0..constraints.len()).collect::<VecDeque<usize>>();
+  while let Some(i) = queue.pop_front() {
+    queued[i] = false;
+    let Some(lc) = linear_form(num_vars, &constraints[i]) else {
+      continue;
+    };
+    if lc.is_empty() {
+      removed[i] = true;
+      continue;
+    }
+
+    // the variable to eliminate is the one that occurs in the fewest other constraints, which
+    // limits the fill-in of the substitution
+    for &(col, _) in lc.iter().filter(|(col, _)| *col < num_vars) {
+      let targets = &mut occurrences[col];
+      targets.sort_unstable();
+      targets.dedup();
+      targets.retain(|&j| !removed[j]);
+    }
+    let Some(&(var, coeff)) = lc
+      .iter()
+      .filter(|(col, _)| *col < num_vars)
+      .min_by_key(|(col, _)| occurrences[*col].len())
+    else {
+      continue;
+    };
+
+    // in the other constraints, every occurrence of the variable becomes the other terms of the
+    // linear combination, while the terms of the linear constraint go away
+    let num_targets = occurrences[var].len() - 1;
+    let num_terms = constraints[i].iter().map(|lc| lc.len()).sum::<usize>();
+    if num_targets * (lc.len() - 1) > (num_terms + num_targets).saturating_add(max_fill_in) {
+      continue;
+    }
+
+    let scale = -coeff.inverse().unwrap();
+    let expr = lc
+      .iter()
+      .filter(|(col, _)| *col != var)
+      .map(|&(col, val)| (col, val * scale))
+      .collect::<LinearCombination<F>>();
+    removed[i] = true;
+
+    let targets = std::mem::take(&mut occurrences[var]);
+    for j in targets.into_iter().filter(|&j| j != i) {
+      for lc in constraints[j].iter_mut() {
+        if let Ok(pos) = lc.binary_search_by_key(&var, |(col, _)| *col) {
+          let (_, val) = lc.remove(pos);
+          *lc = add_scaled(lc, &expr, val);
+        }
+      }
+      for &(col, _) in expr.iter().filter(|(col, _)| *col < num_vars) {
+        occurrences[col].push(j);
+      }
+      if !queued[j] {
+        queued[j] = true;
+        queue.push_back(j);
+      }
+    }
+  }
+
+  let num_removed = removed.iter().filter(|&&removed| removed).count();
+  let mut i = 0;
+  constraints.retain(|_| {
+    i += 1;
+    !removed[i - 1]
+  });
+  num_removed
+}
+
+// the linear combination that a constraint requires to be zero, if A or B is a constant
+fn linear_form<F: PrimeField>(
+  num_vars: usize,
+  [A, B, C]: &[LinearCombination<F>; 3],
+) -> Option<LinearCombination<F>> {
+  let constant = |lc: &LinearCombination<F>| match lc.as_slice() {
+    [] => Some(F::zero()),
+    [(col, val)] if *col == num_vars => Some(*val),
+    _ => None,
+  };
+  let (a, other) = match (constant(A), constant(B)) {
+    (Some(a), _) => (a, B),
+    (None, Some(b)) => (b, A),
+    (None, None) => return None,
+  };
+  Some(add_scaled(&add_scaled(&[], other, a), C, -F::one()))
+}
+
+// sorts the terms by column, merging the terms of the same column and dropping zeros
+fn normalize<F: PrimeField>(mut terms: Vec<(usize, F)>) -> LinearCombination<F> {
+  terms.sort_by_key(|(col, _)| *col);
+  let mut lc: LinearCombination<F> = Vec::with_capacity(terms.len());
+  for (col, val) in terms {
+    match lc.last_mut() {
+      Some((last, sum)) if *last == col => *sum += val,
+      _ => lc.push((col, val)),
+    }
+  }
+  lc.retain(|(_, val)| !val.is_zero());
+  lc
+}
+
+// computes `lc + scale * other`
+fn add_scaled<F: PrimeField>(lc: &[(usize, F)], other: &[(usize, F)], scale: F) -> Vec<(usize, F)> {
+  let mut sum = Vec::with_capacity(lc.len() + other.len());
+  let (mut i, mut j) = (0, 0);
+  while i < lc.len() || j < other.len() {
+    let (col, val) = match (lc.get(i), other.get(j)) {
+      (Some(&(c1, v1)), Some(&(c2, v2))) if c1 == c2 => {
+        i += 1;
+        j += 1;
+        (c1, v1 + v2 * scale)
+      }
+      (Some(&(c1, v1)), Some(&(c2, _))) if c1 < c2 => {
+        i += 1;
+        (c1, v1)
+      }
+      (Some(&(c1, v1)), None) => {
+        i += 1;
+        (c1, v1)
+      }
+      (_, Some(&(c2, v2))) => {
+        j += 1;
+        (c2, v2 * scale)
+      }
+      (None, None) => unreachable!(),
+    };
+    if !val.is_zero() {
+      sum.push((col, val));
+    }
+  }
+  sum
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{InputsAssignment, Instance, NIZKGens, NIZK};
+  use ark_bls12_381::{Fr, G1Projective};
+  use merlin::Transcript;
+
+  #[test]
+  fn check_optimize() {
+    // x0 * x1 = x2, three times, over eight variables and one input, where x2 + x3 = x4,
+    // x4 = in0 and x3 = 5, and x5 * x5 = x5
+    let (num_vars, one) = (8, Fr::from(1u64));
+    let (constant, in0) = (num_vars, num_vars + 1);
+    let constraint = |A: &[(usize, Fr)], B: &[(usize, Fr)], C: &[(usize, Fr)]| Constraint {
+      A: A.to_vec(),
+      B: B.to_vec(),
+      C: C.to_vec(),
+    };
+    let constraints = [
+      constraint(&[(0, one)], &[(1, one)], &[(2, one)]),
+      constraint(&[(constant, one)], &[(2, one), (3, one)], &[(4, one)]),
+      constraint(&[(0, one)], &[(1, one)], &[(2, one)]),
+      constraint(&[(1, one)], &[(0, one)], &[(2, one)]),
+      constraint(&[(4, one)], &[(constant, one)], &[(in0, one)]),
+      constraint(&[(5, one)], &[(5, one)], &[(5, one)]),
+      constraint(
+        &[(constant, one)],
+        &[(3, one)],
+        &[(constant, Fr::from(5u64))],
+      ),
+    ];
+    let inst = Instance::from_constraints(num_vars, 1, &constraints).unwrap();
+    let vars = VarsAssignment::new(&[2, 3, 6, 5, 11, 1].map(Fr::from)).unwrap();
+    let inputs = InputsAssignment::new(&[Fr::from(11u64)]).unwrap();
+    assert!(inst.is_sat(&vars, &inputs).unwrap());
+
+    let (optimized, optimization) = inst.optimize().unwrap();
+    assert_eq!(optimization.vars, vec![0, 1, 5]);
+    assert_eq!(
+      optimization.report,
+      OptimizationReport {
+        num_cons_before: 7,
+        num_cons_after: 2,
+        num_vars_before: 8,
+        num_vars_after: 3,
+        num_cons_padded: (8, 2),
+        num_vars_padded: (8, 4),
+        num_linear_removed: 3,
+        num_duplicates_removed: 2,
+      }
+    );
+
+    // x0 * x1 = in0 - 5 and x5 * x5 = x5 remain, over x0, x1 and x5
+    let optimized_vars = optimization.map_vars(&vars).unwrap();
+    assert_eq!(optimized_vars.assignment, [2, 3, 1].map(Fr::from));
+    assert!(optimized.is_sat(&optimized_vars, &inputs).unwrap());
+    let wrong_inputs = InputsAssignment::new(&[Fr::from(12u64)]).unwrap();
+    assert!(!optimized.is_sat(&optimized_vars, &wrong_inputs).unwrap());
+
+    // an assignment that misses a kept variable is rejected
+    let short_vars = VarsAssignment::new(&[2, 3].map(Fr::from)).unwrap();
+    assert!(optimization.map_vars(&short_vars).is_err());
+
+    // substituting without a bound on the fill-in gives the same result here
+    let (dense, dense_optimization) = inst.optimize_with_max_fill_in(usize::MAX).unwrap();
+    assert_eq!(dense.constraints(), optimized.constraints());
+    assert_eq!(dense_optimization.vars, optimization.vars);
+
+    // random instances keep their satisfying assignments
+    let (inst, vars, inputs) = Instance::<Fr>::produce_synthetic_r1cs(64, 64, 4);
+    for max_fill_in in [0, usize::MAX] {
+      let (optimized, optimization) = inst.optimize_with_max_fill_in(max_fill_in).unwrap();
+      assert!(optimized
+        .is_sat(&optimization.map_vars(&vars).unwrap(), &inputs)
+        .unwrap());
+    }
+  }
+
+  #[test]
+  fn check_optimize_linear() {
+    // x0 * 1 = in0 and x1 * 1 = x0 are both substituted, which leaves no constraints and no
+    // variables, but the optimized instance keeps the minimum padded dimensions
+    let (num_vars, one) = (2, Fr::from(1u64));
+    let (constant, in0) = (num_vars, num_vars + 1);
+    let constraints = [
+      Constraint {
+        A: vec![(0, one)],
+        B: vec![(constant, one)],
+        C: vec![(in0, one)],
+      },
+      Constraint {
+        A: vec![(1, one)],
+        B: vec![(constant, one)],
+        C: vec![(0, one)],
+      },
+    ];
+    let inst = Instance::from_constraints(num_vars, 1, &constraints).unwrap();
+    let vars = VarsAssignment::new(&[Fr::from(7u64), Fr::from(7u64)]).unwrap();
+    let inputs = InputsAssignment::new(&[Fr::from(7u64)]).unwrap();
+    assert!(inst.is_sat(&vars, &inputs).unwrap());
+
+    let (optimized, optimization) = inst.optimize_with_max_fill_in(usize::MAX).unwrap();
+    assert_eq!(optimization.report.num_cons_after, 0);
+    assert_eq!(optimization.report.num_vars_after, 0);
+    assert_eq!(optimization.report.num_cons_padded, (2, 2));
+    assert!(optimization.report.num_vars_padded.1 > 0);
+
+    let optimized_vars = optimization.map_vars(&vars).unwrap();
+    let gens = NIZKGens::<G1Projective>::new(
+      optimized.get_num_cons(),
+      optimized.get_num_vars(),
+      optimized.get_num_inputs(),
+    );
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = NIZK::prove(
+      &optimized,
+      optimized_vars,
+      &inputs,
+      &gens,
+      &mut prover_transcript,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&optimized, &inputs, &mut verifier_transcript, &gens)
+      .is_ok());
+  }
+}
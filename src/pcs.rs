@@ -0,0 +1,466 @@
+//! Commitments to multilinear polynomials.
+//!
+//! This module exposes the Hyrax-style polynomial commitment that Spartan uses for the witness:
+//! a multilinear polynomial in `num_vars` variables is laid out as a matrix of
+//! `2^{num_vars/2}` rows, each row is committed with a Pedersen vector commitment, and an
+//! evaluation is proven with a logarithmic-size dot-product proof.
+//!
+//! Commitments are either non-hiding (`Commitment::commit`), which is deterministic, or hiding
+//! (`Commitment::commit_hiding`), which blinds every row and returns a `Decommitment` that the
+//! prover keeps. Evaluations are proven with an `EvalProof`, which reveals the evaluation, or a
+//! `HidingEvalProof`, which only reveals a commitment to it. A `BatchedEvalProof` opens several
+//! polynomials committed under the same generators, at one or more points, with a single proof.
+//! Hiding commitments and evaluation proofs draw their randomness from a cryptographically secure
+//! `rng` of the caller.
+//!
+//! ```
+//! # use ark_bls12_381::{Fr, G1Projective};
+//! # use ark_std::rand::{rngs::StdRng, SeedableRng};
+//! # use libspartan::pcs::{Commitment, EvalProof, MultilinearPolynomial, PCSGens};
+//! # use merlin::Transcript;
+//! let mut rng = StdRng::from_entropy();
+//! let poly = MultilinearPolynomial::new((0..16u64).map(Fr::from).collect());
+//! let gens = PCSGens::<G1Projective>::new(poly.num_vars(), b"pcs_example");
+//! let (comm, decomm) = Commitment::commit_hiding(&poly, &gens, &mut rng);
+//!
+//! let r = vec![Fr::from(2u64), Fr::from(3u64), Fr::from(5u64), Fr::from(7u64)];
+//! let mut prover_transcript = Transcript::new(b"pcs_example");
+//! let (proof, eval) = EvalProof::prove(
+//!   &poly,
+//!   Some(&decomm),
+//!   &r,
+//!   &gens,
+//!   &mut prover_transcript,
+//!   &mut rng,
+//! );
+//! assert_eq!(eval, poly.evaluate(&r));
+//!
+//! let mut verifier_transcript = Transcript::new(b"pcs_example");
+//! assert!(proof
+//!   .verify(&comm, &r, &eval, &gens, &mut verifier_transcript)
+//!   .is_ok());
+//! ```
+use super::commitments::MultiCommitGens;
+use super::dense_mlpoly::{
+  BatchedPolyEvalProof, DensePolynomial, EqPolynomial, PolyCommitment, PolyCommitmentBlinds,
+  PolyCommitmentGens, PolyEvalProof,
+};
+use super::errors::ProofVerifyError;
+use super::random::RandomTape;
+use super::transcript::{AppendToTranscript, ProofTranscript};
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
+use merlin::Transcript;
+
+/// `PCSGens` holds the public parameters to commit to multilinear polynomials in `num_vars`
+/// variables
+pub struct PCSGens<G: CurveGroup> {
+  num_vars: usize,
+  gens: PolyCommitmentGens<G>,
+}
+
+impl<G: CurveGroup> PCSGens<G> {
+  /// Produces public parameters for polynomials in `num_vars` variables
+  pub fn new(num_vars: usize, label: &'static [u8]) -> Self {
+    PCSGens {
+      num_vars,
+      gens: PolyCommitmentGens::new(num_vars, label),
+    }
+  }
+
+  /// Returns the number of variables of the polynomials that can be committed
+  pub fn num_vars(&self) -> usize {
+    self.num_vars
+  }
+
+  /// Returns the generators under which a `HidingEvalProof` commits to the evaluation
+  pub fn gens_eval(&self) -> &MultiCommitGens<G> {
+    &self.gens.gens.gens_1
+  }
+}
+
+/// `MultilinearPolynomial` is a multilinear polynomial given by its evaluations over the Boolean
+/// hypercube, the first variable being the most significant bit of the index
+#[derive(Debug, Clone)]
+pub struct MultilinearPolynomial<F: PrimeField> {
+  pub(crate) poly: DensePolynomial<F>,
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> {
+  /// Constructs a polynomial from its evaluations, whose number must be a power of two
+  pub fn new(evals: Vec<F>) -> Self {
+    assert!(evals.len().is_power_of_two());
+    MultilinearPolynomial {
+      poly: DensePolynomial::new(evals),
+    }
+  }
+
+  /// Returns the number of variables
+  pub fn num_vars(&self) -> usize {
+    self.poly.get_num_vars()
+  }
+
+  /// Evaluates the polynomial at `r`
+  pub fn evaluate(&self, r: &[F]) -> F {
+    assert_eq!(r.len(), self.num_vars());
+    EqPolynomial::new(r.to_vec())
+      .evals()
+      .iter()
+      .enumerate()
+      .map(|(i, chi)| *chi * self.poly[i])
+      .sum()
+  }
+}
+
+/// `Commitment` is a commitment to a `MultilinearPolynomial`
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<G: CurveGroup> {
+  comm: PolyCommitment<G>,
+}
+
+/// `Decommitment` holds the blinds of a hiding `Commitment`, which the prover needs to open it
+pub struct Decommitment<F> {
+  blinds: PolyCommitmentBlinds<F>,
+}
+
+impl<G: CurveGroup> Commitment<G> {
+  /// Produces a non-hiding commitment to `poly`
+  pub fn commit(poly: &MultilinearPolynomial<G::ScalarField>, gens: &PCSGens<G>) -> Self {
+    assert_eq!(poly.num_vars(), gens.num_vars);
+    let (comm, _blinds) = poly.poly.commit(&gens.gens, None);
+    Commitment { comm }
+  }
+
+  /// Produces a hiding commitment to `poly` along with its decommitment
+  pub fn commit_hiding<R: RngCore + CryptoRng>(
+    poly: &MultilinearPolynomial<G::ScalarField>,
+    gens: &PCSGens<G>,
+    rng: &mut R,
+  ) -> (Self, Decommitment<G::ScalarField>) {
+    assert_eq!(poly.num_vars(), gens.num_vars);
+    let mut random_tape = RandomTape::new_with_rng(b"pcs_commit_randomness", rng);
+    let (comm, blinds) = poly.poly.commit(&gens.gens, Some(&mut random_tape));
+    (Commitment { comm }, Decommitment { blinds })
+  }
+
+  /// Appends the commitment to `transcript`
+  pub fn append_to_transcript(&self, label: &'static [u8], transcript: &mut Transcript) {
+    self.comm.append_to_transcript(label, transcript);
+  }
+}
+
+/// `EvalProof` proves that a committed polynomial evaluates to a public value at a point
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EvalProof<G: CurveGroup> {
+  proof: PolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> EvalProof<G> {
+  /// Proves the evaluation of `poly` at `r`, which is returned along with the proof;
+  /// `decomm` must be provided if the commitment is hiding
+  pub fn prove<R: RngCore + CryptoRng>(
+    poly: &MultilinearPolynomial<G::ScalarField>,
+    decomm: Option<&Decommitment<G::ScalarField>>,
+    r: &[G::ScalarField],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G::ScalarField) {
+    let eval = poly.evaluate(r);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"pcs_eval", &eval);
+    let mut random_tape = RandomTape::new_with_rng(b"pcs_proof_randomness", rng);
+    let (proof, _comm_eval) = PolyEvalProof::prove(
+      &poly.poly,
+      decomm.map(|decomm| &decomm.blinds),
+      r,
+      &eval,
+      None,
+      &gens.gens,
+      transcript,
+      &mut random_tape,
+    );
+    (EvalProof { proof }, eval)
+  }
+
+  /// Verifies that the polynomial committed to in `comm` evaluates to `eval` at `r`
+  pub fn verify(
+    &self,
+    comm: &Commitment<G>,
+    r: &[G::ScalarField],
+    eval: &G::ScalarField,
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    if r.len() != gens.num_vars {
+      return Err(ProofVerifyError::InternalError);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"pcs_eval", eval);
+    self
+      .proof
+      .verify_plain(&gens.gens, transcript, r, eval, &comm.comm)
+  }
+}
+
+/// `HidingEvalProof` proves that a committed polynomial evaluates, at a point, to the value held
+/// in a commitment under `PCSGens::gens_eval`, without revealing the value
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HidingEvalProof<G: CurveGroup> {
+  comm_eval: G,
+  proof: PolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> HidingEvalProof<G> {
+  /// Proves the evaluation of `poly` at `r`; returns the proof, the evaluation and the blind of
+  /// its commitment
+  pub fn prove<R: RngCore + CryptoRng>(
+    poly: &MultilinearPolynomial<G::ScalarField>,
+    decomm: &Decommitment<G::ScalarField>,
+    r: &[G::ScalarField],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G::ScalarField, G::ScalarField) {
+    let eval = poly.evaluate(r);
+    let mut random_tape = RandomTape::new_with_rng(b"pcs_proof_randomness", rng);
+    let blind_eval = random_tape.random_scalar(b"blind_eval");
+    let (proof, comm_eval) = PolyEvalProof::prove(
+      &poly.poly,
+      Some(&decomm.blinds),
+      r,
+      &eval,
+      Some(&blind_eval),
+      &gens.gens,
+      transcript,
+      &mut random_tape,
+    );
+    (HidingEvalProof { comm_eval, proof }, eval, blind_eval)
+  }
+
+  /// Returns the commitment to the evaluation
+  pub fn comm_eval(&self) -> &G {
+    &self.comm_eval
+  }
+
+  /// Verifies that the polynomial committed to in `comm` evaluates at `r` to the value committed
+  /// to in `comm_eval`
+  pub fn verify(
+    &self,
+    comm: &Commitment<G>,
+    r: &[G::ScalarField],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    if r.len() != gens.num_vars {
+      return Err(ProofVerifyError::InternalError);
+    }
+    self
+      .proof
+      .verify(&gens.gens, transcript, r, &self.comm_eval, &comm.comm)
+  }
+}
+
+/// `BatchedEvalProof` proves the public evaluations of several polynomials, committed under the
+/// same `PCSGens`, at one or more points
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchedEvalProof<G: CurveGroup> {
+  proof: BatchedPolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> BatchedEvalProof<G> {
+  /// Proves the evaluation of `polys[i]`, committed to in `comms[i]`, at `points[i]` for every
+  /// `i`, returning the evaluations; `decomms` must be provided if the commitments are hiding
+  pub fn prove<R: RngCore + CryptoRng>(
+    polys: &[&MultilinearPolynomial<G::ScalarField>],
+    comms: &[&Commitment<G>],
+    decomms: Option<&[&Decommitment<G::ScalarField>]>,
+    points: &[Vec<G::ScalarField>],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, Vec<G::ScalarField>) {
+    assert_eq!(polys.len(), points.len());
+    let evals = polys
+      .iter()
+      .zip(points.iter())
+      .map(|(poly, point)| poly.evaluate(point))
+      .collect::<Vec<G::ScalarField>>();
+    let blinds = decomms.map(|decomms| {
+      decomms
+        .iter()
+        .map(|decomm| &decomm.blinds)
+        .collect::<Vec<&PolyCommitmentBlinds<G::ScalarField>>>()
+    });
+    let mut random_tape = RandomTape::new_with_rng(b"pcs_proof_randomness", rng);
+    let proof = PolyEvalProof::prove_batched(
+      &polys.iter().map(|poly| &poly.poly).collect::<Vec<_>>(),
+      blinds.as_deref(),
+      &comms.iter().map(|comm| &comm.comm).collect::<Vec<_>>(),
+      points,
+      &evals,
+      &gens.gens,
+      transcript,
+      &mut random_tape,
+    );
+    (BatchedEvalProof { proof }, evals)
+  }
+
+  /// Verifies that the polynomials committed to in `comms` evaluate to `evals` at `points`
+  pub fn verify(
+    &self,
+    comms: &[&Commitment<G>],
+    points: &[Vec<G::ScalarField>],
+    evals: &[G::ScalarField],
+    gens: &PCSGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    if points.iter().any(|point| point.len() != gens.num_vars) {
+      return Err(ProofVerifyError::InternalError);
+    }
+    PolyEvalProof::verify_batched(
+      &self.proof,
+      &gens.gens,
+      transcript,
+      points,
+      evals,
+      &comms.iter().map(|comm| &comm.comm).collect::<Vec<_>>(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Commitments;
+  use ark_bls12_381::G1Projective;
+  use ark_std::rand::{rngs::StdRng, SeedableRng};
+  use ark_std::{One, UniformRand};
+
+  fn random_poly<F: PrimeField>(
+    num_vars: usize,
+    prng: &mut impl ark_std::rand::Rng,
+  ) -> MultilinearPolynomial<F> {
+    MultilinearPolynomial::new((0..1 << num_vars).map(|_| F::rand(prng)).collect())
+  }
+
+  #[test]
+  fn check_pcs() {
+    check_pcs_helper::<G1Projective>()
+  }
+
+  fn check_pcs_helper<G: CurveGroup>() {
+    let num_vars = 7;
+    let gens = PCSGens::<G>::new(num_vars, b"test-pcs");
+    let mut prng = StdRng::seed_from_u64(0);
+    let poly = random_poly::<G::ScalarField>(num_vars, &mut prng);
+    let r = (0..num_vars)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+
+    // non-hiding commitments are deterministic
+    let comm = Commitment::commit(&poly, &gens);
+    assert_eq!(comm, Commitment::commit(&poly, &gens));
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, eval) = EvalProof::prove(&poly, None, &r, &gens, &mut prover_transcript, &mut prng);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &r, &eval, &gens, &mut verifier_transcript)
+      .is_ok());
+
+    // a wrong evaluation is rejected
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comm,
+        &r,
+        &(eval + G::ScalarField::one()),
+        &gens,
+        &mut verifier_transcript
+      )
+      .is_err());
+
+    // hiding commitments with a public evaluation
+    let (comm_hiding, decomm) = Commitment::commit_hiding(&poly, &gens, &mut prng);
+    assert_ne!(comm, comm_hiding);
+    // and draw fresh blinds for every commitment
+    let (comm_hiding_again, _) = Commitment::commit_hiding(&poly, &gens, &mut prng);
+    assert_ne!(comm_hiding, comm_hiding_again);
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, eval) = EvalProof::prove(
+      &poly,
+      Some(&decomm),
+      &r,
+      &gens,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm_hiding, &r, &eval, &gens, &mut verifier_transcript)
+      .is_ok());
+
+    // hiding commitments with a hidden evaluation
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, eval, blind_eval) =
+      HidingEvalProof::prove(&poly, &decomm, &r, &gens, &mut prover_transcript, &mut prng);
+    assert_eq!(
+      *proof.comm_eval(),
+      eval.commit(&blind_eval, gens.gens_eval())
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm_hiding, &r, &gens, &mut verifier_transcript)
+      .is_ok());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &r, &gens, &mut verifier_transcript)
+      .is_err());
+  }
+
+  #[test]
+  fn check_pcs_batched() {
+    check_pcs_batched_helper::<G1Projective>()
+  }
+
+  fn check_pcs_batched_helper<G: CurveGroup>() {
+    let num_vars = 4;
+    let gens = PCSGens::<G>::new(num_vars, b"test-pcs");
+    let mut prng = StdRng::seed_from_u64(0);
+    let polys = (0..3)
+      .map(|_| random_poly::<G::ScalarField>(num_vars, &mut prng))
+      .collect::<Vec<_>>();
+    let comms = polys
+      .iter()
+      .map(|poly| Commitment::commit(poly, &gens))
+      .collect::<Vec<_>>();
+    let points = (0..3)
+      .map(|_| {
+        (0..num_vars)
+          .map(|_| G::ScalarField::rand(&mut prng))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, evals) = BatchedEvalProof::prove(
+      &polys.iter().collect::<Vec<_>>(),
+      &comms.iter().collect::<Vec<_>>(),
+      None,
+      &points,
+      &gens,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &comms.iter().collect::<Vec<_>>(),
+        &points,
+        &evals,
+        &gens,
+        &mut verifier_transcript
+      )
+      .is_ok());
+  }
+}
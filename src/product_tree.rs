@@ -1,6 +1,8 @@
 #![allow(dead_code)]
+#![allow(clippy::type_complexity)]
 use super::dense_mlpoly::DensePolynomial;
 use super::dense_mlpoly::EqPolynomial;
+use super::errors::ProofVerifyError;
 use super::math::Math;
 use super::sumcheck::SumcheckInstanceProof;
 use super::transcript::ProofTranscript;
@@ -38,7 +40,7 @@ impl<F: PrimeField> ProductCircuit<F> {
     let mut left_vec: Vec<DensePolynomial<F>> = Vec::new();
     let mut right_vec: Vec<DensePolynomial<F>> = Vec::new();
 
-    let num_layers = poly.len().log_2() as usize;
+    let num_layers = poly.len().log_2();
     let (outp_left, outp_right) = poly.split(poly.len() / 2);
 
     left_vec.push(outp_left);
@@ -65,9 +67,9 @@ impl<F: PrimeField> ProductCircuit<F> {
 }
 
 pub struct DotProductCircuit<F> {
-  left: DensePolynomial<F>,
-  right: DensePolynomial<F>,
-  weight: DensePolynomial<F>,
+  pub(crate) left: DensePolynomial<F>,
+  pub(crate) right: DensePolynomial<F>,
+  pub(crate) weight: DensePolynomial<F>,
 }
 
 impl<F: PrimeField> DotProductCircuit<F> {
@@ -154,14 +156,13 @@ impl<F: PrimeField> LayerProofBatched<F> {
     num_rounds: usize,
     degree_bound: usize,
     transcript: &mut Transcript,
-  ) -> (F, Vec<F>)
+  ) -> Result<(F, Vec<F>), ProofVerifyError>
   where
     G: CurveGroup<ScalarField = F>,
   {
     self
       .proof
       .verify::<G>(claim, num_rounds, degree_bound, transcript)
-      .unwrap()
   }
 }
 
@@ -193,7 +194,7 @@ impl<F: PrimeField> ProductCircuitEvalProof<F> {
       let mut poly_C = DensePolynomial::new(EqPolynomial::new(rand.clone()).evals());
       assert_eq!(poly_C.len(), len / 2);
 
-      let num_rounds_prod = poly_C.len().log_2() as usize;
+      let num_rounds_prod = poly_C.len().log_2();
       let comb_func_prod = |poly_A_comp: &F, poly_B_comp: &F, poly_C_comp: &F| -> F {
         *poly_A_comp * *poly_B_comp * *poly_C_comp
       };
@@ -241,7 +242,7 @@ impl<F: PrimeField> ProductCircuitEvalProof<F> {
   where
     G: CurveGroup<ScalarField = F>,
   {
-    let num_layers = len.log_2() as usize;
+    let num_layers = len.log_2();
     let mut claim = eval;
     let mut rand: Vec<F> = Vec::new();
     //let mut num_rounds = 0;
@@ -308,7 +309,7 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
       let mut poly_C_par = DensePolynomial::new(EqPolynomial::<F>::new(rand.clone()).evals());
       assert_eq!(poly_C_par.len(), len / 2);
 
-      let num_rounds_prod = poly_C_par.len().log_2() as usize;
+      let num_rounds_prod = poly_C_par.len().log_2();
       let comb_func_prod = |poly_A_comp: &F, poly_B_comp: &F, poly_C_comp: &F| -> F {
         *poly_A_comp * *poly_B_comp * *poly_C_comp
       };
@@ -444,14 +445,15 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
     claims_dotp_vec: &[F],
     len: usize,
     transcript: &mut Transcript,
-  ) -> (Vec<F>, Vec<F>, Vec<F>)
+  ) -> Result<(Vec<F>, Vec<F>, Vec<F>), ProofVerifyError>
   where
     G: CurveGroup<ScalarField = F>,
   {
-    let num_layers = len.log_2() as usize;
+    let num_layers = len.log_2();
     let mut rand: Vec<F> = Vec::new();
-    //let mut num_rounds = 0;
-    assert_eq!(self.proof.len(), num_layers);
+    if self.proof.len() != num_layers {
+      return Err(ProofVerifyError::InternalError);
+    }
 
     let mut claims_to_verify = claims_prod_vec.to_owned();
     let mut claims_to_verify_dotp: Vec<F> = Vec::new();
@@ -472,12 +474,15 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
         .map(|i| claims_to_verify[i] * coeff_vec[i])
         .sum();
 
-      let (claim_last, rand_prod) = self.proof[i].verify::<G>(claim, num_rounds, 3, transcript);
+      let (claim_last, rand_prod) = self.proof[i].verify::<G>(claim, num_rounds, 3, transcript)?;
 
       let claims_prod_left = &self.proof[i].claims_prod_left;
       let claims_prod_right = &self.proof[i].claims_prod_right;
-      assert_eq!(claims_prod_left.len(), claims_prod_vec.len());
-      assert_eq!(claims_prod_right.len(), claims_prod_vec.len());
+      if claims_prod_left.len() != claims_prod_vec.len()
+        || claims_prod_right.len() != claims_prod_vec.len()
+      {
+        return Err(ProofVerifyError::InternalError);
+      }
 
       for i in 0..claims_prod_vec.len() {
         <Transcript as ProofTranscript<G>>::append_scalar(
@@ -493,7 +498,9 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
         );
       }
 
-      assert_eq!(rand.len(), rand_prod.len());
+      if rand.len() != rand_prod.len() {
+        return Err(ProofVerifyError::InternalError);
+      }
       let eq: F = (0..rand.len())
         .map(|i| rand[i] * rand_prod[i] + (F::one() - rand[i]) * (F::one() - rand_prod[i]))
         .product();
@@ -505,6 +512,12 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
       if i == num_layers - 1 {
         let num_prod_instances = claims_prod_vec.len();
         let (claims_dotp_left, claims_dotp_right, claims_dotp_weight) = &self.claims_dotp;
+        if claims_dotp_left.len() != claims_dotp_vec.len()
+          || claims_dotp_right.len() != claims_dotp_vec.len()
+          || claims_dotp_weight.len() != claims_dotp_vec.len()
+        {
+          return Err(ProofVerifyError::InternalError);
+        }
         for i in 0..claims_dotp_left.len() {
           <Transcript as ProofTranscript<G>>::append_scalar(
             transcript,
@@ -531,7 +544,9 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
         }
       }
 
-      assert_eq!(claim_expected, claim_last);
+      if claim_expected != claim_last {
+        return Err(ProofVerifyError::InternalError);
+      }
 
       // produce a random challenge
       let r_layer =
@@ -565,6 +580,6 @@ impl<F: PrimeField> ProductCircuitEvalProofBatched<F> {
       ext.extend(rand_prod);
       rand = ext;
     }
-    (claims_to_verify, claims_to_verify_dotp, rand)
+    Ok((claims_to_verify, claims_to_verify_dotp, rand))
   }
 }
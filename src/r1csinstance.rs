@@ -1,9 +1,10 @@
-use super::dense_mlpoly::DensePolynomial;
-use super::errors::ProofVerifyError;
+use super::commitments::{GensDerivation, MultiCommitGens};
+use super::dense_mlpoly::{DensePolynomial, EqPolynomial};
+use super::errors::{ProofVerifyError, R1CSError};
 use super::math::Math;
 use super::random::RandomTape;
 use super::sparse_mlpoly::{
-  MultiSparseMatPolynomialAsDense, SparseMatEntry, SparseMatPolyCommitment,
+  MultiSparseMatPolynomialAsDense, SparkMode, SparseMatEntry, SparseMatPolyCommitment,
   SparseMatPolyCommitmentGens, SparseMatPolyEvalProof, SparseMatPolynomial,
 };
 use super::timer::Timer;
@@ -13,6 +14,7 @@ use ark_ff::PrimeField;
 use ark_serialize::*;
 use ark_std::test_rng;
 use merlin::Transcript;
+use sha3::{Digest, Sha3_256};
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct R1CSInstance<F: PrimeField> {
@@ -22,18 +24,49 @@ pub struct R1CSInstance<F: PrimeField> {
   A: SparseMatPolynomial<F>,
   B: SparseMatPolynomial<F>,
   C: SparseMatPolynomial<F>,
+  rounds: Vec<WitnessRound>,
 }
 
-impl<G: CurveGroup> AppendToTranscript<G> for R1CSInstance<G::ScalarField> {
-  fn append_to_transcript(&self, _label: &'static [u8], transcript: &mut Transcript) {
-    let mut data = vec![];
-    self.serialize_compressed(&mut data).unwrap();
+/// `WitnessRound` describes a round of a multi-round witness: the prover commits to the next
+/// `num_vars` variables, after which `num_challenges` verifier challenges are derived.
+/// The challenges of all rounds occupy the last inputs of the instance, in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WitnessRound {
+  pub num_vars: usize,
+  pub num_challenges: usize,
+}
+
+/// `Constraint` is a constraint `<A, z> * <B, z> = <C, z>` of an R1CS instance, where each
+/// linear combination lists the columns of `z = (vars, 1, inputs)` with their coefficients
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint<F> {
+  pub A: Vec<(usize, F)>,
+  pub B: Vec<(usize, F)>,
+  pub C: Vec<(usize, F)>,
+}
 
-    transcript.append_message(b"R1CSInstance", &data);
+impl<F> Constraint<F> {
+  /// whether the constraint has no terms, in which case every assignment satisfies it
+  pub fn is_empty(&self) -> bool {
+    self.A.is_empty() && self.B.is_empty() && self.C.is_empty()
   }
 }
 
-pub struct R1CSCommitmentGens<G> {
+// a writer that feeds everything written to it into a hash
+struct HashWriter<'a>(&'a mut Sha3_256);
+
+impl Write for HashWriter<'_> {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.input(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+pub struct R1CSCommitmentGens<G: CurveGroup> {
   gens: SparseMatPolyCommitmentGens<G>,
 }
 
@@ -44,12 +77,40 @@ impl<G: CurveGroup> R1CSCommitmentGens<G> {
     num_vars: usize,
     num_inputs: usize,
     num_nz_entries: usize,
+    mode: SparkMode,
+  ) -> R1CSCommitmentGens<G> {
+    R1CSCommitmentGens::new_with_derivation(
+      label,
+      num_cons,
+      num_vars,
+      num_inputs,
+      num_nz_entries,
+      mode,
+      MultiCommitGens::new,
+    )
+  }
+
+  pub fn new_with_derivation(
+    label: &'static [u8],
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+    mode: SparkMode,
+    derive: GensDerivation<G>,
   ) -> R1CSCommitmentGens<G> {
     assert!(num_inputs < num_vars);
-    let num_poly_vars_x = num_cons.log_2() as usize;
-    let num_poly_vars_y = (2 * num_vars).log_2() as usize;
-    let gens =
-      SparseMatPolyCommitmentGens::new(label, num_poly_vars_x, num_poly_vars_y, num_nz_entries, 3);
+    let num_poly_vars_x = num_cons.log_2();
+    let num_poly_vars_y = (2 * num_vars).log_2();
+    let gens = SparseMatPolyCommitmentGens::new(
+      label,
+      num_poly_vars_x,
+      num_poly_vars_y,
+      num_nz_entries,
+      3,
+      mode,
+      derive,
+    );
     R1CSCommitmentGens { gens }
   }
 }
@@ -60,6 +121,7 @@ pub struct R1CSCommitment<G: CurveGroup> {
   num_vars: usize,
   num_inputs: usize,
   comm: SparseMatPolyCommitment<G>,
+  rounds: Vec<WitnessRound>,
 }
 
 impl<G: CurveGroup> AppendToTranscript<G> for R1CSCommitment<G> {
@@ -67,6 +129,10 @@ impl<G: CurveGroup> AppendToTranscript<G> for R1CSCommitment<G> {
     transcript.append_u64(b"num_cons", self.num_cons as u64);
     transcript.append_u64(b"num_vars", self.num_vars as u64);
     transcript.append_u64(b"num_inputs", self.num_inputs as u64);
+    for round in self.rounds.iter() {
+      transcript.append_u64(b"round_num_vars", round.num_vars as u64);
+      transcript.append_u64(b"round_num_challenges", round.num_challenges as u64);
+    }
     self.comm.append_to_transcript(b"comm", transcript);
   }
 }
@@ -87,6 +153,10 @@ impl<G: CurveGroup> R1CSCommitment<G> {
   pub fn get_num_inputs(&self) -> usize {
     self.num_inputs
   }
+
+  pub fn get_rounds(&self) -> &[WitnessRound] {
+    &self.rounds
+  }
 }
 
 impl<F: PrimeField> R1CSInstance<F> {
@@ -115,8 +185,8 @@ impl<F: PrimeField> R1CSInstance<F> {
     assert!(num_inputs < num_vars);
 
     // no errors, so create polynomials
-    let num_poly_vars_x = num_cons.log_2() as usize;
-    let num_poly_vars_y = (2 * num_vars).log_2() as usize;
+    let num_poly_vars_x = num_cons.log_2();
+    let num_poly_vars_y = (2 * num_vars).log_2();
 
     let mat_A = (0..A.len())
       .map(|i| SparseMatEntry::new(A[i].0, A[i].1, A[i].2))
@@ -139,6 +209,7 @@ impl<F: PrimeField> R1CSInstance<F> {
       A: poly_A,
       B: poly_B,
       C: poly_C,
+      rounds: Vec::new(),
     }
   }
 
@@ -154,6 +225,92 @@ impl<F: PrimeField> R1CSInstance<F> {
     self.num_inputs
   }
 
+  // the SHA3-256 hash of a label and the serialization of the instance, which is fed to the
+  // hash as it is produced rather than buffered, since the matrices can be large
+  pub(crate) fn digest(&self) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.input(b"Spartan instance");
+    self.serialize_compressed(HashWriter(&mut hasher)).unwrap();
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&hasher.result());
+    digest
+  }
+
+  /// the largest number of non-zero entries in any of A, B, and C, as expected by `SNARKGens`
+  pub fn get_num_nz_entries(&self) -> usize {
+    [&self.A, &self.B, &self.C]
+      .iter()
+      .map(|M| M.get_num_nz_entries())
+      .max()
+      .unwrap()
+  }
+
+  /// the constraints of the instance, one per (padded) row of the matrices, without the
+  /// entries whose coefficient is zero
+  pub fn constraints(&self) -> Vec<Constraint<F>> {
+    let mut constraints = (0..self.num_cons)
+      .map(|_| Constraint {
+        A: Vec::new(),
+        B: Vec::new(),
+        C: Vec::new(),
+      })
+      .collect::<Vec<Constraint<F>>>();
+    for (row, col, val) in self.A.entries().filter(|(_, _, val)| !val.is_zero()) {
+      constraints[row].A.push((col, val));
+    }
+    for (row, col, val) in self.B.entries().filter(|(_, _, val)| !val.is_zero()) {
+      constraints[row].B.push((col, val));
+    }
+    for (row, col, val) in self.C.entries().filter(|(_, _, val)| !val.is_zero()) {
+      constraints[row].C.push((col, val));
+    }
+    constraints
+  }
+
+  pub(crate) fn get_matrices(&self) -> [&SparseMatPolynomial<F>; 3] {
+    [&self.A, &self.B, &self.C]
+  }
+
+  /// the rounds in which the prover supplies the variables; empty if all variables are
+  /// supplied at once
+  pub fn get_rounds(&self) -> &[WitnessRound] {
+    &self.rounds
+  }
+
+  /// the number of inputs that are verifier challenges
+  pub fn get_num_challenges(&self) -> usize {
+    self.rounds.iter().map(|round| round.num_challenges).sum()
+  }
+
+  // every round but the last must end at a row boundary of the commitment to the variables,
+  // so that the rows of each round can be committed before the later rounds are known
+  pub(crate) fn set_rounds(&mut self, rounds: &[WitnessRound]) -> Result<(), R1CSError> {
+    let (_left, right) = EqPolynomial::<F>::compute_factored_lens(self.num_vars.log_2());
+    let row_len = right.pow2();
+
+    let mut num_round_vars = 0;
+    for (i, round) in rounds.iter().enumerate() {
+      num_round_vars += round.num_vars;
+      let is_last = i == rounds.len() - 1;
+      if !is_last && !num_round_vars.is_multiple_of(row_len) {
+        return Err(R1CSError::InvalidWitnessRounds);
+      }
+      if is_last && round.num_challenges != 0 {
+        return Err(R1CSError::InvalidWitnessRounds);
+      }
+    }
+    let num_challenges = rounds
+      .iter()
+      .map(|round| round.num_challenges)
+      .sum::<usize>();
+    if num_round_vars > self.num_vars || num_challenges > self.num_inputs {
+      return Err(R1CSError::InvalidWitnessRounds);
+    }
+
+    self.rounds = rounds.to_vec();
+    Ok(())
+  }
+
   pub fn produce_synthetic_r1cs(
     num_cons: usize,
     num_vars: usize,
@@ -166,8 +323,8 @@ impl<F: PrimeField> R1CSInstance<F> {
     let mut prng = test_rng();
 
     // assert num_cons and num_vars are power of 2
-    assert_eq!((num_cons.log_2() as usize).pow2(), num_cons);
-    assert_eq!((num_vars.log_2() as usize).pow2(), num_vars);
+    assert_eq!(num_cons.log_2().pow2(), num_cons);
+    assert_eq!(num_vars.log_2().pow2(), num_vars);
 
     // num_inputs + 1 <= num_vars
     assert!(num_inputs < num_vars);
@@ -212,8 +369,8 @@ impl<F: PrimeField> R1CSInstance<F> {
     Timer::print(&format!("number_non-zero_entries_B {}", B.len()));
     Timer::print(&format!("number_non-zero_entries_C {}", C.len()));
 
-    let num_poly_vars_x = num_cons.log_2() as usize;
-    let num_poly_vars_y = (2 * num_vars).log_2() as usize;
+    let num_poly_vars_x = num_cons.log_2();
+    let num_poly_vars_y = (2 * num_vars).log_2();
     let poly_A = SparseMatPolynomial::new(num_poly_vars_x, num_poly_vars_y, A);
     let poly_B = SparseMatPolynomial::new(num_poly_vars_x, num_poly_vars_y, B);
     let poly_C = SparseMatPolynomial::new(num_poly_vars_x, num_poly_vars_y, C);
@@ -225,6 +382,7 @@ impl<F: PrimeField> R1CSInstance<F> {
       A: poly_A,
       B: poly_B,
       C: poly_C,
+      rounds: Vec::new(),
     };
 
     assert!(inst.is_sat(&Z[..num_vars], &Z[num_vars + 1..]));
@@ -301,6 +459,15 @@ impl<F: PrimeField> R1CSInstance<F> {
     (evals[0], evals[1], evals[2])
   }
 
+  // evaluates the matrices at a point supplied by a prover, which must have one coordinate per
+  // variable of the matrices
+  pub(crate) fn evaluate_claimed(&self, rx: &[F], ry: &[F]) -> Result<(F, F, F), ProofVerifyError> {
+    if rx.len() != self.num_cons.log_2() || ry.len() != (2 * self.num_vars).log_2() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    Ok(self.evaluate(rx, ry))
+  }
+
   pub fn commit<G: CurveGroup<ScalarField = F>>(
     &self,
     gens: &R1CSCommitmentGens<G>,
@@ -311,6 +478,7 @@ impl<F: PrimeField> R1CSInstance<F> {
       num_vars: self.num_vars,
       num_inputs: self.num_inputs,
       comm,
+      rounds: self.rounds.clone(),
     };
 
     let r1cs_decomm = R1CSDecommitment { dense };
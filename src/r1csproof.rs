@@ -1,15 +1,17 @@
 #![allow(clippy::too_many_arguments)]
-use super::commitments::{Commitments, MultiCommitGens};
+use super::commitments::{Commitments, GensDerivation, MultiCommitGens};
 use super::dense_mlpoly::{
-  DensePolynomial, EqPolynomial, PolyCommitment, PolyCommitmentGens, PolyEvalProof,
+  DensePolynomial, EqPolynomial, PolyCommitment, PolyCommitmentBlinds, PolyCommitmentGens,
+  PolyEvalProof,
 };
 use super::errors::ProofVerifyError;
 use super::math::Math;
-use super::nizk::{EqualityProof, KnowledgeProof, ProductProof};
-use super::r1csinstance::R1CSInstance;
+use super::nizk::{DotProductProof, EqualityProof, KnowledgeProof, ProductProof};
+use super::r1csinstance::{R1CSInstance, WitnessRound};
 use super::random::RandomTape;
+use super::relaxed_r1cs::{FoldingGens, RelaxedR1CSInstance, RelaxedR1CSWitness};
 use super::sparse_mlpoly::{SparsePolyEntry, SparsePolynomial};
-use super::sumcheck::ZKSumcheckInstanceProof;
+use super::sumcheck::{SumcheckInstanceProof, ZKSumcheckGens, ZKSumcheckInstanceProof};
 use super::timer::Timer;
 use super::transcript::{AppendToTranscript, ProofTranscript};
 use ark_ec::CurveGroup;
@@ -29,39 +31,84 @@ pub struct R1CSProof<G: CurveGroup> {
   comm_vars_at_ry: G,
   proof_eval_vars_at_ry: PolyEvalProof<G>,
   proof_eq_sc_phase2: EqualityProof<G>,
+  segment_proofs: Vec<WitnessSegmentProof<G>>,
 }
 
-pub struct R1CSSumcheckGens<G> {
-  gens_1: MultiCommitGens<G>,
-  gens_3: MultiCommitGens<G>,
-  gens_4: MultiCommitGens<G>,
+/// `WitnessSegment` binds the variables `start..start + gens.n` of the witness to an
+/// externally produced Pedersen commitment `comm` under `gens`. The length of the segment
+/// must be a power of two and `start` must be a multiple of it. In data-parallel proofs,
+/// `start` indexes into the concatenation of the variables of all copies.
+pub struct WitnessSegment<'a, G: CurveGroup> {
+  pub start: usize,
+  pub gens: &'a MultiCommitGens<G>,
+  pub comm: G,
 }
 
-// TODO: fix passing gens_1_ref
-impl<G: CurveGroup> R1CSSumcheckGens<G> {
-  pub fn new(label: &'static [u8], gens_1_ref: &MultiCommitGens<G>) -> Self {
-    let gens_1 = gens_1_ref.clone();
-    let gens_3 = MultiCommitGens::new(3, label);
-    let gens_4 = MultiCommitGens::new(4, label);
-
-    R1CSSumcheckGens {
-      gens_1,
-      gens_3,
-      gens_4,
-    }
+impl<'a, G: CurveGroup> WitnessSegment<'a, G> {
+  fn is_aligned(&self, num_vars: usize) -> bool {
+    let len = self.gens.n();
+    len > 0
+      && len.next_power_of_two() == len
+      && self.start.is_multiple_of(len)
+      && self.start + len <= num_vars
+  }
+
+  // the point at which the witness polynomial evaluates to the segment's polynomial at r
+  fn point(&self, num_vars: usize, r: &[G::ScalarField]) -> Vec<G::ScalarField> {
+    let num_bits = num_vars.log_2() - self.gens.n().log_2();
+    let mut point = (0..num_bits)
+      .map(|i| {
+        if (self.start / self.gens.n()) >> (num_bits - 1 - i) & 1 == 1 {
+          G::ScalarField::one()
+        } else {
+          G::ScalarField::zero()
+        }
+      })
+      .collect::<Vec<G::ScalarField>>();
+    point.extend_from_slice(r);
+    point
   }
 }
 
-pub struct R1CSGens<G> {
-  gens_sc: R1CSSumcheckGens<G>,
-  gens_pc: PolyCommitmentGens<G>,
+/// Proves that a segment of the committed witness opens an external commitment: both are
+/// evaluated at a random point and the two evaluations are shown to be equal, without
+/// revealing them
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+struct WitnessSegmentProof<G: CurveGroup> {
+  comm_eval: G,
+  proof_eval: PolyEvalProof<G>,
+  proof_dotprod: DotProductProof<G>,
+}
+
+pub struct R1CSGens<G: CurveGroup> {
+  gens_sc: ZKSumcheckGens<G>,
+  pub(crate) gens_pc: PolyCommitmentGens<G>,
 }
 
 impl<G: CurveGroup> R1CSGens<G> {
-  pub fn new(label: &'static [u8], _num_cons: usize, num_vars: usize) -> Self {
-    let num_poly_vars = num_vars.log_2() as usize;
-    let gens_pc = PolyCommitmentGens::new(num_poly_vars, label);
-    let gens_sc = R1CSSumcheckGens::new(label, &gens_pc.gens.gens_1);
+  pub fn new(label: &'static [u8], num_cons: usize, num_vars: usize) -> Self {
+    R1CSGens::new_with_derivation(label, num_cons, num_vars, MultiCommitGens::new)
+  }
+
+  pub fn new_with_derivation(
+    label: &'static [u8],
+    num_cons: usize,
+    num_vars: usize,
+    derive: GensDerivation<G>,
+  ) -> Self {
+    let num_poly_vars = num_vars.log_2();
+    let mut gens_pc = PolyCommitmentGens::new_with_derivation(num_poly_vars, label, derive);
+    // the ZK sum-checks and the sigma protocols commit to many single values under `gens_1`
+    gens_pc.gens.gens_1.precompute_fixed_base_tables();
+    // the first sum-check has a cubic polynomial for each of the (at most
+    // log(num_cons) + log(num_vars)) rounds, which dominates the quadratic ones of the second
+    let num_coeffs_sc = 3 * (num_cons.log_2() + num_poly_vars + 1);
+    let gens_sc = ZKSumcheckGens::new_with_derivation(
+      num_coeffs_sc,
+      &[label, b"_sumcheck"].concat(),
+      &gens_pc.gens.gens_1,
+      derive,
+    );
     R1CSGens { gens_sc, gens_pc }
   }
 }
@@ -74,7 +121,7 @@ impl<G: CurveGroup> R1CSProof<G> {
     evals_Az: &mut DensePolynomial<G::ScalarField>,
     evals_Bz: &mut DensePolynomial<G::ScalarField>,
     evals_Cz: &mut DensePolynomial<G::ScalarField>,
-    gens: &R1CSSumcheckGens<G>,
+    gens: &ZKSumcheckGens<G>,
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
   ) -> (
@@ -100,11 +147,11 @@ impl<G: CurveGroup> R1CSProof<G> {
         evals_Bz,
         evals_Cz,
         comb_func,
-        &gens.gens_1,
-        &gens.gens_4,
+        gens,
         transcript,
         random_tape,
-      );
+      )
+      .expect("instances have at least two constraints");
 
     (sc_proof_phase_one, r, claims, blind_claim_postsc)
   }
@@ -116,7 +163,7 @@ impl<G: CurveGroup> R1CSProof<G> {
     blind_claim: &G::ScalarField,
     evals_z: &mut DensePolynomial<G::ScalarField>,
     evals_ABC: &mut DensePolynomial<G::ScalarField>,
-    gens: &R1CSSumcheckGens<G>,
+    gens: &ZKSumcheckGens<G>,
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
   ) -> (
@@ -135,11 +182,11 @@ impl<G: CurveGroup> R1CSProof<G> {
       evals_z,
       evals_ABC,
       comb_func,
-      &gens.gens_1,
-      &gens.gens_3,
+      gens,
       transcript,
       random_tape,
-    );
+    )
+    .expect("instances have at least one variable");
 
     (sc_proof_phase_two, r, claims, blind_claim_postsc)
   }
@@ -155,6 +202,37 @@ impl<G: CurveGroup> R1CSProof<G> {
     gens: &R1CSGens<G>,
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    R1CSProof::prove_uniform(inst, vec![vars], &[input], gens, transcript, random_tape)
+  }
+
+  /// Proves the satisfiability of `vars.len()` copies of the same R1CS instance, i.e., of
+  /// the block-diagonal instance with one block per copy. The matrices are only ever
+  /// evaluated at points of the single instance, so their commitment does not depend on
+  /// the number of copies. `gens` must support `vars.len() * inst.get_num_vars()` variables.
+  pub fn prove_uniform(
+    inst: &R1CSInstance<G::ScalarField>,
+    vars: Vec<Vec<G::ScalarField>>,
+    inputs: &[&[G::ScalarField]],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    R1CSProof::prove_with_segments(inst, vars, inputs, &[], &[], gens, transcript, random_tape)
+  }
+
+  /// Like `prove_uniform`, but the proof additionally shows that each of `segments` commits to
+  /// the corresponding variables, where `blinds` holds the blinds used to produce the segments'
+  /// commitments.
+  pub fn prove_with_segments(
+    inst: &R1CSInstance<G::ScalarField>,
+    vars: Vec<Vec<G::ScalarField>>,
+    inputs: &[&[G::ScalarField]],
+    segments: &[WitnessSegment<G>],
+    blinds: &[G::ScalarField],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
   ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
     let timer_prove = Timer::new("R1CSProof::prove");
     <Transcript as ProofTranscript<G>>::append_protocol_name(
@@ -162,13 +240,22 @@ impl<G: CurveGroup> R1CSProof<G> {
       R1CSProof::<G>::protocol_name(),
     );
 
-    // we currently require the number of |inputs| + 1 to be at most number of vars
-    assert!(input.len() < vars.len());
-    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    // the number of copies must be a power of two
+    let num_copies = vars.len();
+    assert_eq!(inputs.len(), num_copies);
+    assert_eq!(num_copies.next_power_of_two(), num_copies);
+
+    for (vars, input) in vars.iter().zip(inputs.iter()) {
+      // we currently require the number of |inputs| + 1 to be at most number of vars
+      assert_eq!(vars.len(), inst.get_num_vars());
+      assert!(input.len() < vars.len());
+      <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    }
     let timer_commit = Timer::new("polycommit");
     let (poly_vars, comm_vars, blinds_vars) = {
-      // create a multilinear polynomial using the supplied assignment for variables
-      let poly_vars = DensePolynomial::<G::ScalarField>::new(vars.clone());
+      // create a multilinear polynomial using the supplied assignments for variables,
+      // where the top variables select the copy
+      let poly_vars = DensePolynomial::<G::ScalarField>::new(vars.concat());
 
       // produce a commitment to the satisfying assignment
       let (comm_vars, blinds_vars) = poly_vars.commit(&gens.gens_pc, Some(random_tape));
@@ -179,37 +266,205 @@ impl<G: CurveGroup> R1CSProof<G> {
     };
     timer_commit.stop();
 
+    let proof = R1CSProof::prove_committed(
+      inst,
+      vars,
+      inputs,
+      (poly_vars, comm_vars, blinds_vars),
+      segments,
+      blinds,
+      gens,
+      transcript,
+      random_tape,
+    );
+
+    timer_prove.stop();
+    proof
+  }
+
+  /// Proves the satisfiability of an instance whose variables are supplied over the rounds of
+  /// `inst.get_rounds()`. In round `i`, `witness(i, challenges)` returns the variables of the
+  /// round given the challenges of the previous rounds; they are committed to before the
+  /// challenges of round `i` are derived. `input` excludes the challenges, which are appended
+  /// to it as the last inputs of the instance.
+  pub fn prove_multiround(
+    inst: &R1CSInstance<G::ScalarField>,
+    input: &[G::ScalarField],
+    mut witness: impl FnMut(usize, &[G::ScalarField]) -> Vec<G::ScalarField>,
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    let timer_prove = Timer::new("R1CSProof::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      R1CSProof::<G>::protocol_name(),
+    );
+
+    assert_eq!(
+      input.len() + inst.get_num_challenges(),
+      inst.get_num_inputs()
+    );
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+
+    let timer_commit = Timer::new("polycommit");
+    let rounds = witness_rounds(inst.get_num_vars(), inst.get_rounds());
+    let row_len = gens.gens_pc.gens.gens_n.n();
+    let (mut vars, mut comms, mut blinds, mut challenges) =
+      (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (i, round) in rounds.iter().enumerate() {
+      let round_vars = witness(i, &challenges);
+      assert_eq!(round_vars.len(), round.num_vars);
+
+      // the rows of the commitment that this round completes
+      let start = vars.len();
+      vars.extend(round_vars);
+      if i == rounds.len() - 1 {
+        vars.resize(inst.get_num_vars(), G::ScalarField::zero());
+      }
+      assert_eq!(vars.len() % row_len, 0);
+      let (comm, blinds_round) =
+        PolyCommitment::commit_rows(&vars[start..], &gens.gens_pc, random_tape);
+
+      // add the commitment to the prover's transcript before deriving the round's challenges
+      comm.append_to_transcript(b"poly_commitment", transcript);
+      challenges.extend(<Transcript as ProofTranscript<G>>::challenge_vector(
+        transcript,
+        b"challenge_round",
+        round.num_challenges,
+      ));
+      comms.push(comm);
+      blinds.push(blinds_round);
+    }
+    let poly_vars = DensePolynomial::new(vars.clone());
+    let comm_vars = PolyCommitment::concat(&comms);
+    let blinds_vars = PolyCommitmentBlinds::concat(&blinds);
+    timer_commit.stop();
+
+    let input = [input, &challenges].concat();
+    let proof = R1CSProof::prove_committed(
+      inst,
+      vec![vars],
+      &[&input],
+      (poly_vars, comm_vars, blinds_vars),
+      &[],
+      &[],
+      gens,
+      transcript,
+      random_tape,
+    );
+
+    timer_prove.stop();
+    proof
+  }
+
+  // the part of the proof after the prover commits to the variables
+  #[allow(clippy::type_complexity)]
+  fn prove_committed(
+    inst: &R1CSInstance<G::ScalarField>,
+    vars: Vec<Vec<G::ScalarField>>,
+    inputs: &[&[G::ScalarField]],
+    committed_vars: (
+      DensePolynomial<G::ScalarField>,
+      PolyCommitment<G>,
+      PolyCommitmentBlinds<G::ScalarField>,
+    ),
+    segments: &[WitnessSegment<G>],
+    blinds: &[G::ScalarField],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    let num_copies = vars.len();
+    let (poly_vars, comm_vars, blinds_vars) = committed_vars;
+
+    // link the committed witness to the externally committed segments
+    assert_eq!(segments.len(), blinds.len());
+    let segment_proofs = segments
+      .iter()
+      .zip(blinds.iter())
+      .map(|(segment, blind)| {
+        assert!(segment.is_aligned(poly_vars.len()));
+        <Transcript as ProofTranscript<G>>::append_point(
+          transcript,
+          b"segment_comm",
+          &segment.comm,
+        );
+        let r = <Transcript as ProofTranscript<G>>::challenge_vector(
+          transcript,
+          b"challenge_segment",
+          segment.gens.n().log_2(),
+        );
+        let point = segment.point(poly_vars.len(), &r);
+        let eval = poly_vars.evaluate::<G>(&point);
+        let blind_eval = random_tape.random_scalar(b"blind_eval");
+        let (proof_eval, comm_eval) = PolyEvalProof::prove(
+          &poly_vars,
+          Some(&blinds_vars),
+          &point,
+          &eval,
+          Some(&blind_eval),
+          &gens.gens_pc,
+          transcript,
+          random_tape,
+        );
+        let (proof_dotprod, _comm_segment, _comm_eval) = DotProductProof::prove(
+          &gens.gens_pc.gens.gens_1,
+          segment.gens,
+          transcript,
+          random_tape,
+          &vars_segment(&vars, segment),
+          blind,
+          &EqPolynomial::new(r).evals(),
+          &eval,
+          &blind_eval,
+        );
+        WitnessSegmentProof {
+          comm_eval,
+          proof_eval,
+          proof_dotprod,
+        }
+      })
+      .collect::<Vec<WitnessSegmentProof<G>>>();
+
     let timer_sc_proof_phase1 = Timer::new("prove_sc_phase_one");
 
-    // append input to variables to create a single vector z
-    let z = {
-      let num_inputs = input.len();
-      let num_vars = vars.len();
-      let mut z = vars;
-      z.extend(&vec![G::ScalarField::one()]); // add constant term in z
-      z.extend(input);
-      z.extend(&vec![G::ScalarField::zero(); num_vars - num_inputs - 1]); // we will pad with zeros
-      z
-    };
+    // append input to variables of each copy to create vectors z_1, ..., z_N
+    let zs = vars
+      .into_iter()
+      .zip(inputs.iter())
+      .map(|(vars, input)| build_z::<G>(vars, G::ScalarField::one(), input))
+      .collect::<Vec<Vec<G::ScalarField>>>();
 
     // derive the verifier's challenge tau
-    let (num_rounds_x, num_rounds_y) = (
-      inst.get_num_cons().log_2() as usize,
-      z.len().log_2() as usize,
-    );
+    let num_rounds_k = num_copies.log_2();
+    let (num_rounds_x, num_rounds_y) = (inst.get_num_cons().log_2(), zs[0].len().log_2());
     let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
       transcript,
       b"challenge_tau",
-      num_rounds_x,
+      num_rounds_k + num_rounds_x,
     );
 
-    // compute the initial evaluation table for R(\tau, x)
+    // compute the initial evaluation table for R(\tau, (k, x)); the block-diagonal
+    // structure lets us multiply each copy with the small matrices separately
     let mut poly_tau = DensePolynomial::new(EqPolynomial::new(tau).evals());
-    let (mut poly_Az, mut poly_Bz, mut poly_Cz) =
-      inst.multiply_vec(inst.get_num_cons(), z.len(), &z);
+    let (mut poly_Az, mut poly_Bz, mut poly_Cz) = {
+      let (mut Az, mut Bz, mut Cz) = (Vec::new(), Vec::new(), Vec::new());
+      for z in zs.iter() {
+        let (Az_k, Bz_k, Cz_k) = inst.multiply_vec(inst.get_num_cons(), z.len(), z);
+        Az.push(Az_k);
+        Bz.push(Bz_k);
+        Cz.push(Cz_k);
+      }
+      (
+        DensePolynomial::merge(&Az),
+        DensePolynomial::merge(&Bz),
+        DensePolynomial::merge(&Cz),
+      )
+    };
 
-    let (sc_proof_phase1, rx, _claims_phase1, blind_claim_postsc1) = R1CSProof::prove_phase_one(
-      num_rounds_x,
+    let (sc_proof_phase1, rkx, _claims_phase1, blind_claim_postsc1) = R1CSProof::prove_phase_one(
+      num_rounds_k + num_rounds_x,
       &mut poly_tau,
       &mut poly_Az,
       &mut poly_Bz,
@@ -222,6 +477,7 @@ impl<G: CurveGroup> R1CSProof<G> {
     assert_eq!(poly_Az.len(), 1);
     assert_eq!(poly_Bz.len(), 1);
     assert_eq!(poly_Cz.len(), 1);
+    let (rk, rx) = (rkx[..num_rounds_k].to_vec(), rkx[num_rounds_k..].to_vec());
     timer_sc_proof_phase1.stop();
 
     let (tau_claim, Az_claim, Bz_claim, Cz_claim) =
@@ -283,23 +539,23 @@ impl<G: CurveGroup> R1CSProof<G> {
 
     let timer_sc_proof_phase2 = Timer::new("prove_sc_phase_two");
     // combine the three claims into a single claim
-    let r_A = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Az");
-    let r_B = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Bz");
-    let r_C = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Cz");
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
     let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
     let blind_claim_phase2 = r_A * Az_blind + r_B * Bz_blind + r_C * Cz_blind;
-
-    let evals_ABC = {
-      // compute the initial evaluation table for R(\tau, x)
-      let evals_rx = EqPolynomial::new(rx.clone()).evals();
-      let (evals_A, evals_B, evals_C) =
-        inst.compute_eval_table_sparse(inst.get_num_cons(), z.len(), &evals_rx);
-
-      assert_eq!(evals_A.len(), evals_B.len());
-      assert_eq!(evals_A.len(), evals_C.len());
-      (0..evals_A.len())
-        .map(|i| r_A * evals_A[i] + r_B * evals_B[i] + r_C * evals_C[i])
-        .collect::<Vec<G::ScalarField>>()
+    let evals_ABC = evals_ABC::<G>(inst, &rx, zs[0].len(), (r_A, r_B, r_C));
+
+    // the evaluation table of Z(rk, y) = \sum_k eq(rk, k) * z_k(y)
+    let z = if num_copies == 1 {
+      zs.into_iter().next().unwrap()
+    } else {
+      let evals_rk = EqPolynomial::new(rk.clone()).evals();
+      let mut z = vec![G::ScalarField::zero(); zs[0].len()];
+      for (z_k, eq_k) in zs.iter().zip(evals_rk.iter()) {
+        for (z_i, z_k_i) in z.iter_mut().zip(z_k.iter()) {
+          *z_i += *eq_k * z_k_i;
+        }
+      }
+      z
     };
 
     // another instance of the sum-check protocol
@@ -316,12 +572,13 @@ impl<G: CurveGroup> R1CSProof<G> {
     timer_sc_proof_phase2.stop();
 
     let timer_polyeval = Timer::new("polyeval");
-    let eval_vars_at_ry = poly_vars.evaluate::<G>(&ry[1..]);
+    let r_vars = [rk.as_slice(), &ry[1..]].concat();
+    let eval_vars_at_ry = poly_vars.evaluate::<G>(&r_vars);
     let blind_eval = random_tape.random_scalar(b"blind_eval");
     let (proof_eval_vars_at_ry, comm_vars_at_ry) = PolyEvalProof::prove(
       &poly_vars,
       Some(&blinds_vars),
-      &ry[1..],
+      &r_vars,
       &eval_vars_at_ry,
       Some(&blind_eval),
       &gens.gens_pc,
@@ -344,8 +601,6 @@ impl<G: CurveGroup> R1CSProof<G> {
       &blind_claim_postsc2,
     );
 
-    timer_prove.stop();
-
     (
       R1CSProof {
         comm_vars,
@@ -362,6 +617,7 @@ impl<G: CurveGroup> R1CSProof<G> {
         comm_vars_at_ry,
         proof_eval_vars_at_ry,
         proof_eq_sc_phase2,
+        segment_proofs,
       },
       rx,
       ry,
@@ -377,38 +633,179 @@ impl<G: CurveGroup> R1CSProof<G> {
     evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
     transcript: &mut Transcript,
     gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    self.verify_uniform(num_vars, num_cons, &[input], evals, transcript, gens)
+  }
+
+  /// Verifies a proof produced by `prove_uniform` for `inputs.len()` copies of an instance
+  /// with `num_vars` variables and `num_cons` constraints
+  #[allow(clippy::type_complexity)]
+  pub fn verify_uniform(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    inputs: &[&[G::ScalarField]],
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    self.verify_with_segments(num_vars, num_cons, inputs, &[], evals, transcript, gens)
+  }
+
+  /// Verifies a proof produced by `prove_with_segments`, given the same `segments`
+  #[allow(clippy::type_complexity)]
+  pub fn verify_with_segments(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    inputs: &[&[G::ScalarField]],
+    segments: &[WitnessSegment<G>],
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
   ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
       R1CSProof::<G>::protocol_name(),
     );
 
-    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    // the number of copies must be a power of two
+    let num_copies = inputs.len();
+    if num_copies.next_power_of_two() != num_copies {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    for input in inputs {
+      <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    }
 
-    let n = num_vars;
     // add the commitment to the verifier's transcript
     self
       .comm_vars
       .append_to_transcript(b"poly_commitment", transcript);
 
-    let (num_rounds_x, num_rounds_y) = (num_cons.log_2() as usize, (2 * num_vars).log_2() as usize);
+    self.verify_committed(
+      num_vars, num_cons, inputs, segments, evals, transcript, gens,
+    )
+  }
+
+  /// Verifies a proof produced by `prove_multiround` for an instance with `num_vars`
+  /// variables, `num_cons` constraints, and the given witness `rounds`, where `input`
+  /// excludes the challenges
+  #[allow(clippy::type_complexity)]
+  pub fn verify_multiround(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    rounds: &[WitnessRound],
+    input: &[G::ScalarField],
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      R1CSProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+
+    // add the commitment to each round's rows to the verifier's transcript
+    let rounds = witness_rounds(num_vars, rounds);
+    let row_len = gens.gens_pc.gens.gens_n.n();
+    let num_rows = self.comm_vars.get_num_rows();
+    let (mut num_round_vars, mut start, mut challenges) = (0, 0, Vec::new());
+    for (i, round) in rounds.iter().enumerate() {
+      num_round_vars += round.num_vars;
+      let end = if i == rounds.len() - 1 {
+        num_rows
+      } else {
+        num_round_vars / row_len
+      };
+      if num_round_vars % row_len != 0 && i != rounds.len() - 1 || end < start || end > num_rows {
+        return Err(ProofVerifyError::InternalError);
+      }
+      self
+        .comm_vars
+        .get_rows(start, end)
+        .append_to_transcript(b"poly_commitment", transcript);
+      challenges.extend(<Transcript as ProofTranscript<G>>::challenge_vector(
+        transcript,
+        b"challenge_round",
+        round.num_challenges,
+      ));
+      start = end;
+    }
+
+    let input = [input, &challenges].concat();
+    self.verify_committed(num_vars, num_cons, &[&input], &[], evals, transcript, gens)
+  }
+
+  // the part of the verification after the verifier receives the commitment to the variables
+  #[allow(clippy::type_complexity)]
+  fn verify_committed(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    inputs: &[&[G::ScalarField]],
+    segments: &[WitnessSegment<G>],
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    let num_copies = inputs.len();
+    let n = num_vars;
+
+    // check the links between the committed witness and the external segment commitments
+    if segments.len() != self.segment_proofs.len() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    for (segment, proof) in segments.iter().zip(self.segment_proofs.iter()) {
+      if !segment.is_aligned(num_copies * n) {
+        return Err(ProofVerifyError::InternalError);
+      }
+      <Transcript as ProofTranscript<G>>::append_point(transcript, b"segment_comm", &segment.comm);
+      let r = <Transcript as ProofTranscript<G>>::challenge_vector(
+        transcript,
+        b"challenge_segment",
+        segment.gens.n().log_2(),
+      );
+      let point = segment.point(num_copies * n, &r);
+      proof.proof_eval.verify(
+        &gens.gens_pc,
+        transcript,
+        &point,
+        &proof.comm_eval,
+        &self.comm_vars,
+      )?;
+      proof.proof_dotprod.verify(
+        &gens.gens_pc.gens.gens_1,
+        segment.gens,
+        transcript,
+        &EqPolynomial::new(r).evals(),
+        &segment.comm,
+        &proof.comm_eval,
+      )?;
+    }
+
+    let num_rounds_k = num_copies.log_2();
+    let (num_rounds_x, num_rounds_y) = (num_cons.log_2(), (2 * num_vars).log_2());
 
     // derive the verifier's challenge tau
     let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
       transcript,
       b"challenge_tau",
-      num_rounds_x,
+      num_rounds_k + num_rounds_x,
     );
 
     // verify the first sum-check instance
     let claim_phase1 = G::ScalarField::zero().commit(&G::ScalarField::zero(), &gens.gens_sc.gens_1);
 
-    let (comm_claim_post_phase1, rx) = self.sc_proof_phase1.verify(
+    let (comm_claim_post_phase1, rkx) = self.sc_proof_phase1.verify(
       &claim_phase1,
-      num_rounds_x,
+      num_rounds_k + num_rounds_x,
       3,
-      &gens.gens_sc.gens_1,
-      &gens.gens_sc.gens_4,
+      &gens.gens_sc,
       transcript,
     )?;
     // perform the intermediate sum-check test with claimed Az, Bz, and Cz
@@ -433,9 +830,12 @@ impl<G: CurveGroup> R1CSProof<G> {
       comm_prod_Az_Bz_claims,
     );
 
-    let taus_bound_rx: G::ScalarField = (0..rx.len())
-      .map(|i| rx[i] * tau[i] + (G::ScalarField::one() - rx[i]) * (G::ScalarField::one() - tau[i]))
+    let taus_bound_rx: G::ScalarField = (0..rkx.len())
+      .map(|i| {
+        rkx[i] * tau[i] + (G::ScalarField::one() - rkx[i]) * (G::ScalarField::one() - tau[i])
+      })
       .product();
+    let (rk, rx) = (rkx[..num_rounds_k].to_vec(), rkx[num_rounds_k..].to_vec());
     let expected_claim_post_phase1 = (*comm_prod_Az_Bz_claims - *comm_Cz_claim) * taus_bound_rx;
 
     // verify proof that expected_claim_post_phase1 == claim_post_phase1
@@ -447,9 +847,7 @@ impl<G: CurveGroup> R1CSProof<G> {
     )?;
 
     // derive three public challenges and then derive a joint claim
-    let r_A = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Az");
-    let r_B = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Bz");
-    let r_C = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Cz");
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
 
     // r_A * comm_Az_claim + r_B * comm_Bz_claim + r_C * comm_Cz_claim;
     let scalars = vec![r_A, r_B, r_C];
@@ -464,31 +862,23 @@ impl<G: CurveGroup> R1CSProof<G> {
       &comm_claim_phase2,
       num_rounds_y,
       2,
-      &gens.gens_sc.gens_1,
-      &gens.gens_sc.gens_3,
+      &gens.gens_sc,
       transcript,
     )?;
 
-    // verify Z(ry) proof against the initial commitment
+    // verify Z(rk, ry) proof against the initial commitment
     self.proof_eval_vars_at_ry.verify(
       &gens.gens_pc,
       transcript,
-      &ry[1..],
+      &[rk.as_slice(), &ry[1..]].concat(),
       &self.comm_vars_at_ry,
       &self.comm_vars,
     )?;
 
-    let poly_input_eval = {
-      // constant term
-      let mut input_as_sparse_poly_entries = vec![SparsePolyEntry::new(0, G::ScalarField::one())];
-      //remaining inputs
-      input_as_sparse_poly_entries.extend(
-        (0..input.len())
-          .map(|i| SparsePolyEntry::new(i + 1, input[i]))
-          .collect::<Vec<SparsePolyEntry<G::ScalarField>>>(),
-      );
-      SparsePolynomial::new(n.log_2() as usize, input_as_sparse_poly_entries).evaluate(&ry[1..])
-    };
+    let evals_rk = EqPolynomial::new(rk).evals();
+    let poly_input_eval = (0..num_copies)
+      .map(|k| evals_rk[k] * eval_inputs::<G>(n, G::ScalarField::one(), inputs[k], &ry[1..]))
+      .sum::<G::ScalarField>();
 
     // compute commitment to eval_Z_at_ry = (F::one() - ry[0]) * self.eval_vars_at_ry + ry[0] * poly_input_eval
     let scalars = vec![(G::ScalarField::one() - ry[0]), ry[0]];
@@ -519,6 +909,598 @@ impl<G: CurveGroup> R1CSProof<G> {
   }
 }
 
+// the rounds of a witness, where an instance without rounds has a single round
+fn witness_rounds(num_vars: usize, rounds: &[WitnessRound]) -> Vec<WitnessRound> {
+  if rounds.is_empty() {
+    vec![WitnessRound {
+      num_vars,
+      num_challenges: 0,
+    }]
+  } else {
+    rounds.to_vec()
+  }
+}
+
+// the variables covered by `segment` in the concatenation of the variables of all copies
+fn vars_segment<F: Clone, G: CurveGroup<ScalarField = F>>(
+  vars: &[Vec<F>],
+  segment: &WitnessSegment<G>,
+) -> Vec<F> {
+  vars
+    .iter()
+    .flatten()
+    .skip(segment.start)
+    .take(segment.gens.n())
+    .cloned()
+    .collect()
+}
+
+// append the constant term and the inputs to the variables to create a single vector z,
+// which is padded with zeros to twice the number of variables
+pub(crate) fn build_z<G: CurveGroup>(
+  vars: Vec<G::ScalarField>,
+  constant: G::ScalarField,
+  input: &[G::ScalarField],
+) -> Vec<G::ScalarField> {
+  // we currently require the number of |inputs| + 1 to be at most number of vars
+  let num_vars = vars.len();
+  assert!(input.len() < num_vars);
+  let mut z = vars;
+  z.push(constant);
+  z.extend(input);
+  z.resize(2 * num_vars, G::ScalarField::zero());
+  z
+}
+
+// the evaluation at r of the half of z that holds the constant term and the inputs
+pub(crate) fn eval_inputs<G: CurveGroup>(
+  num_vars: usize,
+  constant: G::ScalarField,
+  input: &[G::ScalarField],
+  r: &[G::ScalarField],
+) -> G::ScalarField {
+  let mut entries = vec![SparsePolyEntry::new(0, constant)];
+  entries.extend(
+    input
+      .iter()
+      .enumerate()
+      .map(|(i, x)| SparsePolyEntry::new(i + 1, *x)),
+  );
+  SparsePolynomial::new(num_vars.log_2(), entries).evaluate(r)
+}
+
+// the evaluation of z at ry, where ry[0] selects between the variables and the inputs
+pub(crate) fn eval_Z_at_ry<G: CurveGroup>(
+  ry: &[G::ScalarField],
+  eval_vars_at_ry: G::ScalarField,
+  eval_inputs_at_ry: G::ScalarField,
+) -> G::ScalarField {
+  (G::ScalarField::one() - ry[0]) * eval_vars_at_ry + ry[0] * eval_inputs_at_ry
+}
+
+// derive three public challenges to combine the claims on Az, Bz, and Cz
+fn challenge_ABC<G: CurveGroup>(
+  transcript: &mut Transcript,
+) -> (G::ScalarField, G::ScalarField, G::ScalarField) {
+  let r_A = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Az");
+  let r_B = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Bz");
+  let r_C = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenege_Cz");
+  (r_A, r_B, r_C)
+}
+
+// the evaluation table of r_A * A(rx, y) + r_B * B(rx, y) + r_C * C(rx, y)
+fn evals_ABC<G: CurveGroup>(
+  inst: &R1CSInstance<G::ScalarField>,
+  rx: &[G::ScalarField],
+  num_cols: usize,
+  (r_A, r_B, r_C): (G::ScalarField, G::ScalarField, G::ScalarField),
+) -> Vec<G::ScalarField> {
+  let evals_rx = EqPolynomial::new(rx.to_vec()).evals();
+  let (evals_A, evals_B, evals_C) =
+    inst.compute_eval_table_sparse(inst.get_num_cons(), num_cols, &evals_rx);
+
+  assert_eq!(evals_A.len(), evals_B.len());
+  assert_eq!(evals_A.len(), evals_C.len());
+  (0..evals_A.len())
+    .map(|i| r_A * evals_A[i] + r_B * evals_B[i] + r_C * evals_C[i])
+    .collect()
+}
+
+// a non-hiding commitment to the variables, which is added to the transcript
+pub(crate) fn commit_vars_plain<G: CurveGroup>(
+  vars: &[G::ScalarField],
+  gens: &R1CSGens<G>,
+  transcript: &mut Transcript,
+) -> (DensePolynomial<G::ScalarField>, PolyCommitment<G>) {
+  let poly_vars = DensePolynomial::new(vars.to_vec());
+  let (comm_vars, _blinds_vars) = poly_vars.commit(&gens.gens_pc, None);
+  comm_vars.append_to_transcript(b"poly_commitment", transcript);
+  (poly_vars, comm_vars)
+}
+
+// the second sum-check of the proofs without zero-knowledge, over z(y) * evals(y)
+pub(crate) fn prove_phase_two_plain<G: CurveGroup>(
+  claim: &G::ScalarField,
+  z: Vec<G::ScalarField>,
+  evals: Vec<G::ScalarField>,
+  transcript: &mut Transcript,
+) -> (SumcheckInstanceProof<G::ScalarField>, Vec<G::ScalarField>) {
+  let num_rounds = z.len().log_2();
+  let comb_func = |poly_A_comp: &G::ScalarField, poly_B_comp: &G::ScalarField| -> G::ScalarField {
+    *poly_A_comp * *poly_B_comp
+  };
+  let (sc_proof, r, _claims) = SumcheckInstanceProof::prove_quad::<_, G>(
+    claim,
+    num_rounds,
+    &mut DensePolynomial::new(z),
+    &mut DensePolynomial::new(evals),
+    comb_func,
+    transcript,
+  );
+  (sc_proof, r)
+}
+
+// prove the evaluation of a polynomial under a non-hiding commitment, sending it in the clear
+pub(crate) fn prove_eval_plain<G: CurveGroup>(
+  label: &'static [u8],
+  poly: &DensePolynomial<G::ScalarField>,
+  r: &[G::ScalarField],
+  gens: &PolyCommitmentGens<G>,
+  transcript: &mut Transcript,
+  random_tape: &mut RandomTape<G>,
+) -> (G::ScalarField, PolyEvalProof<G>) {
+  let eval = poly.evaluate::<G>(r);
+  <Transcript as ProofTranscript<G>>::append_scalar(transcript, label, &eval);
+  let (proof, _comm_eval) =
+    PolyEvalProof::prove(poly, None, r, &eval, None, gens, transcript, random_tape);
+  (eval, proof)
+}
+
+// verify an evaluation produced by `prove_eval_plain`
+pub(crate) fn verify_eval_plain<G: CurveGroup>(
+  label: &'static [u8],
+  proof: &PolyEvalProof<G>,
+  r: &[G::ScalarField],
+  eval: &G::ScalarField,
+  comm: &PolyCommitment<G>,
+  gens: &PolyCommitmentGens<G>,
+  transcript: &mut Transcript,
+) -> Result<(), ProofVerifyError> {
+  <Transcript as ProofTranscript<G>>::append_scalar(transcript, label, eval);
+  proof.verify_plain(gens, transcript, r, eval, comm)
+}
+
+/// A variant of `R1CSProof` without zero-knowledge: the witness commitment is
+/// not hiding, sum-check round polynomials and the claims on `Az`, `Bz`, `Cz`
+/// are sent in the clear, and no sigma protocols are needed.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct R1CSPlainProof<G: CurveGroup> {
+  comm_vars: PolyCommitment<G>,
+  sc_proof_phase1: SumcheckInstanceProof<G::ScalarField>,
+  claims_phase2: (G::ScalarField, G::ScalarField, G::ScalarField),
+  sc_proof_phase2: SumcheckInstanceProof<G::ScalarField>,
+  eval_vars_at_ry: G::ScalarField,
+  proof_eval_vars_at_ry: PolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> R1CSPlainProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"R1CS plain proof"
+  }
+
+  pub fn prove(
+    inst: &R1CSInstance<G::ScalarField>,
+    vars: Vec<G::ScalarField>,
+    input: &[G::ScalarField],
+    gens: &R1CSGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (R1CSPlainProof<G>, Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    let timer_prove = Timer::new("R1CSPlainProof::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      R1CSPlainProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+    let timer_commit = Timer::new("polycommit");
+    let (poly_vars, comm_vars) = commit_vars_plain(&vars, gens, transcript);
+    timer_commit.stop();
+
+    let timer_sc_proof_phase1 = Timer::new("prove_sc_phase_one");
+    let z = build_z::<G>(vars, G::ScalarField::one(), input);
+
+    // derive the verifier's challenge tau
+    let num_rounds_x = inst.get_num_cons().log_2();
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // compute the initial evaluation table for R(\tau, x)
+    let mut poly_tau = DensePolynomial::new(EqPolynomial::new(tau).evals());
+    let (mut poly_Az, mut poly_Bz, mut poly_Cz) =
+      inst.multiply_vec(inst.get_num_cons(), z.len(), &z);
+
+    let comb_func =
+      |poly_A_comp: &G::ScalarField,
+       poly_B_comp: &G::ScalarField,
+       poly_C_comp: &G::ScalarField,
+       poly_D_comp: &G::ScalarField|
+       -> G::ScalarField { *poly_A_comp * (*poly_B_comp * *poly_C_comp - *poly_D_comp) };
+    let (sc_proof_phase1, rx, claims_phase1) =
+      SumcheckInstanceProof::prove_cubic_with_additive_term::<_, G>(
+        &G::ScalarField::zero(), // claim is zero
+        num_rounds_x,
+        &mut poly_tau,
+        &mut poly_Az,
+        &mut poly_Bz,
+        &mut poly_Cz,
+        comb_func,
+        transcript,
+      );
+    timer_sc_proof_phase1.stop();
+
+    let (Az_claim, Bz_claim, Cz_claim) = (claims_phase1[1], claims_phase1[2], claims_phase1[3]);
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &[Az_claim, Bz_claim, Cz_claim],
+    );
+
+    let timer_sc_proof_phase2 = Timer::new("prove_sc_phase_two");
+    // combine the three claims into a single claim
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
+    let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
+    let evals = evals_ABC::<G>(inst, &rx, z.len(), (r_A, r_B, r_C));
+    let (sc_proof_phase2, ry) = prove_phase_two_plain::<G>(&claim_phase2, z, evals, transcript);
+    timer_sc_proof_phase2.stop();
+
+    let timer_polyeval = Timer::new("polyeval");
+    let (eval_vars_at_ry, proof_eval_vars_at_ry) = prove_eval_plain(
+      b"eval_vars_at_ry",
+      &poly_vars,
+      &ry[1..],
+      &gens.gens_pc,
+      transcript,
+      random_tape,
+    );
+    timer_polyeval.stop();
+
+    timer_prove.stop();
+
+    (
+      R1CSPlainProof {
+        comm_vars,
+        sc_proof_phase1,
+        claims_phase2: (Az_claim, Bz_claim, Cz_claim),
+        sc_proof_phase2,
+        eval_vars_at_ry,
+        proof_eval_vars_at_ry,
+      },
+      rx,
+      ry,
+    )
+  }
+
+  #[allow(clippy::type_complexity)]
+  pub fn verify(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    input: &[G::ScalarField],
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &R1CSGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      R1CSPlainProof::<G>::protocol_name(),
+    );
+
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"input", input);
+
+    // add the commitment to the verifier's transcript
+    self
+      .comm_vars
+      .append_to_transcript(b"poly_commitment", transcript);
+
+    let (num_rounds_x, num_rounds_y) = (num_cons.log_2(), (2 * num_vars).log_2());
+
+    // derive the verifier's challenge tau
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // verify the first sum-check instance
+    let (claim_post_phase1, rx) =
+      self
+        .sc_proof_phase1
+        .verify::<G>(G::ScalarField::zero(), num_rounds_x, 3, transcript)?;
+
+    // perform the intermediate sum-check test with claimed Az, Bz, and Cz
+    let (Az_claim, Bz_claim, Cz_claim) = self.claims_phase2;
+    let taus_bound_rx = EqPolynomial::new(tau).evaluate(&rx);
+    if claim_post_phase1 != (Az_claim * Bz_claim - Cz_claim) * taus_bound_rx {
+      return Err(ProofVerifyError::InternalError);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &[Az_claim, Bz_claim, Cz_claim],
+    );
+
+    // derive three public challenges and then derive a joint claim
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
+    let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
+
+    // verify the joint claim with a sum-check protocol
+    let (claim_post_phase2, ry) =
+      self
+        .sc_proof_phase2
+        .verify::<G>(claim_phase2, num_rounds_y, 2, transcript)?;
+
+    // verify Z(ry) proof against the initial commitment
+    verify_eval_plain(
+      b"eval_vars_at_ry",
+      &self.proof_eval_vars_at_ry,
+      &ry[1..],
+      &self.eval_vars_at_ry,
+      &self.comm_vars,
+      &gens.gens_pc,
+      transcript,
+    )?;
+
+    let eval_inputs_at_ry = eval_inputs::<G>(num_vars, G::ScalarField::one(), input, &ry[1..]);
+    let eval_Z_at_ry = eval_Z_at_ry::<G>(&ry, self.eval_vars_at_ry, eval_inputs_at_ry);
+
+    // perform the final check in the second sum-check protocol
+    let (eval_A_r, eval_B_r, eval_C_r) = evals;
+    if claim_post_phase2 != eval_Z_at_ry * (r_A * eval_A_r + r_B * eval_B_r + r_C * eval_C_r) {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    Ok((rx, ry))
+  }
+}
+
+/// A proof of satisfiability of a relaxed R1CS instance `Az * Bz = u * Cz + E`, where
+/// `z = (W, u, X)`, against the commitments to `W` and `E` in the instance. Like
+/// `R1CSPlainProof`, it is not zero-knowledge.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct RelaxedR1CSProof<G: CurveGroup> {
+  sc_proof_phase1: SumcheckInstanceProof<G::ScalarField>,
+  claims_phase2: (
+    G::ScalarField,
+    G::ScalarField,
+    G::ScalarField,
+    G::ScalarField,
+  ),
+  proof_eval_E_at_rx: PolyEvalProof<G>,
+  sc_proof_phase2: SumcheckInstanceProof<G::ScalarField>,
+  eval_W_at_ry: G::ScalarField,
+  proof_eval_W_at_ry: PolyEvalProof<G>,
+}
+
+impl<G: CurveGroup> RelaxedR1CSProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Relaxed R1CS proof"
+  }
+
+  pub fn prove(
+    inst: &R1CSInstance<G::ScalarField>,
+    U: &RelaxedR1CSInstance<G>,
+    W: &RelaxedR1CSWitness<G::ScalarField>,
+    gens: &FoldingGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> (
+    RelaxedR1CSProof<G>,
+    Vec<G::ScalarField>,
+    Vec<G::ScalarField>,
+  ) {
+    let timer_prove = Timer::new("RelaxedR1CSProof::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RelaxedR1CSProof::<G>::protocol_name(),
+    );
+    U.append_to_transcript(b"U", transcript);
+
+    let timer_sc_proof_phase1 = Timer::new("prove_sc_phase_one");
+    // the constant term of z is u in a relaxed instance
+    let z = build_z::<G>(W.W.clone(), U.u, &U.X);
+
+    // derive the verifier's challenge tau
+    let num_rounds_x = inst.get_num_cons().log_2();
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // the sum-check is over eq(tau, x) * (Az(x) * Bz(x) - u * Cz(x) - E(x))
+    let poly_E = DensePolynomial::new(W.E.clone());
+    let (poly_Az, poly_Bz, poly_Cz) = inst.multiply_vec(inst.get_num_cons(), z.len(), &z);
+    let mut polys = vec![
+      DensePolynomial::new(EqPolynomial::new(tau).evals()),
+      poly_Az,
+      poly_Bz,
+      poly_Cz,
+      poly_E.clone(),
+    ];
+    let u = U.u;
+    let comb_func = |evals: &[G::ScalarField]| -> G::ScalarField {
+      evals[0] * (evals[1] * evals[2] - u * evals[3] - evals[4])
+    };
+    let (sc_proof_phase1, rx, claims_phase1) = SumcheckInstanceProof::prove_arbitrary::<_, G>(
+      &G::ScalarField::zero(), // claim is zero
+      num_rounds_x,
+      &mut polys,
+      comb_func,
+      3,
+      transcript,
+    );
+    timer_sc_proof_phase1.stop();
+
+    let (Az_claim, Bz_claim, Cz_claim, E_claim) = (
+      claims_phase1[1],
+      claims_phase1[2],
+      claims_phase1[3],
+      claims_phase1[4],
+    );
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &[Az_claim, Bz_claim, Cz_claim, E_claim],
+    );
+
+    // prove the claimed evaluation of E against its commitment
+    let (proof_eval_E_at_rx, _comm_E_at_rx) = PolyEvalProof::prove(
+      &poly_E,
+      None,
+      &rx,
+      &E_claim,
+      None,
+      &gens.gens_E,
+      transcript,
+      random_tape,
+    );
+
+    let timer_sc_proof_phase2 = Timer::new("prove_sc_phase_two");
+    // combine the three claims into a single claim
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
+    let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
+    let evals = evals_ABC::<G>(inst, &rx, z.len(), (r_A, r_B, r_C));
+    let (sc_proof_phase2, ry) = prove_phase_two_plain::<G>(&claim_phase2, z, evals, transcript);
+    timer_sc_proof_phase2.stop();
+
+    let timer_polyeval = Timer::new("polyeval");
+    let (eval_W_at_ry, proof_eval_W_at_ry) = prove_eval_plain(
+      b"eval_W_at_ry",
+      &DensePolynomial::new(W.W.clone()),
+      &ry[1..],
+      &gens.gens_W,
+      transcript,
+      random_tape,
+    );
+    timer_polyeval.stop();
+
+    timer_prove.stop();
+
+    (
+      RelaxedR1CSProof {
+        sc_proof_phase1,
+        claims_phase2: (Az_claim, Bz_claim, Cz_claim, E_claim),
+        proof_eval_E_at_rx,
+        sc_proof_phase2,
+        eval_W_at_ry,
+        proof_eval_W_at_ry,
+      },
+      rx,
+      ry,
+    )
+  }
+
+  #[allow(clippy::type_complexity)]
+  pub fn verify(
+    &self,
+    num_vars: usize,
+    num_cons: usize,
+    U: &RelaxedR1CSInstance<G>,
+    evals: &(G::ScalarField, G::ScalarField, G::ScalarField),
+    transcript: &mut Transcript,
+    gens: &FoldingGens<G>,
+  ) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      RelaxedR1CSProof::<G>::protocol_name(),
+    );
+    U.append_to_transcript(b"U", transcript);
+
+    let n = num_vars;
+    let (num_rounds_x, num_rounds_y) = (num_cons.log_2(), (2 * num_vars).log_2());
+
+    // the commitments in the instance must be to vectors of the right sizes
+    let num_rows = |num_vars: usize| {
+      let (left_num_vars, _right_num_vars) =
+        EqPolynomial::<G::ScalarField>::compute_factored_lens(num_vars);
+      left_num_vars.pow2()
+    };
+    if U.X.len() >= n
+      || U.comm_W.get_num_rows() != num_rows(n.log_2())
+      || U.comm_E.get_num_rows() != num_rows(num_rounds_x)
+    {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    // derive the verifier's challenge tau
+    let tau = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_tau",
+      num_rounds_x,
+    );
+
+    // verify the first sum-check instance
+    let (claim_post_phase1, rx) =
+      self
+        .sc_proof_phase1
+        .verify::<G>(G::ScalarField::zero(), num_rounds_x, 3, transcript)?;
+
+    // perform the intermediate sum-check test with claimed Az, Bz, Cz, and E
+    let (Az_claim, Bz_claim, Cz_claim, E_claim) = self.claims_phase2;
+    let taus_bound_rx = EqPolynomial::new(tau).evaluate(&rx);
+    if claim_post_phase1 != (Az_claim * Bz_claim - U.u * Cz_claim - E_claim) * taus_bound_rx {
+      return Err(ProofVerifyError::InternalError);
+    }
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"claims_phase2",
+      &[Az_claim, Bz_claim, Cz_claim, E_claim],
+    );
+
+    // verify the claimed evaluation of E against its commitment
+    self
+      .proof_eval_E_at_rx
+      .verify_plain(&gens.gens_E, transcript, &rx, &E_claim, &U.comm_E)?;
+
+    // derive three public challenges and then derive a joint claim
+    let (r_A, r_B, r_C) = challenge_ABC::<G>(transcript);
+    let claim_phase2 = r_A * Az_claim + r_B * Bz_claim + r_C * Cz_claim;
+
+    // verify the joint claim with a sum-check protocol
+    let (claim_post_phase2, ry) =
+      self
+        .sc_proof_phase2
+        .verify::<G>(claim_phase2, num_rounds_y, 2, transcript)?;
+
+    // verify W(ry) proof against the commitment in the instance
+    verify_eval_plain(
+      b"eval_W_at_ry",
+      &self.proof_eval_W_at_ry,
+      &ry[1..],
+      &self.eval_W_at_ry,
+      &U.comm_W,
+      &gens.gens_W,
+      transcript,
+    )?;
+
+    // the constant term is u in a relaxed instance
+    let eval_inputs_at_ry = eval_inputs::<G>(n, U.u, &U.X, &ry[1..]);
+    let eval_Z_at_ry = eval_Z_at_ry::<G>(&ry, self.eval_W_at_ry, eval_inputs_at_ry);
+
+    // perform the final check in the second sum-check protocol
+    let (eval_A_r, eval_B_r, eval_C_r) = evals;
+    if claim_post_phase2 != eval_Z_at_ry * (r_A * eval_A_r + r_B * eval_B_r + r_C * eval_C_r) {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    Ok((rx, ry))
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -645,4 +1627,59 @@ mod tests {
       )
       .is_ok());
   }
+
+  #[test]
+  pub fn check_r1cs_plain_proof() {
+    check_r1cs_plain_proof_helper::<G1Projective>()
+  }
+
+  fn check_r1cs_plain_proof_helper<G: CurveGroup>() {
+    let num_vars = 1024;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+    let (inst, vars, input) =
+      R1CSInstance::<G::ScalarField>::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+
+    let gens = R1CSGens::<G>::new(b"test-m", num_cons, num_vars);
+
+    let mut random_tape = RandomTape::new(b"proof");
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, rx, ry) = R1CSPlainProof::prove(
+      &inst,
+      vars.clone(),
+      &input,
+      &gens,
+      &mut prover_transcript,
+      &mut random_tape,
+    );
+
+    let inst_evals = inst.evaluate(&rx, &ry);
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        inst.get_num_vars(),
+        inst.get_num_cons(),
+        &input,
+        &inst_evals,
+        &mut verifier_transcript,
+        &gens,
+      )
+      .is_ok());
+
+    // a proof for a different input must not verify
+    let mut bad_input = input;
+    bad_input[0] += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        inst.get_num_vars(),
+        inst.get_num_cons(),
+        &bad_input,
+        &inst_evals,
+        &mut verifier_transcript,
+        &gens,
+      )
+      .is_err());
+  }
 }
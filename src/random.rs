@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 use super::transcript::ProofTranscript;
 use ark_ec::CurveGroup;
 use ark_ff::UniformRand;
+use ark_std::rand::{CryptoRng, RngCore};
 use ark_std::test_rng;
 use merlin::Transcript;
 
@@ -29,6 +30,27 @@ impl<G: CurveGroup> RandomTape<G> {
     }
   }
 
+  /// seeds the tape from `rng`, so that proofs draw fresh randomness from the caller
+  pub fn new_with_rng<R: RngCore + CryptoRng>(name: &'static [u8], rng: &mut R) -> Self {
+    let mut tape = Transcript::new(name);
+    <Transcript as ProofTranscript<G>>::append_scalar(
+      &mut tape,
+      b"init_randomness",
+      &G::ScalarField::rand(rng),
+    );
+    Self {
+      tape,
+      phantom: PhantomData,
+    }
+  }
+
+  /// mixes a secret of the prover into the tape, which hedges against a weak `rng` passed to
+  /// `new_with_rng`; it does not make the randomness of a tape created with `new` fresh, since
+  /// such a tape draws the same randomness for the same secret
+  pub fn append_scalar(&mut self, label: &'static [u8], scalar: &G::ScalarField) {
+    <Transcript as ProofTranscript<G>>::append_scalar(&mut self.tape, label, scalar);
+  }
+
   pub fn random_scalar(&mut self, label: &'static [u8]) -> G::ScalarField {
     <Transcript as ProofTranscript<G>>::challenge_scalar(&mut self.tape, label)
   }
@@ -0,0 +1,312 @@
+//! Relaxed R1CS and Nova-style folding (non-interactive folding scheme, NIFS).
+//!
+//! A relaxed R1CS instance over matrices `A, B, C` consists of commitments to a witness `W`
+//! and an error vector `E`, a scalar `u` and public inputs `X`. It is satisfied if
+//! `Az * Bz = u * Cz + E` for `z = (W, u, X)`. A satisfying R1CS assignment is a relaxed
+//! instance with `u = 1` and `E = 0`, and two relaxed instances of the same R1CS can be folded
+//! into one, so that many steps of a computation can be proved with a single proof at the end.
+//!
+//! Commitments are not hiding, so neither folding nor the resulting proofs are zero-knowledge.
+#![allow(clippy::type_complexity)]
+use super::dense_mlpoly::{DensePolynomial, PolyCommitment, PolyCommitmentGens};
+use super::errors::{ProofVerifyError, R1CSError};
+use super::math::Math;
+use super::r1csinstance::R1CSInstance;
+use super::timer::Timer;
+use super::transcript::{AppendToTranscript, ProofTranscript};
+use super::{InputsAssignment, Instance, VarsAssignment};
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::{One, Zero};
+use core::cmp::max;
+use merlin::Transcript;
+
+/// `FoldingGens` holds public parameters for committing to relaxed R1CS witnesses and error vectors
+pub struct FoldingGens<G: CurveGroup> {
+  pub(crate) gens_W: PolyCommitmentGens<G>,
+  pub(crate) gens_E: PolyCommitmentGens<G>,
+}
+
+impl<G: CurveGroup> FoldingGens<G> {
+  /// Constructs a new `FoldingGens` given the size of the R1CS statement
+  pub fn new(num_cons: usize, num_vars: usize, num_inputs: usize) -> Self {
+    let num_vars_padded = max(num_vars, num_inputs + 1).next_power_of_two();
+    let num_cons_padded = max(num_cons, 2).next_power_of_two();
+
+    let gens_W = PolyCommitmentGens::new(num_vars_padded.log_2(), b"gens_relaxed_r1cs_W");
+    let gens_E = PolyCommitmentGens::new(num_cons_padded.log_2(), b"gens_relaxed_r1cs_E");
+    FoldingGens { gens_W, gens_E }
+  }
+}
+
+/// `RelaxedR1CSInstance` holds the public part of a relaxed R1CS instance:
+/// commitments to the witness and to the error vector, the scalar `u`, and the public inputs
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RelaxedR1CSInstance<G: CurveGroup> {
+  pub(crate) comm_W: PolyCommitment<G>,
+  pub(crate) comm_E: PolyCommitment<G>,
+  pub(crate) u: G::ScalarField,
+  pub(crate) X: Vec<G::ScalarField>,
+}
+
+/// `RelaxedR1CSWitness` holds the witness and the error vector of a relaxed R1CS instance
+#[derive(Clone, Debug)]
+pub struct RelaxedR1CSWitness<F> {
+  pub(crate) W: Vec<F>,
+  pub(crate) E: Vec<F>,
+}
+
+impl<G: CurveGroup> AppendToTranscript<G> for RelaxedR1CSInstance<G> {
+  fn append_to_transcript(&self, label: &'static [u8], transcript: &mut Transcript) {
+    transcript.append_message(label, b"relaxed_r1cs_instance_begin");
+    self.comm_W.append_to_transcript(b"comm_W", transcript);
+    self.comm_E.append_to_transcript(b"comm_E", transcript);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"u", &self.u);
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"X", &self.X);
+    transcript.append_message(label, b"relaxed_r1cs_instance_end");
+  }
+}
+
+impl<G: CurveGroup> RelaxedR1CSInstance<G> {
+  /// Creates a relaxed R1CS instance and witness (with `u = 1` and `E = 0`) from an assignment to an R1CS `Instance`
+  pub fn from_r1cs(
+    inst: &Instance<G::ScalarField>,
+    vars: VarsAssignment<G::ScalarField>,
+    inputs: &InputsAssignment<G::ScalarField>,
+    gens: &FoldingGens<G>,
+  ) -> Result<(Self, RelaxedR1CSWitness<G::ScalarField>), R1CSError> {
+    if vars.assignment.len() > inst.inst.get_num_vars() {
+      return Err(R1CSError::InvalidNumberOfVars);
+    }
+
+    if inputs.assignment.len() != inst.inst.get_num_inputs() {
+      return Err(R1CSError::InvalidNumberOfInputs);
+    }
+
+    let W = RelaxedR1CSWitness {
+      W: inst.pad_vars(vars).assignment,
+      E: vec![G::ScalarField::zero(); inst.inst.get_num_cons()],
+    };
+    let U = RelaxedR1CSInstance {
+      comm_W: W.commit_W(gens),
+      comm_E: W.commit_E(gens),
+      u: G::ScalarField::one(),
+      X: inputs.assignment.clone(),
+    };
+    Ok((U, W))
+  }
+
+  /// Returns the scalar `u` of the relaxed instance
+  pub fn get_u(&self) -> G::ScalarField {
+    self.u
+  }
+
+  /// Returns the public inputs `X` of the relaxed instance
+  pub fn get_inputs(&self) -> &[G::ScalarField] {
+    &self.X
+  }
+
+  /// Checks if the relaxed instance is satisfied by the given witness, including that the
+  /// commitments in the instance open to the witness and error vectors
+  pub fn is_sat(
+    &self,
+    inst: &Instance<G::ScalarField>,
+    W: &RelaxedR1CSWitness<G::ScalarField>,
+    gens: &FoldingGens<G>,
+  ) -> Result<bool, R1CSError> {
+    if W.W.len() != inst.inst.get_num_vars() {
+      return Err(R1CSError::InvalidNumberOfVars);
+    }
+
+    if self.X.len() != inst.inst.get_num_inputs() || W.E.len() != inst.inst.get_num_cons() {
+      return Err(R1CSError::InvalidNumberOfInputs);
+    }
+
+    let (Az, Bz, Cz) = multiply_vec(&inst.inst, self, W);
+    let res = (0..inst.inst.get_num_cons()).all(|i| Az[i] * Bz[i] == self.u * Cz[i] + W.E[i]);
+
+    Ok(res && self.comm_W == W.commit_W(gens) && self.comm_E == W.commit_E(gens))
+  }
+}
+
+impl<F: PrimeField> RelaxedR1CSWitness<F> {
+  pub(crate) fn commit_W<G: CurveGroup<ScalarField = F>>(
+    &self,
+    gens: &FoldingGens<G>,
+  ) -> PolyCommitment<G> {
+    DensePolynomial::new(self.W.clone())
+      .commit(&gens.gens_W, None)
+      .0
+  }
+
+  pub(crate) fn commit_E<G: CurveGroup<ScalarField = F>>(
+    &self,
+    gens: &FoldingGens<G>,
+  ) -> PolyCommitment<G> {
+    DensePolynomial::new(self.E.clone())
+      .commit(&gens.gens_E, None)
+      .0
+  }
+}
+
+// computes Az, Bz, Cz for z = (W, u, X)
+pub(crate) fn multiply_vec<G: CurveGroup>(
+  inst: &R1CSInstance<G::ScalarField>,
+  U: &RelaxedR1CSInstance<G>,
+  W: &RelaxedR1CSWitness<G::ScalarField>,
+) -> (
+  DensePolynomial<G::ScalarField>,
+  DensePolynomial<G::ScalarField>,
+  DensePolynomial<G::ScalarField>,
+) {
+  let z = {
+    let mut z = W.W.clone();
+    z.push(U.u);
+    z.extend(&U.X);
+    z
+  };
+  inst.multiply_vec(inst.get_num_cons(), z.len(), &z)
+}
+
+/// `NIFS` holds a proof that a relaxed R1CS instance is the folding of two relaxed R1CS instances.
+/// It consists of a commitment to the cross term of the two instances.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct NIFS<G: CurveGroup> {
+  comm_T: PolyCommitment<G>,
+}
+
+impl<G: CurveGroup> NIFS<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"NIFS folding proof"
+  }
+
+  /// Folds two satisfied relaxed instances of the R1CS `Instance` into one, returning
+  /// the folding proof together with the folded instance and witness
+  pub fn prove(
+    inst: &Instance<G::ScalarField>,
+    U1: &RelaxedR1CSInstance<G>,
+    W1: &RelaxedR1CSWitness<G::ScalarField>,
+    U2: &RelaxedR1CSInstance<G>,
+    W2: &RelaxedR1CSWitness<G::ScalarField>,
+    gens: &FoldingGens<G>,
+    transcript: &mut Transcript,
+  ) -> (
+    NIFS<G>,
+    RelaxedR1CSInstance<G>,
+    RelaxedR1CSWitness<G::ScalarField>,
+  ) {
+    let timer_prove = Timer::new("NIFS::prove");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      NIFS::<G>::protocol_name(),
+    );
+    U1.append_to_transcript(b"U1", transcript);
+    U2.append_to_transcript(b"U2", transcript);
+
+    // compute the cross term T = Az1 * Bz2 + Az2 * Bz1 - u1 * Cz2 - u2 * Cz1
+    let (Az1, Bz1, Cz1) = multiply_vec(&inst.inst, U1, W1);
+    let (Az2, Bz2, Cz2) = multiply_vec(&inst.inst, U2, W2);
+    let T = (0..inst.inst.get_num_cons())
+      .map(|i| Az1[i] * Bz2[i] + Az2[i] * Bz1[i] - U1.u * Cz2[i] - U2.u * Cz1[i])
+      .collect::<Vec<G::ScalarField>>();
+    let (comm_T, _blinds) = DensePolynomial::new(T.clone()).commit(&gens.gens_E, None);
+    comm_T.append_to_transcript(b"comm_T", transcript);
+
+    let r = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_r");
+    let U = NIFS::fold_instances(U1, U2, &comm_T, &r);
+    let W = RelaxedR1CSWitness {
+      W: (0..W1.W.len()).map(|i| W1.W[i] + r * W2.W[i]).collect(),
+      E: (0..W1.E.len())
+        .map(|i| W1.E[i] + r * (T[i] + r * W2.E[i]))
+        .collect(),
+    };
+    timer_prove.stop();
+
+    (NIFS { comm_T }, U, W)
+  }
+
+  /// Computes the folding of two relaxed instances given the folding proof
+  pub fn verify(
+    &self,
+    U1: &RelaxedR1CSInstance<G>,
+    U2: &RelaxedR1CSInstance<G>,
+    transcript: &mut Transcript,
+  ) -> Result<RelaxedR1CSInstance<G>, ProofVerifyError> {
+    if U1.X.len() != U2.X.len()
+      || U1.comm_W.get_num_rows() != U2.comm_W.get_num_rows()
+      || U1.comm_E.get_num_rows() != U2.comm_E.get_num_rows()
+      || U1.comm_E.get_num_rows() != self.comm_T.get_num_rows()
+    {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      NIFS::<G>::protocol_name(),
+    );
+    U1.append_to_transcript(b"U1", transcript);
+    U2.append_to_transcript(b"U2", transcript);
+    self.comm_T.append_to_transcript(b"comm_T", transcript);
+
+    let r = <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_r");
+    Ok(NIFS::fold_instances(U1, U2, &self.comm_T, &r))
+  }
+
+  fn fold_instances(
+    U1: &RelaxedR1CSInstance<G>,
+    U2: &RelaxedR1CSInstance<G>,
+    comm_T: &PolyCommitment<G>,
+    r: &G::ScalarField,
+  ) -> RelaxedR1CSInstance<G> {
+    RelaxedR1CSInstance {
+      comm_W: U1.comm_W.combine(&U2.comm_W, r),
+      // E1 + r * T + r^2 * E2
+      comm_E: U1.comm_E.combine(&comm_T.combine(&U2.comm_E, r), r),
+      u: U1.u + *r * U2.u,
+      X: (0..U1.X.len()).map(|i| U1.X[i] + *r * U2.X[i]).collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+
+  #[test]
+  fn check_folding() {
+    check_folding_helper::<G1Projective>()
+  }
+
+  fn check_folding_helper<G: CurveGroup>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let gens = FoldingGens::<G>::new(num_cons, num_vars, num_inputs);
+    let (inst, vars, inputs) = Instance::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (U1, W1) = RelaxedR1CSInstance::from_r1cs(&inst, vars.clone(), &inputs, &gens).unwrap();
+    assert!(U1.is_sat(&inst, &W1, &gens).unwrap());
+
+    // fold the instance with itself a few times
+    let (mut U, mut W) = (U1.clone(), W1.clone());
+    for _ in 0..3 {
+      let mut prover_transcript = Transcript::new(b"example");
+      let (proof, U_next, W_next) =
+        NIFS::prove(&inst, &U, &W, &U1, &W1, &gens, &mut prover_transcript);
+
+      let mut verifier_transcript = Transcript::new(b"example");
+      let U_verifier = proof.verify(&U, &U1, &mut verifier_transcript).unwrap();
+      assert_eq!(U_verifier, U_next);
+      assert!(U_next.is_sat(&inst, &W_next, &gens).unwrap());
+      U = U_next;
+      W = W_next;
+    }
+    assert_ne!(U.get_u(), G::ScalarField::one());
+
+    // a folded witness does not satisfy a tampered error vector
+    W.E[0] += G::ScalarField::one();
+    assert!(!U.is_sat(&inst, &W, &gens).unwrap());
+  }
+}
@@ -0,0 +1,736 @@
+//! Sigma protocols over Pedersen commitments.
+//!
+//! This module exposes the Schnorr-style proofs that Spartan uses for zero-knowledge, made
+//! non-interactive with a merlin `Transcript`. Values are committed as `v * G[0] + blind * h`
+//! under a single-generator `MultiCommitGens` and vectors as `<v, G> + blind * h`; an `Opening`
+//! or a `VectorOpening` keeps the committed values with their blind.
+//!
+//! - `KnowledgeProof` proves knowledge of an opening of a commitment.
+//! - `EqualityProof` proves that two commitments hold the same value.
+//! - `ProductProof` proves that a commitment holds the product of the values of two others.
+//! - `DotProductProof` proves that a commitment holds the inner product of a committed vector
+//!   with a public vector.
+//! - `OrKnowledgeProof` proves knowledge of an opening of one out of several commitments,
+//!   without revealing which.
+//!
+//! Each proof has a `batch_verify` that checks several proofs produced one after the other on
+//! the same transcript with a single multiscalar multiplication, weighting their checks with
+//! randomness from the verifier. Provers draw their nonces, and `batch_verify` its weights, from
+//! a cryptographically secure `rng` of the caller.
+//!
+//! ```
+//! # use ark_bls12_381::{Fr, G1Projective};
+//! # use ark_std::rand::{rngs::StdRng, SeedableRng};
+//! # use libspartan::sigma::{EqualityProof, Opening};
+//! # use libspartan::MultiCommitGens;
+//! # use merlin::Transcript;
+//! let gens = MultiCommitGens::<G1Projective>::new(1, b"sigma_example");
+//! let mut rng = StdRng::from_entropy();
+//! let age = Opening::random(Fr::from(42u64), &mut rng);
+//! let age_again = Opening::random(Fr::from(42u64), &mut rng);
+//!
+//! let mut prover_transcript = Transcript::new(b"sigma_example");
+//! let (proof, C1, C2) =
+//!   EqualityProof::prove(&age, &age_again, &gens, &mut prover_transcript, &mut rng);
+//!
+//! let mut verifier_transcript = Transcript::new(b"sigma_example");
+//! assert!(proof
+//!   .verify(&C1, &C2, &gens, &mut verifier_transcript)
+//!   .is_ok());
+//! ```
+#![allow(non_snake_case)]
+#![allow(clippy::type_complexity)]
+use super::commitments::{Commitments, MultiCommitGens};
+use super::errors::ProofVerifyError;
+use super::nizk;
+use super::random::RandomTape;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
+use merlin::Transcript;
+
+/// `Opening` is a value together with the blind of its commitment
+#[derive(Clone, Debug)]
+pub struct Opening<F: PrimeField> {
+  value: F,
+  blind: F,
+}
+
+impl<F: PrimeField> Opening<F> {
+  /// Constructs an opening of `value` with the given blind
+  pub fn new(value: F, blind: F) -> Self {
+    Opening { value, blind }
+  }
+
+  /// Constructs an opening of `value` with a blind drawn from `rng`
+  pub fn random<R: RngCore + CryptoRng>(value: F, rng: &mut R) -> Self {
+    Opening {
+      value,
+      blind: F::rand(rng),
+    }
+  }
+
+  /// Returns the committed value
+  pub fn value(&self) -> &F {
+    &self.value
+  }
+
+  /// Returns the blind
+  pub fn blind(&self) -> &F {
+    &self.blind
+  }
+
+  /// Commits to the value under a single-generator `gens`
+  pub fn commit<G: CurveGroup<ScalarField = F>>(&self, gens: &MultiCommitGens<G>) -> G {
+    self.value.commit(&self.blind, gens)
+  }
+}
+
+/// `VectorOpening` is a vector together with the blind of its commitment
+#[derive(Clone, Debug)]
+pub struct VectorOpening<F: PrimeField> {
+  values: Vec<F>,
+  blind: F,
+}
+
+impl<F: PrimeField> VectorOpening<F> {
+  /// Constructs an opening of `values` with the given blind
+  pub fn new(values: Vec<F>, blind: F) -> Self {
+    VectorOpening { values, blind }
+  }
+
+  /// Constructs an opening of `values` with a blind drawn from `rng`
+  pub fn random<R: RngCore + CryptoRng>(values: Vec<F>, rng: &mut R) -> Self {
+    VectorOpening {
+      values,
+      blind: F::rand(rng),
+    }
+  }
+
+  /// Returns the committed vector
+  pub fn values(&self) -> &[F] {
+    &self.values
+  }
+
+  /// Returns the blind
+  pub fn blind(&self) -> &F {
+    &self.blind
+  }
+
+  /// Commits to the vector under `gens`, which must have as many generators as the vector has
+  /// entries
+  pub fn commit<G: CurveGroup<ScalarField = F>>(&self, gens: &MultiCommitGens<G>) -> G {
+    Commitments::batch_commit(&self.values, &self.blind, gens)
+  }
+}
+
+// a tape seeded from rng, which the blinds are mixed into as well
+fn random_tape<G: CurveGroup, R: RngCore + CryptoRng>(
+  blinds: &[&G::ScalarField],
+  rng: &mut R,
+) -> RandomTape<G> {
+  let mut random_tape = RandomTape::new_with_rng(b"sigma_proof_randomness", rng);
+  for blind in blinds {
+    random_tape.append_scalar(b"blind", *blind);
+  }
+  random_tape
+}
+
+/// `KnowledgeProof` proves knowledge of an opening of a commitment
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KnowledgeProof<G: CurveGroup> {
+  proof: nizk::KnowledgeProof<G>,
+}
+
+impl<G: CurveGroup> KnowledgeProof<G> {
+  /// Proves knowledge of `opening` and returns the proof along with the commitment
+  pub fn prove<R: RngCore + CryptoRng>(
+    opening: &Opening<G::ScalarField>,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G) {
+    let mut random_tape = random_tape(&[&opening.blind], rng);
+    let (proof, C) = nizk::KnowledgeProof::prove(
+      gens,
+      transcript,
+      &mut random_tape,
+      &opening.value,
+      &opening.blind,
+    );
+    (KnowledgeProof { proof }, C)
+  }
+
+  /// Verifies the proof against the commitment `C`
+  pub fn verify(
+    &self,
+    C: &G,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    self.proof.verify(gens, transcript, C)
+  }
+
+  /// Verifies proofs, each with its commitment, that were produced in order on `transcript`
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G)],
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    let proofs = proofs
+      .iter()
+      .map(|(proof, C)| (&proof.proof, *C))
+      .collect::<Vec<_>>();
+    nizk::KnowledgeProof::batch_verify(&proofs, gens, transcript, rng)
+  }
+}
+
+/// `EqualityProof` proves that two commitments hold the same value
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EqualityProof<G: CurveGroup> {
+  proof: nizk::EqualityProof<G>,
+}
+
+impl<G: CurveGroup> EqualityProof<G> {
+  /// Proves that `opening1` and `opening2` have the same value and returns the proof along with
+  /// their commitments
+  pub fn prove<R: RngCore + CryptoRng>(
+    opening1: &Opening<G::ScalarField>,
+    opening2: &Opening<G::ScalarField>,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G, G) {
+    assert_eq!(opening1.value, opening2.value);
+    let mut random_tape = random_tape(&[&opening1.blind, &opening2.blind], rng);
+    let (proof, C1, C2) = nizk::EqualityProof::prove(
+      gens,
+      transcript,
+      &mut random_tape,
+      &opening1.value,
+      &opening1.blind,
+      &opening2.value,
+      &opening2.blind,
+    );
+    (EqualityProof { proof }, C1, C2)
+  }
+
+  /// Verifies the proof against the commitments `C1` and `C2`
+  pub fn verify(
+    &self,
+    C1: &G,
+    C2: &G,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    self.proof.verify(gens, transcript, C1, C2)
+  }
+
+  /// Verifies proofs, each with its two commitments, that were produced in order on `transcript`
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G, &G)],
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    let proofs = proofs
+      .iter()
+      .map(|(proof, C1, C2)| (&proof.proof, *C1, *C2))
+      .collect::<Vec<_>>();
+    nizk::EqualityProof::batch_verify(&proofs, gens, transcript, rng)
+  }
+}
+
+/// `ProductProof` proves that a commitment `Z` holds the product of the values committed in `X`
+/// and `Y`
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProductProof<G: CurveGroup> {
+  proof: nizk::ProductProof<G>,
+}
+
+impl<G: CurveGroup> ProductProof<G> {
+  /// Proves that the value of `z` is the product of the values of `x` and `y`, and returns the
+  /// proof along with the three commitments
+  pub fn prove<R: RngCore + CryptoRng>(
+    x: &Opening<G::ScalarField>,
+    y: &Opening<G::ScalarField>,
+    z: &Opening<G::ScalarField>,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G, G, G) {
+    assert_eq!(x.value * y.value, z.value);
+    let mut random_tape = random_tape(&[&x.blind, &y.blind, &z.blind], rng);
+    let (proof, X, Y, Z) = nizk::ProductProof::prove(
+      gens,
+      transcript,
+      &mut random_tape,
+      &x.value,
+      &x.blind,
+      &y.value,
+      &y.blind,
+      &z.value,
+      &z.blind,
+    );
+    (ProductProof { proof }, X, Y, Z)
+  }
+
+  /// Verifies the proof against the commitments `X`, `Y` and `Z`
+  pub fn verify(
+    &self,
+    X: &G,
+    Y: &G,
+    Z: &G,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    self.proof.verify(gens, transcript, X, Y, Z)
+  }
+
+  /// Verifies proofs, each with its three commitments, that were produced in order on
+  /// `transcript`
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &G, &G, &G)],
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    let proofs = proofs
+      .iter()
+      .map(|(proof, X, Y, Z)| (&proof.proof, *X, *Y, *Z))
+      .collect::<Vec<_>>();
+    nizk::ProductProof::batch_verify(&proofs, gens, transcript, rng)
+  }
+}
+
+/// `DotProductProof` proves that a commitment `Cy` holds the inner product of the vector
+/// committed in `Cx` with a public vector `a`
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DotProductProof<G: CurveGroup> {
+  proof: nizk::DotProductProof<G>,
+}
+
+impl<G: CurveGroup> DotProductProof<G> {
+  /// Proves that the value of `y` is the inner product of `x` with `a`, and returns the proof
+  /// along with the commitment `Cx` to `x` under `gens_n` and `Cy` to `y` under `gens_1`
+  pub fn prove<R: RngCore + CryptoRng>(
+    x: &VectorOpening<G::ScalarField>,
+    a: &[G::ScalarField],
+    y: &Opening<G::ScalarField>,
+    gens_1: &MultiCommitGens<G>,
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> (Self, G, G) {
+    assert_eq!(
+      nizk::DotProductProof::<G>::compute_dotproduct(&x.values, a),
+      y.value
+    );
+    let mut random_tape = random_tape(&[&x.blind, &y.blind], rng);
+    let (proof, Cx, Cy) = nizk::DotProductProof::prove(
+      gens_1,
+      gens_n,
+      transcript,
+      &mut random_tape,
+      &x.values,
+      &x.blind,
+      a,
+      &y.value,
+      &y.blind,
+    );
+    (DotProductProof { proof }, Cx, Cy)
+  }
+
+  /// Verifies the proof against the public vector `a` and the commitments `Cx` and `Cy`
+  pub fn verify(
+    &self,
+    a: &[G::ScalarField],
+    Cx: &G,
+    Cy: &G,
+    gens_1: &MultiCommitGens<G>,
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens_1)?;
+    if a.len() != gens_n.n() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    self.proof.verify(gens_1, gens_n, transcript, a, Cx, Cy)
+  }
+
+  /// Verifies proofs, each with its public vector and commitments, that were produced in order
+  /// on `transcript`
+  pub fn batch_verify<R: RngCore + CryptoRng>(
+    proofs: &[(&Self, &[G::ScalarField], &G, &G)],
+    gens_1: &MultiCommitGens<G>,
+    gens_n: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens_1)?;
+    let proofs = proofs
+      .iter()
+      .map(|(proof, a, Cx, Cy)| (&proof.proof, *a, *Cx, *Cy))
+      .collect::<Vec<_>>();
+    nizk::DotProductProof::batch_verify(&proofs, gens_1, gens_n, transcript, rng)
+  }
+}
+
+/// `OrKnowledgeProof` proves knowledge of an opening of one out of several commitments without
+/// revealing which one
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OrKnowledgeProof<G: CurveGroup> {
+  proof: nizk::OrKnowledgeProof<G>,
+}
+
+impl<G: CurveGroup> OrKnowledgeProof<G> {
+  /// Proves knowledge of `opening`, which opens `comms[index]`
+  pub fn prove<R: RngCore + CryptoRng>(
+    comms: &[G],
+    index: usize,
+    opening: &Opening<G::ScalarField>,
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Self {
+    let mut random_tape = random_tape(&[&opening.blind], rng);
+    let proof = nizk::OrKnowledgeProof::prove(
+      gens,
+      transcript,
+      &mut random_tape,
+      comms,
+      index,
+      &opening.value,
+      &opening.blind,
+    );
+    OrKnowledgeProof { proof }
+  }
+
+  /// Verifies that the prover knows an opening of one of `comms`
+  pub fn verify(
+    &self,
+    comms: &[G],
+    gens: &MultiCommitGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    check_gens_1(gens)?;
+    self.proof.verify(gens, transcript, comms)
+  }
+}
+
+fn check_gens_1<G: CurveGroup>(gens: &MultiCommitGens<G>) -> Result<(), ProofVerifyError> {
+  if gens.n() == 1 {
+    Ok(())
+  } else {
+    Err(ProofVerifyError::InternalError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_std::rand::{rngs::StdRng, SeedableRng};
+  use ark_std::{One, UniformRand};
+
+  #[test]
+  fn check_sigma_proofs() {
+    check_sigma_proofs_helper::<G1Projective>()
+  }
+
+  fn check_sigma_proofs_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-sigma-gens_1");
+    let gens_n = MultiCommitGens::<G>::new(8, b"test-sigma-gens_n");
+
+    let x = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+    let y = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+    let z = Opening::random(x.value * y.value, &mut prng);
+    let x_again = Opening::random(x.value, &mut prng);
+    let v = VectorOpening::random(
+      (0..8).map(|_| G::ScalarField::rand(&mut prng)).collect(),
+      &mut prng,
+    );
+    let a = (0..8)
+      .map(|_| G::ScalarField::rand(&mut prng))
+      .collect::<Vec<_>>();
+    let w = Opening::random(
+      nizk::DotProductProof::<G>::compute_dotproduct(v.values(), &a),
+      &mut prng,
+    );
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof_k, C) = KnowledgeProof::prove(&x, &gens_1, &mut prover_transcript, &mut prng);
+    let (proof_e, C1, C2) =
+      EqualityProof::prove(&x, &x_again, &gens_1, &mut prover_transcript, &mut prng);
+    let (proof_p, X, Y, Z) =
+      ProductProof::prove(&x, &y, &z, &gens_1, &mut prover_transcript, &mut prng);
+    let (proof_d, Cv, Cw) = DotProductProof::prove(
+      &v,
+      &a,
+      &w,
+      &gens_1,
+      &gens_n,
+      &mut prover_transcript,
+      &mut prng,
+    );
+    assert_eq!(C, x.commit(&gens_1));
+    assert_eq!(Cv, v.commit(&gens_n));
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_k
+      .verify(&C, &gens_1, &mut verifier_transcript)
+      .is_ok());
+    assert!(proof_e
+      .verify(&C1, &C2, &gens_1, &mut verifier_transcript)
+      .is_ok());
+    assert!(proof_p
+      .verify(&X, &Y, &Z, &gens_1, &mut verifier_transcript)
+      .is_ok());
+    assert!(proof_d
+      .verify(&a, &Cv, &Cw, &gens_1, &gens_n, &mut verifier_transcript)
+      .is_ok());
+
+    // a wrong commitment is rejected
+    let C_wrong = C + gens_1.G()[0];
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_k
+      .verify(&C_wrong, &gens_1, &mut verifier_transcript)
+      .is_err());
+
+    // the proofs round-trip through their canonical encoding
+    let mut bytes = Vec::new();
+    proof_k.serialize_compressed(&mut bytes).unwrap();
+    let proof_k2 = KnowledgeProof::<G>::deserialize_compressed(&bytes[..]).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_k2
+      .verify(&C, &gens_1, &mut verifier_transcript)
+      .is_ok());
+  }
+
+  #[test]
+  fn check_sigma_batch_verify() {
+    check_sigma_batch_verify_helper::<G1Projective>()
+  }
+
+  fn check_sigma_batch_verify_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-sigma-gens_1");
+    let gens_n = MultiCommitGens::<G>::new(4, b"test-sigma-gens_n");
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let mut knowledge = Vec::new();
+    let mut equalities = Vec::new();
+    let mut products = Vec::new();
+    let mut dots = Vec::new();
+    for _ in 0..4 {
+      let x = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+      knowledge.push(KnowledgeProof::prove(
+        &x,
+        &gens_1,
+        &mut prover_transcript,
+        &mut prng,
+      ));
+    }
+    for _ in 0..4 {
+      let x = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+      let x_again = Opening::random(x.value, &mut prng);
+      equalities.push(EqualityProof::prove(
+        &x,
+        &x_again,
+        &gens_1,
+        &mut prover_transcript,
+        &mut prng,
+      ));
+    }
+    for _ in 0..4 {
+      let x = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+      let y = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+      let z = Opening::random(x.value * y.value, &mut prng);
+      products.push(ProductProof::prove(
+        &x,
+        &y,
+        &z,
+        &gens_1,
+        &mut prover_transcript,
+        &mut prng,
+      ));
+    }
+    for _ in 0..4 {
+      let v = VectorOpening::random(
+        (0..4).map(|_| G::ScalarField::rand(&mut prng)).collect(),
+        &mut prng,
+      );
+      let a = (0..4)
+        .map(|_| G::ScalarField::rand(&mut prng))
+        .collect::<Vec<_>>();
+      let y = Opening::random(
+        nizk::DotProductProof::<G>::compute_dotproduct(v.values(), &a),
+        &mut prng,
+      );
+      let (proof, Cx, Cy) = DotProductProof::prove(
+        &v,
+        &a,
+        &y,
+        &gens_1,
+        &gens_n,
+        &mut prover_transcript,
+        &mut prng,
+      );
+      dots.push((proof, a, Cx, Cy));
+    }
+
+    let knowledge_refs = knowledge.iter().map(|(p, C)| (p, C)).collect::<Vec<_>>();
+    let equality_refs = equalities
+      .iter()
+      .map(|(p, C1, C2)| (p, C1, C2))
+      .collect::<Vec<_>>();
+    let product_refs = products
+      .iter()
+      .map(|(p, X, Y, Z)| (p, X, Y, Z))
+      .collect::<Vec<_>>();
+    let dot_refs = dots
+      .iter()
+      .map(|(p, a, Cx, Cy)| (p, a.as_slice(), Cx, Cy))
+      .collect::<Vec<_>>();
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(KnowledgeProof::batch_verify(
+      &knowledge_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+    assert!(EqualityProof::batch_verify(
+      &equality_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+    assert!(ProductProof::batch_verify(
+      &product_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+    assert!(DotProductProof::batch_verify(
+      &dot_refs,
+      &gens_1,
+      &gens_n,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+
+    // dot-product proofs are rejected under a gens_1 with more than one generator, as in verify
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(DotProductProof::batch_verify(
+      &dot_refs,
+      &gens_n,
+      &gens_n,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_err());
+
+    // a single wrong commitment makes the batch fail
+    let mut product_refs = product_refs;
+    let (p, X, Y, _) = product_refs[2];
+    product_refs[2] = (p, X, Y, X);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(KnowledgeProof::batch_verify(
+      &knowledge_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+    assert!(EqualityProof::batch_verify(
+      &equality_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_ok());
+    assert!(ProductProof::batch_verify(
+      &product_refs,
+      &gens_1,
+      &mut verifier_transcript,
+      &mut prng
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn check_or_knowledge_proof() {
+    check_or_knowledge_proof_helper::<G1Projective>()
+  }
+
+  fn check_or_knowledge_proof_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-sigma-gens_1");
+
+    let opening = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+    let mut comms = (0..5).map(|_| G::rand(&mut prng)).collect::<Vec<G>>();
+    comms[3] = opening.commit(&gens_1);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = OrKnowledgeProof::prove(
+      &comms,
+      3,
+      &opening,
+      &gens_1,
+      &mut prover_transcript,
+      &mut prng,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comms, &gens_1, &mut verifier_transcript)
+      .is_ok());
+
+    // the proof does not carry over to a set without the opened commitment
+    comms[3] = (opening.value + G::ScalarField::one()).commit(&opening.blind, &gens_1);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comms, &gens_1, &mut verifier_transcript)
+      .is_err());
+  }
+
+  #[test]
+  fn check_sigma_fresh_nonces() {
+    check_sigma_fresh_nonces_helper::<G1Projective>()
+  }
+
+  fn check_sigma_fresh_nonces_helper<G: CurveGroup>() {
+    let mut prng = StdRng::seed_from_u64(0);
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-sigma-gens_1");
+    let x = Opening::random(G::ScalarField::rand(&mut prng), &mut prng);
+
+    // alpha, the commitment to the nonces, comes first in the encoding of a proof
+    let alpha = |proof: &KnowledgeProof<G>| {
+      let mut bytes = Vec::new();
+      proof.serialize_compressed(&mut bytes).unwrap();
+      bytes.truncate(G::zero().compressed_size());
+      bytes
+    };
+
+    // two proofs of the same opening on the same transcript use different nonces
+    let (proof1, _) =
+      KnowledgeProof::prove(&x, &gens_1, &mut Transcript::new(b"example"), &mut prng);
+    let (proof2, _) =
+      KnowledgeProof::prove(&x, &gens_1, &mut Transcript::new(b"example"), &mut prng);
+    assert_ne!(alpha(&proof1), alpha(&proof2));
+  }
+}
@@ -1,11 +1,13 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::needless_range_loop)]
+use super::commitments::GensDerivation;
 use super::dense_mlpoly::DensePolynomial;
 use super::dense_mlpoly::{
   EqPolynomial, IdentityPolynomial, PolyCommitment, PolyCommitmentGens, PolyEvalProof,
 };
 use super::errors::ProofVerifyError;
+use super::fraction_tree::{FractionalSumCircuit, FractionalSumCircuitEvalProofBatched};
 use super::math::Math;
 use super::product_tree::{DotProductCircuit, ProductCircuit, ProductCircuitEvalProofBatched};
 use super::random::RandomTape;
@@ -18,7 +20,7 @@ use ark_std::{One, Zero};
 use core::cmp::Ordering;
 use merlin::Transcript;
 
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SparseMatEntry<F: PrimeField> {
   row: usize,
   col: usize,
@@ -31,7 +33,7 @@ impl<F: PrimeField> SparseMatEntry<F> {
   }
 }
 
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SparseMatPolynomial<F: PrimeField> {
   num_vars_x: usize,
   num_vars_y: usize,
@@ -98,10 +100,7 @@ impl<G: CurveGroup> DerefsEvalProof<G> {
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
   ) -> PolyEvalProof<G> {
-    assert_eq!(
-      joint_poly.get_num_vars(),
-      r.len() + evals.len().log_2() as usize
-    );
+    assert_eq!(joint_poly.get_num_vars(), r.len() + evals.len().log_2());
 
     // append the claimed evaluations to transcript
     <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"evals_ops_val", &evals);
@@ -111,7 +110,7 @@ impl<G: CurveGroup> DerefsEvalProof<G> {
       let challenges = <Transcript as ProofTranscript<G>>::challenge_vector(
         transcript,
         b"challenge_combine_n_to_one",
-        evals.len().log_2() as usize,
+        evals.len().log_2(),
       );
 
       let mut poly_evals = DensePolynomial::new(evals);
@@ -185,7 +184,7 @@ impl<G: CurveGroup> DerefsEvalProof<G> {
     let challenges = <Transcript as ProofTranscript<G>>::challenge_vector(
       transcript,
       b"challenge_combine_n_to_one",
-      evals.len().log_2() as usize,
+      evals.len().log_2(),
     );
     let mut poly_evals = DensePolynomial::new(evals);
     for i in (0..challenges.len()).rev() {
@@ -313,10 +312,22 @@ pub struct MultiSparseMatPolynomialAsDense<F> {
   comb_mem: DensePolynomial<F>,
 }
 
-pub struct SparseMatPolyCommitmentGens<G> {
+/// `SparkMode` selects the memory-checking argument used to prove evaluations of
+/// committed sparse polynomials
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SparkMode {
+  /// offline memory checking with grand-product circuits over hashed (addr, val, ts) tuples
+  #[default]
+  ProductTree,
+  /// a logarithmic-derivative (logUp) lookup argument proved with a sum-check over fractions
+  LogUp,
+}
+
+pub struct SparseMatPolyCommitmentGens<G: CurveGroup> {
   gens_ops: PolyCommitmentGens<G>,
   gens_mem: PolyCommitmentGens<G>,
   gens_derefs: PolyCommitmentGens<G>,
+  mode: SparkMode,
 }
 
 impl<G: CurveGroup> SparseMatPolyCommitmentGens<G> {
@@ -326,24 +337,27 @@ impl<G: CurveGroup> SparseMatPolyCommitmentGens<G> {
     num_vars_y: usize,
     num_nz_entries: usize,
     batch_size: usize,
+    mode: SparkMode,
+    derive: GensDerivation<G>,
   ) -> SparseMatPolyCommitmentGens<G> {
-    let num_vars_ops = num_nz_entries.next_power_of_two().log_2() as usize
-      + (batch_size * 5).next_power_of_two().log_2() as usize;
+    let num_vars_ops =
+      num_nz_entries.next_power_of_two().log_2() + (batch_size * 5).next_power_of_two().log_2();
     let num_vars_mem = if num_vars_x > num_vars_y {
       num_vars_x
     } else {
       num_vars_y
     } + 1;
-    let num_vars_derefs = num_nz_entries.next_power_of_two().log_2() as usize
-      + (batch_size * 2).next_power_of_two().log_2() as usize;
+    let num_vars_derefs =
+      num_nz_entries.next_power_of_two().log_2() + (batch_size * 2).next_power_of_two().log_2();
 
-    let gens_ops = PolyCommitmentGens::new(num_vars_ops, label);
-    let gens_mem = PolyCommitmentGens::new(num_vars_mem, label);
-    let gens_derefs = PolyCommitmentGens::new(num_vars_derefs, label);
+    let gens_ops = PolyCommitmentGens::new_with_derivation(num_vars_ops, label, derive);
+    let gens_mem = PolyCommitmentGens::new_with_derivation(num_vars_mem, label, derive);
+    let gens_derefs = PolyCommitmentGens::new_with_derivation(num_vars_derefs, label, derive);
     SparseMatPolyCommitmentGens {
       gens_ops,
       gens_mem,
       gens_derefs,
+      mode,
     }
   }
 }
@@ -384,6 +398,11 @@ impl<F: PrimeField> SparseMatPolynomial<F> {
     self.M.len().next_power_of_two()
   }
 
+  // the entries of the matrix as `(row, col, val)`, in the order in which they were given
+  pub(crate) fn entries(&self) -> impl Iterator<Item = (usize, usize, F)> + '_ {
+    self.M.iter().map(|entry| (entry.row, entry.col, entry.val))
+  }
+
   fn sparse_to_dense_vecs(&self, N: usize) -> (Vec<usize>, Vec<usize>, Vec<F>) {
     assert!(N >= self.get_num_nz_entries());
     let mut ops_row: Vec<usize> = vec![0; N];
@@ -536,6 +555,125 @@ impl<F: PrimeField> SparseMatPolynomial<F> {
   }
 }
 
+/// Sparse matrix polynomials preprocessed for repeated evaluation. Only the rows and columns
+/// with non-zero entries get an equality weight, computed from two tables of square-root size,
+/// and the entries of each polynomial are sorted by row with those at the same position summed,
+/// so that an evaluation costs one multiplication per row and per entry other than one.
+pub struct SparseMatPolyEvaluator<F: PrimeField> {
+  num_vars_x: usize,
+  num_vars_y: usize,
+  // the rows and the columns with a non-zero entry in any of the polynomials
+  rows: Vec<usize>,
+  cols: Vec<usize>,
+  // for each polynomial, the entries of the i-th row of `rows` are
+  // `entries[row_ptrs[i]..row_ptrs[i + 1]]`, each made of an index into `cols` and a value,
+  // which is `None` for ones since they need no multiplication
+  polys: Vec<(Vec<usize>, Vec<(usize, Option<F>)>)>,
+}
+
+impl<F: PrimeField> SparseMatPolyEvaluator<F> {
+  pub fn new(polys: &[&SparseMatPolynomial<F>]) -> Self {
+    let (num_vars_x, num_vars_y) = (polys[0].num_vars_x, polys[0].num_vars_y);
+    assert!(polys
+      .iter()
+      .all(|poly| poly.num_vars_x == num_vars_x && poly.num_vars_y == num_vars_y));
+
+    let sorted_indices = |index: fn(&SparseMatEntry<F>) -> usize| {
+      let mut indices = polys
+        .iter()
+        .flat_map(|poly| poly.M.iter().map(index))
+        .collect::<Vec<usize>>();
+      indices.sort_unstable();
+      indices.dedup();
+      indices
+    };
+    let rows = sorted_indices(|entry| entry.row);
+    let cols = sorted_indices(|entry| entry.col);
+
+    let polys = polys
+      .iter()
+      .map(|poly| {
+        let mut M = poly
+          .M
+          .iter()
+          .map(|entry| (entry.row, entry.col, entry.val))
+          .collect::<Vec<_>>();
+        M.sort_unstable_by_key(|&(row, col, _)| (row, col));
+        let mut merged: Vec<(usize, usize, F)> = Vec::with_capacity(M.len());
+        for (row, col, val) in M {
+          match merged.last_mut() {
+            Some(last) if (last.0, last.1) == (row, col) => last.2 += val,
+            _ => merged.push((row, col, val)),
+          }
+        }
+
+        let mut row_ptrs = vec![0; rows.len() + 1];
+        let mut entries = Vec::with_capacity(merged.len());
+        for (row, col, val) in merged.into_iter().filter(|(_, _, val)| !val.is_zero()) {
+          row_ptrs[rows.binary_search(&row).unwrap() + 1] += 1;
+          let val = if val.is_one() { None } else { Some(val) };
+          entries.push((cols.binary_search(&col).unwrap(), val));
+        }
+        for i in 0..rows.len() {
+          row_ptrs[i + 1] += row_ptrs[i];
+        }
+        (row_ptrs, entries)
+      })
+      .collect();
+
+    SparseMatPolyEvaluator {
+      num_vars_x,
+      num_vars_y,
+      rows,
+      cols,
+      polys,
+    }
+  }
+
+  /// evaluates the polynomials at `(rx, ry)`, like `SparseMatPolynomial::multi_evaluate`;
+  /// returns an error if the point does not have one coordinate per variable
+  pub fn evaluate(&self, rx: &[F], ry: &[F]) -> Result<Vec<F>, ProofVerifyError> {
+    if rx.len() != self.num_vars_x || ry.len() != self.num_vars_y {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let eq_rows = Self::eq_evals(rx, &self.rows);
+    let eq_cols = Self::eq_evals(ry, &self.cols);
+
+    Ok(
+      self
+        .polys
+        .iter()
+        .map(|(row_ptrs, entries)| {
+          (0..self.rows.len())
+            .map(|i| {
+              let row_eval = entries[row_ptrs[i]..row_ptrs[i + 1]]
+                .iter()
+                .map(|(j, val)| match val {
+                  Some(val) => eq_cols[*j] * val,
+                  None => eq_cols[*j],
+                })
+                .sum::<F>();
+              eq_rows[i] * row_eval
+            })
+            .sum()
+        })
+        .collect(),
+    )
+  }
+
+  // the evaluations of `eq(r, idx)` at the given indices, as products of the factored tables
+  // of the high and the low bits
+  fn eq_evals(r: &[F], indices: &[usize]) -> Vec<F> {
+    let (L, R) = EqPolynomial::new(r.to_vec()).compute_factored_evals();
+    let (_left_num_vars, right_num_vars) = EqPolynomial::<F>::compute_factored_lens(r.len());
+    let mask = right_num_vars.pow2() - 1;
+    indices
+      .iter()
+      .map(|idx| L[idx >> right_num_vars] * R[idx & mask])
+      .collect()
+  }
+}
+
 impl<F: PrimeField> MultiSparseMatPolynomialAsDense<F> {
   pub fn deref(&self, row_mem_val: &[F], col_mem_val: &[F]) -> Derefs<F> {
     let row_ops_val = self.row.deref(row_mem_val);
@@ -708,6 +846,8 @@ impl<F: PrimeField> PolyEvalNetwork<F> {
   }
 }
 
+// the openings of comb_ops, comb_mem and the derefs are under gens_ops, gens_mem and
+// gens_derefs respectively, so each is a separate PolyEvalProof after an n-to-1 reduction
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
 struct HashLayerProof<G: CurveGroup> {
   eval_row: (Vec<G::ScalarField>, Vec<G::ScalarField>, G::ScalarField),
@@ -807,7 +947,7 @@ impl<G: CurveGroup> HashLayerProof<G> {
     let challenges_ops = <Transcript as ProofTranscript<G>>::challenge_vector(
       transcript,
       b"challenge_combine_n_to_one",
-      evals_ops.len().log_2() as usize,
+      evals_ops.len().log_2(),
     );
 
     let mut poly_evals_ops = DensePolynomial::new(evals_ops);
@@ -946,32 +1086,16 @@ impl<G: CurveGroup> HashLayerProof<G> {
     Ok(())
   }
 
-  fn verify(
+  // checks the decommitments of derefs, ops, and mem against the claims of the evaluation sum-check
+  fn verify_openings(
     &self,
     rand: (&Vec<G::ScalarField>, &Vec<G::ScalarField>),
-    claims_row: &(
-      G::ScalarField,
-      Vec<G::ScalarField>,
-      Vec<G::ScalarField>,
-      G::ScalarField,
-    ),
-    claims_col: &(
-      G::ScalarField,
-      Vec<G::ScalarField>,
-      Vec<G::ScalarField>,
-      G::ScalarField,
-    ),
     claims_dotp: &[G::ScalarField],
     comm: &SparseMatPolyCommitment<G>,
     gens: &SparseMatPolyCommitmentGens<G>,
     comm_derefs: &DerefsCommitment<G>,
-    rx: &[G::ScalarField],
-    ry: &[G::ScalarField],
-    r_hash: &G::ScalarField,
-    r_multiset_check: &G::ScalarField,
     transcript: &mut Transcript,
   ) -> Result<(), ProofVerifyError> {
-    let timer = Timer::new("verify_hash_proof");
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
       HashLayerProof::<G>::protocol_name(),
@@ -1021,7 +1145,7 @@ impl<G: CurveGroup> HashLayerProof<G> {
     let challenges_ops = <Transcript as ProofTranscript<G>>::challenge_vector(
       transcript,
       b"challenge_combine_n_to_one",
-      evals_ops.len().log_2() as usize,
+      evals_ops.len().log_2(),
     );
 
     let mut poly_evals_ops = DensePolynomial::new(evals_ops);
@@ -1074,7 +1198,39 @@ impl<G: CurveGroup> HashLayerProof<G> {
       &r_joint_mem,
       &joint_claim_eval_mem,
       &comm.comm_comb_mem,
-    )?;
+    )
+  }
+
+  fn verify(
+    &self,
+    rand: (&Vec<G::ScalarField>, &Vec<G::ScalarField>),
+    claims_row: &(
+      G::ScalarField,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    claims_col: &(
+      G::ScalarField,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    claims_dotp: &[G::ScalarField],
+    comm: &SparseMatPolyCommitment<G>,
+    gens: &SparseMatPolyCommitmentGens<G>,
+    comm_derefs: &DerefsCommitment<G>,
+    rx: &[G::ScalarField],
+    ry: &[G::ScalarField],
+    r_hash: &G::ScalarField,
+    r_multiset_check: &G::ScalarField,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    let timer = Timer::new("verify_hash_proof");
+    self.verify_openings(rand, claims_dotp, comm, gens, comm_derefs, transcript)?;
+
+    let (rand_mem, rand_ops) = rand;
+    let (eval_row_ops_val, eval_col_ops_val) = &self.eval_derefs;
 
     // verify the claims from the product layer
     let (eval_ops_addr, eval_read_ts, eval_audit_ts) = &self.eval_row;
@@ -1470,7 +1626,7 @@ impl<F: PrimeField> ProductLayerProof<F> {
       &claims_dotp_circuit,
       num_ops,
       transcript,
-    );
+    )?;
     // verify the correctness of claim_row_eval_init and claim_row_eval_audit
     let (claims_mem, _claims_mem_dotp, rand_mem) = self.proof_mem.verify::<G>(
       &[
@@ -1482,7 +1638,7 @@ impl<F: PrimeField> ProductLayerProof<F> {
       &Vec::new(),
       num_cells,
       transcript,
-    );
+    )?;
     timer.stop();
 
     Ok((claims_mem, rand_mem, claims_ops, claims_dotp, rand_ops))
@@ -1606,151 +1762,811 @@ impl<G: CurveGroup> PolyEvalNetworkProof<G> {
   }
 }
 
+#[derive(Debug)]
+struct LogUpLayers<F> {
+  ops_vec: Vec<FractionalSumCircuit<F>>,
+  mem: FractionalSumCircuit<F>,
+}
+
+impl<F: PrimeField> LogUpLayers<F> {
+  // adds up fractions given as (numerator, denominator) pairs without inverting denominators
+  fn add_fractions(fractions: &[(F, F)]) -> (F, F) {
+    fractions
+      .iter()
+      .fold((F::zero(), F::one()), |(p_acc, q_acc), (p, q)| {
+        (p_acc * q + *p * q_acc, q_acc * q)
+      })
+  }
+
+  pub fn new(
+    eval_table: &[F],
+    addr_timestamps: &AddrTimestamps<F>,
+    poly_ops_val: &[DensePolynomial<F>],
+    r_logup: &(F, F),
+  ) -> Self {
+    let (r_hash, r_lookup) = r_logup;
+
+    //hash(addr, val) = val * r_hash + addr
+    let hash_func = |addr: &F, val: &F| -> F { *val * *r_hash + *addr };
+
+    // every operation contributes 1 / (r_lookup - hash(addr, val))
+    let ops_vec = (0..addr_timestamps.ops_addr.len())
+      .map(|i| {
+        let (addrs, derefs) = (&addr_timestamps.ops_addr[i], &poly_ops_val[i]);
+        assert_eq!(addrs.len(), derefs.len());
+        let num_ops = addrs.len();
+        let poly_p = DensePolynomial::new(vec![F::one(); num_ops]);
+        let poly_q = DensePolynomial::new(
+          (0..num_ops)
+            .map(|j| *r_lookup - hash_func(&addrs[j], &derefs[j]))
+            .collect::<Vec<F>>(),
+        );
+        FractionalSumCircuit::new(&poly_p, &poly_q)
+      })
+      .collect::<Vec<FractionalSumCircuit<F>>>();
+
+    // every memory cell contributes m / (r_lookup - hash(addr, val)), where the multiplicity m
+    // is the final timestamp of the cell, i.e., the number of operations that read it
+    let num_mem_cells = eval_table.len();
+    let poly_q_mem = DensePolynomial::new(
+      (0..num_mem_cells)
+        .map(|i| *r_lookup - hash_func(&F::from(i as u64), &eval_table[i]))
+        .collect::<Vec<F>>(),
+    );
+    let mem = FractionalSumCircuit::new(&addr_timestamps.audit_ts, &poly_q_mem);
+
+    // lookup check
+    let (p_ops, q_ops) = LogUpLayers::add_fractions(
+      &ops_vec
+        .iter()
+        .map(|circuit| circuit.evaluate())
+        .collect::<Vec<(F, F)>>(),
+    );
+    let (p_mem, q_mem) = mem.evaluate();
+    debug_assert_eq!(p_ops * q_mem, p_mem * q_ops);
+
+    LogUpLayers { ops_vec, mem }
+  }
+}
+
+#[derive(Debug)]
+struct LogUpNetwork<F> {
+  row_layers: LogUpLayers<F>,
+  col_layers: LogUpLayers<F>,
+}
+
+impl<F: PrimeField> LogUpNetwork<F> {
+  pub fn new(
+    dense: &MultiSparseMatPolynomialAsDense<F>,
+    derefs: &Derefs<F>,
+    mem_rx: &[F],
+    mem_ry: &[F],
+    r_logup: &(F, F),
+  ) -> Self {
+    let row_layers = LogUpLayers::new(mem_rx, &dense.row, &derefs.row_ops_val, r_logup);
+    let col_layers = LogUpLayers::new(mem_ry, &dense.col, &derefs.col_ops_val, r_logup);
+
+    LogUpNetwork {
+      row_layers,
+      col_layers,
+    }
+  }
+}
+
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
-pub struct SparseMatPolyEvalProof<G: CurveGroup> {
-  comm_derefs: DerefsCommitment<G>,
-  poly_eval_network_proof: PolyEvalNetworkProof<G>,
+struct FractionLayerProof<F: PrimeField> {
+  eval_row: (Vec<(F, F)>, (F, F)),
+  eval_col: (Vec<(F, F)>, (F, F)),
+  eval_val: (Vec<F>, Vec<F>),
+  proof_mem: FractionalSumCircuitEvalProofBatched<F>,
+  proof_ops: FractionalSumCircuitEvalProofBatched<F>,
 }
 
-impl<G: CurveGroup> SparseMatPolyEvalProof<G> {
+impl<F: PrimeField> FractionLayerProof<F> {
   fn protocol_name() -> &'static [u8] {
-    b"Sparse polynomial evaluation proof"
+    b"Sparse polynomial fraction layer proof"
   }
 
-  fn equalize(
-    rx: &[G::ScalarField],
-    ry: &[G::ScalarField],
-  ) -> (Vec<G::ScalarField>, Vec<G::ScalarField>) {
-    match rx.len().cmp(&ry.len()) {
-      Ordering::Less => {
-        let diff = ry.len() - rx.len();
-        let mut rx_ext = vec![G::ScalarField::zero(); diff];
-        rx_ext.extend(rx);
-        (rx_ext, ry.to_vec())
-      }
-      Ordering::Greater => {
-        let diff = rx.len() - ry.len();
-        let mut ry_ext = vec![G::ScalarField::zero(); diff];
-        ry_ext.extend(ry);
-        (rx.to_vec(), ry_ext)
-      }
-      Ordering::Equal => (rx.to_vec(), ry.to_vec()),
-    }
+  fn append_fractions_to_transcript<G>(
+    label: &'static [u8],
+    fractions: &[(F, F)],
+    transcript: &mut Transcript,
+  ) where
+    G: CurveGroup<ScalarField = F>,
+  {
+    let scalars = fractions
+      .iter()
+      .flat_map(|(p, q)| [*p, *q])
+      .collect::<Vec<F>>();
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, label, &scalars);
   }
 
-  pub fn prove(
-    dense: &MultiSparseMatPolynomialAsDense<G::ScalarField>,
-    rx: &[G::ScalarField], // point at which the polynomial is evaluated
-    ry: &[G::ScalarField],
-    evals: &[G::ScalarField], // a vector evaluation of \widetilde{M}(r = (rx,ry)) for each M
-    gens: &SparseMatPolyCommitmentGens<G>,
+  pub fn prove<G>(
+    row_layers: &mut LogUpLayers<F>,
+    col_layers: &mut LogUpLayers<F>,
+    dense: &MultiSparseMatPolynomialAsDense<F>,
+    derefs: &Derefs<F>,
+    eval: &[F],
     transcript: &mut Transcript,
-    random_tape: &mut RandomTape<G>,
-  ) -> SparseMatPolyEvalProof<G> {
+  ) -> (Self, Vec<F>, Vec<F>)
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
     <Transcript as ProofTranscript<G>>::append_protocol_name(
       transcript,
-      SparseMatPolyEvalProof::<G>::protocol_name(),
+      FractionLayerProof::<F>::protocol_name(),
     );
 
-    // ensure there is one eval for each polynomial in dense
-    assert_eq!(evals.len(), dense.batch_size);
+    let row_eval_ops = (0..row_layers.ops_vec.len())
+      .map(|i| row_layers.ops_vec[i].evaluate())
+      .collect::<Vec<(F, F)>>();
+    let row_eval_mem = row_layers.mem.evaluate();
+    let col_eval_ops = (0..col_layers.ops_vec.len())
+      .map(|i| col_layers.ops_vec[i].evaluate())
+      .collect::<Vec<(F, F)>>();
+    let col_eval_mem = col_layers.mem.evaluate();
+
+    // lookup check
+    for (eval_ops, eval_mem) in [
+      (&row_eval_ops, &row_eval_mem),
+      (&col_eval_ops, &col_eval_mem),
+    ] {
+      let (p_ops, q_ops) = LogUpLayers::add_fractions(eval_ops);
+      assert_eq!(p_ops * eval_mem.1, eval_mem.0 * q_ops);
+    }
 
-    let (mem_rx, mem_ry) = {
-      // equalize the lengths of rx and ry
-      let (rx_ext, ry_ext) = SparseMatPolyEvalProof::<G>::equalize(rx, ry);
-      let poly_rx = EqPolynomial::new(rx_ext).evals();
-      let poly_ry = EqPolynomial::new(ry_ext).evals();
-      (poly_rx, poly_ry)
-    };
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_row_eval_ops",
+      &row_eval_ops,
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_row_eval_mem",
+      &[row_eval_mem],
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_col_eval_ops",
+      &col_eval_ops,
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_col_eval_mem",
+      &[col_eval_mem],
+      transcript,
+    );
 
-    let derefs = dense.deref(&mem_rx, &mem_ry);
+    // prepare dotproduct circuits for batching them with ops-related fraction circuits
+    assert_eq!(eval.len(), derefs.row_ops_val.len());
+    assert_eq!(eval.len(), derefs.col_ops_val.len());
+    assert_eq!(eval.len(), dense.val.len());
+    let mut dotp_circuit_vec: Vec<DotProductCircuit<F>> = Vec::new();
+    let mut eval_dotp_left_vec: Vec<F> = Vec::new();
+    let mut eval_dotp_right_vec: Vec<F> = Vec::new();
+    for i in 0..derefs.row_ops_val.len() {
+      // evaluate sparse polynomial evaluation using two dotp checks
+      let left = derefs.row_ops_val[i].clone();
+      let right = derefs.col_ops_val[i].clone();
+      let weights = dense.val[i].clone();
 
-    // commit to non-deterministic choices of the prover
-    let timer_commit = Timer::new("commit_nondet_witness");
-    let comm_derefs = {
-      let comm = derefs.commit(&gens.gens_derefs);
-      comm.append_to_transcript(b"comm_poly_row_col_ops_val", transcript);
-      comm
-    };
-    timer_commit.stop();
+      // build two dot product circuits to prove evaluation of sparse polynomial
+      let mut dotp_circuit = DotProductCircuit::new(left, right, weights);
+      let (dotp_circuit_left, dotp_circuit_right) = dotp_circuit.split();
 
-    let poly_eval_network_proof = {
-      // produce a random element from the transcript for hash function
-      let r_mem_check =
-        <Transcript as ProofTranscript<G>>::challenge_vector(transcript, b"challenge_r_hash", 2);
+      let (eval_dotp_left, eval_dotp_right) =
+        (dotp_circuit_left.evaluate(), dotp_circuit_right.evaluate());
 
-      // build a network to evaluate the sparse polynomial
-      let timer_build_network = Timer::new("build_layered_network");
-      let mut net = PolyEvalNetwork::new(
-        dense,
-        &derefs,
-        &mem_rx,
-        &mem_ry,
-        &(r_mem_check[0], r_mem_check[1]),
+      <Transcript as ProofTranscript<G>>::append_scalar(
+        transcript,
+        b"claim_eval_dotp_left",
+        &eval_dotp_left,
       );
-      timer_build_network.stop();
 
-      let timer_eval_network = Timer::new("evalproof_layered_network");
-      let poly_eval_network_proof = PolyEvalNetworkProof::prove(
-        &mut net,
-        dense,
-        &derefs,
-        evals,
-        gens,
+      <Transcript as ProofTranscript<G>>::append_scalar(
         transcript,
-        random_tape,
+        b"claim_eval_dotp_right",
+        &eval_dotp_right,
       );
-      timer_eval_network.stop();
 
-      poly_eval_network_proof
-    };
+      assert_eq!(eval_dotp_left + eval_dotp_right, eval[i]);
+      eval_dotp_left_vec.push(eval_dotp_left);
+      eval_dotp_right_vec.push(eval_dotp_right);
 
-    SparseMatPolyEvalProof {
-      comm_derefs,
-      poly_eval_network_proof,
+      dotp_circuit_vec.push(dotp_circuit_left);
+      dotp_circuit_vec.push(dotp_circuit_right);
     }
-  }
 
-  pub fn verify(
-    &self,
-    comm: &SparseMatPolyCommitment<G>,
-    rx: &[G::ScalarField], // point at which the polynomial is evaluated
-    ry: &[G::ScalarField],
-    evals: &[G::ScalarField], // evaluation of \widetilde{M}(r = (rx,ry))
-    gens: &SparseMatPolyCommitmentGens<G>,
-    transcript: &mut Transcript,
-  ) -> Result<(), ProofVerifyError> {
-    <Transcript as ProofTranscript<G>>::append_protocol_name(
+    // all operations into the memories encoded by rx and ry have the same length,
+    // so a single batched proof covers the row and col operations of every instance
+    let (proof_ops, rand_ops) = FractionalSumCircuitEvalProofBatched::<F>::prove::<G>(
+      &mut row_layers
+        .ops_vec
+        .iter_mut()
+        .chain(col_layers.ops_vec.iter_mut())
+        .collect(),
+      &mut dotp_circuit_vec.iter_mut().collect(),
       transcript,
-      SparseMatPolyEvalProof::<G>::protocol_name(),
     );
 
-    // equalize the lengths of rx and ry
-    let (rx_ext, ry_ext) = SparseMatPolyEvalProof::<G>::equalize(rx, ry);
-
-    let (nz, num_mem_cells) = (comm.num_ops, comm.num_mem_cells);
-    assert_eq!(rx_ext.len().pow2(), num_mem_cells);
+    // produce a batched proof of memory-related fraction circuits
+    let (proof_mem, rand_mem) = FractionalSumCircuitEvalProofBatched::<F>::prove::<G>(
+      &mut vec![&mut row_layers.mem, &mut col_layers.mem],
+      &mut Vec::new(),
+      transcript,
+    );
 
-    // add claims to transcript and obtain challenges for randomized mem-check circuit
-    self
-      .comm_derefs
-      .append_to_transcript(b"comm_poly_row_col_ops_val", transcript);
+    let fraction_layer_proof = FractionLayerProof {
+      eval_row: (row_eval_ops, row_eval_mem),
+      eval_col: (col_eval_ops, col_eval_mem),
+      eval_val: (eval_dotp_left_vec, eval_dotp_right_vec),
+      proof_mem,
+      proof_ops,
+    };
+
+    let msg = format!(
+      "len_fraction_layer_proof {:?}",
+      fraction_layer_proof.compressed_size()
+    );
+    Timer::print(&msg);
+
+    (fraction_layer_proof, rand_mem, rand_ops)
+  }
+
+  pub fn verify<G>(
+    &self,
+    num_ops: usize,
+    num_cells: usize,
+    eval: &[F],
+    transcript: &mut Transcript,
+  ) -> Result<(Vec<(F, F)>, Vec<F>, Vec<(F, F)>, Vec<F>, Vec<F>), ProofVerifyError>
+  where
+    G: CurveGroup<ScalarField = F>,
+  {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      FractionLayerProof::<F>::protocol_name(),
+    );
+
+    let timer = Timer::new("verify_fraction_proof");
+    let num_instances = eval.len();
+
+    // lookup check
+    let (row_eval_ops, row_eval_mem) = &self.eval_row;
+    let (col_eval_ops, col_eval_mem) = &self.eval_col;
+    for (eval_ops, eval_mem) in [(row_eval_ops, row_eval_mem), (col_eval_ops, col_eval_mem)] {
+      if eval_ops.len() != num_instances {
+        return Err(ProofVerifyError::InternalError);
+      }
+      let (p_ops, q_ops) = LogUpLayers::add_fractions(eval_ops);
+      if q_ops.is_zero() || eval_mem.1.is_zero() || p_ops * eval_mem.1 != eval_mem.0 * q_ops {
+        return Err(ProofVerifyError::InternalError);
+      }
+    }
+
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_row_eval_ops",
+      row_eval_ops,
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_row_eval_mem",
+      &[*row_eval_mem],
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_col_eval_ops",
+      col_eval_ops,
+      transcript,
+    );
+    FractionLayerProof::<F>::append_fractions_to_transcript::<G>(
+      b"claim_col_eval_mem",
+      &[*col_eval_mem],
+      transcript,
+    );
+
+    // verify the evaluation of the sparse polynomial
+    let (eval_dotp_left, eval_dotp_right) = &self.eval_val;
+    if eval_dotp_left.len() != num_instances || eval_dotp_right.len() != num_instances {
+      return Err(ProofVerifyError::InternalError);
+    }
+    let mut claims_dotp_circuit: Vec<F> = Vec::new();
+    for i in 0..num_instances {
+      if eval_dotp_left[i] + eval_dotp_right[i] != eval[i] {
+        return Err(ProofVerifyError::InternalError);
+      }
+
+      <Transcript as ProofTranscript<G>>::append_scalar(
+        transcript,
+        b"claim_eval_dotp_left",
+        &eval_dotp_left[i],
+      );
+
+      <Transcript as ProofTranscript<G>>::append_scalar(
+        transcript,
+        b"claim_eval_dotp_right",
+        &eval_dotp_right[i],
+      );
+
+      claims_dotp_circuit.push(eval_dotp_left[i]);
+      claims_dotp_circuit.push(eval_dotp_right[i]);
+    }
+
+    // verify the correctness of the row and col claims on operations
+    let mut claims_ops_circuit: Vec<(F, F)> = Vec::new();
+    claims_ops_circuit.extend(row_eval_ops);
+    claims_ops_circuit.extend(col_eval_ops);
+    let (claims_ops, claims_dotp, rand_ops) = self.proof_ops.verify::<G>(
+      &claims_ops_circuit,
+      &claims_dotp_circuit,
+      num_ops,
+      transcript,
+    )?;
+
+    // verify the correctness of the row and col claims on memory
+    let (claims_mem, _claims_mem_dotp, rand_mem) =
+      self
+        .proof_mem
+        .verify::<G>(&[*row_eval_mem, *col_eval_mem], &[], num_cells, transcript)?;
+    timer.stop();
+
+    Ok((claims_mem, rand_mem, claims_ops, claims_dotp, rand_ops))
+  }
+}
+
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+struct LogUpNetworkProof<G: CurveGroup> {
+  proof_fraction_layer: FractionLayerProof<G::ScalarField>,
+  proof_hash_layer: HashLayerProof<G>,
+}
+
+impl<G: CurveGroup> LogUpNetworkProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Sparse polynomial logup evaluation proof"
+  }
+
+  pub fn prove(
+    network: &mut LogUpNetwork<G::ScalarField>,
+    dense: &MultiSparseMatPolynomialAsDense<G::ScalarField>,
+    derefs: &Derefs<G::ScalarField>,
+    evals: &[G::ScalarField],
+    gens: &SparseMatPolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> Self {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      LogUpNetworkProof::<G>::protocol_name(),
+    );
+
+    let (proof_fraction_layer, rand_mem, rand_ops) = FractionLayerProof::<G::ScalarField>::prove::<G>(
+      &mut network.row_layers,
+      &mut network.col_layers,
+      dense,
+      derefs,
+      evals,
+      transcript,
+    );
+
+    // the leaves of the fraction circuits are opened exactly like the leaves of product circuits
+    let proof_hash_layer = HashLayerProof::prove(
+      (&rand_mem, &rand_ops),
+      dense,
+      derefs,
+      gens,
+      transcript,
+      random_tape,
+    );
+
+    LogUpNetworkProof {
+      proof_fraction_layer,
+      proof_hash_layer,
+    }
+  }
+
+  fn verify_helper(
+    rand_mem: &[G::ScalarField],
+    claims_ops: &[(G::ScalarField, G::ScalarField)],
+    claim_mem: &(G::ScalarField, G::ScalarField),
+    eval_ops_val: &[G::ScalarField],
+    eval_ops_addr: &[G::ScalarField],
+    eval_audit_ts: &G::ScalarField,
+    r: &[G::ScalarField],
+    r_logup: &(G::ScalarField, G::ScalarField),
+  ) -> Result<(), ProofVerifyError> {
+    let (r_hash, r_lookup) = r_logup;
+    let hash_func =
+      |addr: &G::ScalarField, val: &G::ScalarField| -> G::ScalarField { *val * *r_hash + *addr };
+
+    // operations
+    if claims_ops.len() != eval_ops_addr.len() || claims_ops.len() != eval_ops_val.len() {
+      return Err(ProofVerifyError::InternalError);
+    }
+    for i in 0..eval_ops_addr.len() {
+      let (claim_p, claim_q) = claims_ops[i];
+      let eval_q = *r_lookup - hash_func(&eval_ops_addr[i], &eval_ops_val[i]);
+      if !claim_p.is_one() || claim_q != eval_q {
+        return Err(ProofVerifyError::InternalError);
+      }
+    }
+
+    // memory: the multiplicities are given by audit_ts
+    let eval_mem_addr = IdentityPolynomial::new(rand_mem.len()).evaluate(rand_mem);
+    let eval_mem_val = EqPolynomial::new(r.to_vec()).evaluate(rand_mem);
+    let eval_q = *r_lookup - hash_func(&eval_mem_addr, &eval_mem_val);
+    if claim_mem.0 != *eval_audit_ts || claim_mem.1 != eval_q {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    Ok(())
+  }
+
+  pub fn verify(
+    &self,
+    comm: &SparseMatPolyCommitment<G>,
+    comm_derefs: &DerefsCommitment<G>,
+    evals: &[G::ScalarField],
+    gens: &SparseMatPolyCommitmentGens<G>,
+    rx: &[G::ScalarField],
+    ry: &[G::ScalarField],
+    r_logup: &(G::ScalarField, G::ScalarField),
+    nz: usize,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    let timer = Timer::new("verify_logup_polyeval_proof");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      LogUpNetworkProof::<G>::protocol_name(),
+    );
+
+    let num_instances = evals.len();
+    let num_ops = nz.next_power_of_two();
+    let num_cells = rx.len().pow2();
+    assert_eq!(rx.len(), ry.len());
+
+    let (claims_mem, rand_mem, claims_ops, claims_dotp, rand_ops) = self
+      .proof_fraction_layer
+      .verify::<G>(num_ops, num_cells, evals, transcript)?;
+    assert_eq!(claims_mem.len(), 2);
+    assert_eq!(claims_ops.len(), 2 * num_instances);
+    assert_eq!(claims_dotp.len(), 3 * num_instances);
+
+    self.proof_hash_layer.verify_openings(
+      (&rand_mem, &rand_ops),
+      &claims_dotp,
+      comm,
+      gens,
+      comm_derefs,
+      transcript,
+    )?;
+
+    let (claims_ops_row, claims_ops_col) = claims_ops.split_at(num_instances);
+    let (eval_row_ops_val, eval_col_ops_val) = &self.proof_hash_layer.eval_derefs;
+    let (eval_row_addr_vec, _eval_row_read_ts_vec, eval_row_audit_ts) =
+      &self.proof_hash_layer.eval_row;
+    let (eval_col_addr_vec, _eval_col_read_ts_vec, eval_col_audit_ts) =
+      &self.proof_hash_layer.eval_col;
+
+    LogUpNetworkProof::<G>::verify_helper(
+      &rand_mem,
+      claims_ops_row,
+      &claims_mem[0],
+      eval_row_ops_val,
+      eval_row_addr_vec,
+      eval_row_audit_ts,
+      rx,
+      r_logup,
+    )?;
+    LogUpNetworkProof::<G>::verify_helper(
+      &rand_mem,
+      claims_ops_col,
+      &claims_mem[1],
+      eval_col_ops_val,
+      eval_col_addr_vec,
+      eval_col_audit_ts,
+      ry,
+      r_logup,
+    )?;
+    timer.stop();
+
+    Ok(())
+  }
+}
+
+// the memory-checking proof inside `SparseMatPolyEvalProof`, one variant per `SparkMode`
+#[derive(Debug)]
+enum SparkNetworkProof<G: CurveGroup> {
+  ProductTree(PolyEvalNetworkProof<G>),
+  LogUp(LogUpNetworkProof<G>),
+}
+
+impl<G: CurveGroup> CanonicalSerialize for SparkNetworkProof<G> {
+  fn serialize_with_mode<W: Write>(
+    &self,
+    mut writer: W,
+    compress: Compress,
+  ) -> Result<(), SerializationError> {
+    match self {
+      SparkNetworkProof::ProductTree(proof) => {
+        0u8.serialize_with_mode(&mut writer, compress)?;
+        proof.serialize_with_mode(writer, compress)
+      }
+      SparkNetworkProof::LogUp(proof) => {
+        1u8.serialize_with_mode(&mut writer, compress)?;
+        proof.serialize_with_mode(writer, compress)
+      }
+    }
+  }
+
+  fn serialized_size(&self, compress: Compress) -> usize {
+    1 + match self {
+      SparkNetworkProof::ProductTree(proof) => proof.serialized_size(compress),
+      SparkNetworkProof::LogUp(proof) => proof.serialized_size(compress),
+    }
+  }
+}
+
+impl<G: CurveGroup> Valid for SparkNetworkProof<G> {
+  fn check(&self) -> Result<(), SerializationError> {
+    match self {
+      SparkNetworkProof::ProductTree(proof) => proof.check(),
+      SparkNetworkProof::LogUp(proof) => proof.check(),
+    }
+  }
+}
+
+impl<G: CurveGroup> CanonicalDeserialize for SparkNetworkProof<G> {
+  fn deserialize_with_mode<R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+  ) -> Result<Self, SerializationError> {
+    match u8::deserialize_with_mode(&mut reader, compress, validate)? {
+      0 => Ok(SparkNetworkProof::ProductTree(
+        PolyEvalNetworkProof::deserialize_with_mode(reader, compress, validate)?,
+      )),
+      1 => Ok(SparkNetworkProof::LogUp(
+        LogUpNetworkProof::deserialize_with_mode(reader, compress, validate)?,
+      )),
+      _ => Err(SerializationError::InvalidData),
+    }
+  }
+}
+
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SparseMatPolyEvalProof<G: CurveGroup> {
+  comm_derefs: DerefsCommitment<G>,
+  poly_eval_network_proof: SparkNetworkProof<G>,
+}
+
+impl<G: CurveGroup> SparseMatPolyEvalProof<G> {
+  fn protocol_name() -> &'static [u8] {
+    b"Sparse polynomial evaluation proof"
+  }
+
+  fn equalize(
+    rx: &[G::ScalarField],
+    ry: &[G::ScalarField],
+  ) -> (Vec<G::ScalarField>, Vec<G::ScalarField>) {
+    match rx.len().cmp(&ry.len()) {
+      Ordering::Less => {
+        let diff = ry.len() - rx.len();
+        let mut rx_ext = vec![G::ScalarField::zero(); diff];
+        rx_ext.extend(rx);
+        (rx_ext, ry.to_vec())
+      }
+      Ordering::Greater => {
+        let diff = rx.len() - ry.len();
+        let mut ry_ext = vec![G::ScalarField::zero(); diff];
+        ry_ext.extend(ry);
+        (rx.to_vec(), ry_ext)
+      }
+      Ordering::Equal => (rx.to_vec(), ry.to_vec()),
+    }
+  }
+
+  pub fn prove(
+    dense: &MultiSparseMatPolynomialAsDense<G::ScalarField>,
+    rx: &[G::ScalarField], // point at which the polynomial is evaluated
+    ry: &[G::ScalarField],
+    evals: &[G::ScalarField], // a vector evaluation of \widetilde{M}(r = (rx,ry)) for each M
+    gens: &SparseMatPolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> SparseMatPolyEvalProof<G> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      SparseMatPolyEvalProof::<G>::protocol_name(),
+    );
+
+    // ensure there is one eval for each polynomial in dense
+    assert_eq!(evals.len(), dense.batch_size);
+
+    let (mem_rx, mem_ry) = {
+      // equalize the lengths of rx and ry
+      let (rx_ext, ry_ext) = SparseMatPolyEvalProof::<G>::equalize(rx, ry);
+      let poly_rx = EqPolynomial::new(rx_ext).evals();
+      let poly_ry = EqPolynomial::new(ry_ext).evals();
+      (poly_rx, poly_ry)
+    };
 
+    let derefs = dense.deref(&mem_rx, &mem_ry);
+
+    // commit to non-deterministic choices of the prover
+    let timer_commit = Timer::new("commit_nondet_witness");
+    let comm_derefs = {
+      let comm = derefs.commit(&gens.gens_derefs);
+      comm.append_to_transcript(b"comm_poly_row_col_ops_val", transcript);
+      comm
+    };
+    timer_commit.stop();
+
+    let poly_eval_network_proof = match gens.mode {
+      SparkMode::ProductTree => {
+        SparkNetworkProof::ProductTree(SparseMatPolyEvalProof::prove_product_tree(
+          dense,
+          &derefs,
+          &mem_rx,
+          &mem_ry,
+          evals,
+          gens,
+          transcript,
+          random_tape,
+        ))
+      }
+      SparkMode::LogUp => SparkNetworkProof::LogUp(SparseMatPolyEvalProof::prove_logup(
+        dense,
+        &derefs,
+        &mem_rx,
+        &mem_ry,
+        evals,
+        gens,
+        transcript,
+        random_tape,
+      )),
+    };
+
+    SparseMatPolyEvalProof {
+      comm_derefs,
+      poly_eval_network_proof,
+    }
+  }
+
+  fn prove_product_tree(
+    dense: &MultiSparseMatPolynomialAsDense<G::ScalarField>,
+    derefs: &Derefs<G::ScalarField>,
+    mem_rx: &[G::ScalarField],
+    mem_ry: &[G::ScalarField],
+    evals: &[G::ScalarField],
+    gens: &SparseMatPolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> PolyEvalNetworkProof<G> {
     // produce a random element from the transcript for hash function
     let r_mem_check =
       <Transcript as ProofTranscript<G>>::challenge_vector(transcript, b"challenge_r_hash", 2);
 
-    self.poly_eval_network_proof.verify(
-      comm,
-      &self.comm_derefs,
+    // build a network to evaluate the sparse polynomial
+    let timer_build_network = Timer::new("build_layered_network");
+    let mut net = PolyEvalNetwork::new(
+      dense,
+      derefs,
+      mem_rx,
+      mem_ry,
+      &(r_mem_check[0], r_mem_check[1]),
+    );
+    timer_build_network.stop();
+
+    let timer_eval_network = Timer::new("evalproof_layered_network");
+    let poly_eval_network_proof = PolyEvalNetworkProof::prove(
+      &mut net,
+      dense,
+      derefs,
       evals,
       gens,
-      &rx_ext,
-      &ry_ext,
-      &(r_mem_check[0], r_mem_check[1]),
-      nz,
       transcript,
-    )
+      random_tape,
+    );
+    timer_eval_network.stop();
+
+    poly_eval_network_proof
+  }
+
+  fn prove_logup(
+    dense: &MultiSparseMatPolynomialAsDense<G::ScalarField>,
+    derefs: &Derefs<G::ScalarField>,
+    mem_rx: &[G::ScalarField],
+    mem_ry: &[G::ScalarField],
+    evals: &[G::ScalarField],
+    gens: &SparseMatPolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> LogUpNetworkProof<G> {
+    // produce random elements from the transcript for the hash function and the lookup argument
+    let r_logup =
+      <Transcript as ProofTranscript<G>>::challenge_vector(transcript, b"challenge_r_logup", 2);
+
+    // build a network of fraction circuits to evaluate the sparse polynomial
+    let timer_build_network = Timer::new("build_logup_network");
+    let mut net = LogUpNetwork::new(dense, derefs, mem_rx, mem_ry, &(r_logup[0], r_logup[1]));
+    timer_build_network.stop();
+
+    let timer_eval_network = Timer::new("evalproof_logup_network");
+    let poly_eval_network_proof = LogUpNetworkProof::prove(
+      &mut net,
+      dense,
+      derefs,
+      evals,
+      gens,
+      transcript,
+      random_tape,
+    );
+    timer_eval_network.stop();
+
+    poly_eval_network_proof
+  }
+
+  pub fn verify(
+    &self,
+    comm: &SparseMatPolyCommitment<G>,
+    rx: &[G::ScalarField], // point at which the polynomial is evaluated
+    ry: &[G::ScalarField],
+    evals: &[G::ScalarField], // evaluation of \widetilde{M}(r = (rx,ry))
+    gens: &SparseMatPolyCommitmentGens<G>,
+    transcript: &mut Transcript,
+  ) -> Result<(), ProofVerifyError> {
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      SparseMatPolyEvalProof::<G>::protocol_name(),
+    );
+
+    // equalize the lengths of rx and ry
+    let (rx_ext, ry_ext) = SparseMatPolyEvalProof::<G>::equalize(rx, ry);
+
+    let (nz, num_mem_cells) = (comm.num_ops, comm.num_mem_cells);
+    assert_eq!(rx_ext.len().pow2(), num_mem_cells);
+
+    // add claims to transcript and obtain challenges for randomized mem-check circuit
+    self
+      .comm_derefs
+      .append_to_transcript(b"comm_poly_row_col_ops_val", transcript);
+
+    match (&self.poly_eval_network_proof, gens.mode) {
+      (SparkNetworkProof::ProductTree(proof), SparkMode::ProductTree) => {
+        // produce a random element from the transcript for hash function
+        let r_mem_check =
+          <Transcript as ProofTranscript<G>>::challenge_vector(transcript, b"challenge_r_hash", 2);
+
+        proof.verify(
+          comm,
+          &self.comm_derefs,
+          evals,
+          gens,
+          &rx_ext,
+          &ry_ext,
+          &(r_mem_check[0], r_mem_check[1]),
+          nz,
+          transcript,
+        )
+      }
+      (SparkNetworkProof::LogUp(proof), SparkMode::LogUp) => {
+        let r_logup =
+          <Transcript as ProofTranscript<G>>::challenge_vector(transcript, b"challenge_r_logup", 2);
+
+        proof.verify(
+          comm,
+          &self.comm_derefs,
+          evals,
+          gens,
+          &rx_ext,
+          &ry_ext,
+          &(r_logup[0], r_logup[1]),
+          nz,
+          transcript,
+        )
+      }
+      // the proof was produced for a different memory-checking argument
+      _ => Err(ProofVerifyError::InternalError),
+    }
   }
 }
 
@@ -1804,23 +2620,73 @@ impl<F: PrimeField> SparsePolynomial<F> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::commitments::MultiCommitGens;
   use ark_bls12_381::G1Projective;
   use ark_std::rand::RngCore;
   use ark_std::test_rng;
   use ark_std::UniformRand;
 
+  #[test]
+  fn check_sparse_polyeval_evaluator() {
+    check_sparse_polyeval_evaluator_helper::<G1Projective>()
+  }
+
+  fn check_sparse_polyeval_evaluator_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+    let (num_vars_x, num_vars_y) = (5, 6);
+
+    // entries at the same position, including ones that cancel out, and empty rows
+    let polys = (0..3)
+      .map(|_| {
+        let mut M = (0..40)
+          .map(|_| {
+            SparseMatEntry::new(
+              (prng.next_u64() as usize % num_vars_x.pow2()) & !1,
+              prng.next_u64() as usize % num_vars_y.pow2(),
+              G::ScalarField::rand(&mut prng),
+            )
+          })
+          .collect::<Vec<_>>();
+        M.push(SparseMatEntry::new(M[0].row, M[0].col, M[0].val));
+        M.push(SparseMatEntry::new(M[1].row, M[1].col, -M[1].val));
+        SparseMatPolynomial::new(num_vars_x, num_vars_y, M)
+      })
+      .collect::<Vec<_>>();
+    let polys = polys.iter().collect::<Vec<_>>();
+
+    let evaluator = SparseMatPolyEvaluator::new(&polys);
+    for _ in 0..5 {
+      let rx = (0..num_vars_x)
+        .map(|_| G::ScalarField::rand(&mut prng))
+        .collect::<Vec<_>>();
+      let ry = (0..num_vars_y)
+        .map(|_| G::ScalarField::rand(&mut prng))
+        .collect::<Vec<_>>();
+      assert_eq!(
+        evaluator.evaluate(&rx, &ry).unwrap(),
+        SparseMatPolynomial::multi_evaluate(&polys, &rx, &ry)
+      );
+    }
+  }
+
   #[test]
   fn check_sparse_polyeval_proof() {
-    check_sparse_polyeval_proof_helper::<G1Projective>()
+    check_sparse_polyeval_proof_helper::<G1Projective>(SparkMode::ProductTree)
   }
-  fn check_sparse_polyeval_proof_helper<G: CurveGroup>() {
+
+  #[test]
+  fn check_sparse_polyeval_proof_logup() {
+    check_sparse_polyeval_proof_helper::<G1Projective>(SparkMode::LogUp)
+  }
+
+  fn check_sparse_polyeval_proof_helper<G: CurveGroup>(mode: SparkMode) {
     let mut prng = test_rng();
 
     let num_nz_entries: usize = 256;
     let num_rows: usize = 256;
     let num_cols: usize = 256;
-    let num_vars_x: usize = num_rows.log_2() as usize;
-    let num_vars_y: usize = num_cols.log_2() as usize;
+    let num_vars_x: usize = num_rows.log_2();
+    let num_vars_y: usize = num_cols.log_2();
 
     let mut M: Vec<SparseMatEntry<G::ScalarField>> = Vec::new();
 
@@ -1839,6 +2705,8 @@ mod tests {
       num_vars_y,
       num_nz_entries,
       3,
+      mode,
+      MultiCommitGens::new,
     );
 
     // commitment
@@ -1877,5 +2745,107 @@ mod tests {
         &mut verifier_transcript,
       )
       .is_ok());
+
+    // the proof survives a serialization round trip
+    let mut proof_encoded = vec![];
+    proof.serialize_compressed(&mut proof_encoded).unwrap();
+    let proof_decoded =
+      SparseMatPolyEvalProof::<G>::deserialize_compressed(&proof_encoded[..]).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof_decoded
+      .verify(
+        &poly_comm,
+        &rx,
+        &ry,
+        &evals,
+        &gens,
+        &mut verifier_transcript,
+      )
+      .is_ok());
+
+    // the logUp verifier reports a wrong evaluation as an error
+    if mode == SparkMode::LogUp {
+      let bad_evals = vec![eval[0], eval[0] + G::ScalarField::one(), eval[0]];
+      let mut verifier_transcript = Transcript::new(b"example");
+      assert!(proof
+        .verify(
+          &poly_comm,
+          &rx,
+          &ry,
+          &bad_evals,
+          &gens,
+          &mut verifier_transcript,
+        )
+        .is_err());
+    }
+  }
+
+  #[test]
+  fn check_sparse_polyeval_proof_mode_mismatch() {
+    check_sparse_polyeval_proof_mode_mismatch_helper::<G1Projective>()
+  }
+
+  fn check_sparse_polyeval_proof_mode_mismatch_helper<G: CurveGroup>() {
+    let mut prng = test_rng();
+
+    let num_vars: usize = 4;
+    let M = (0..16)
+      .map(|i| SparseMatEntry::new(i, (i * 7) % 16, G::ScalarField::rand(&mut prng)))
+      .collect::<Vec<_>>();
+    let poly_M = SparseMatPolynomial::new(num_vars, num_vars, M);
+    let gens_logup = SparseMatPolyCommitmentGens::<G>::new(
+      b"gens_sparse_poly",
+      num_vars,
+      num_vars,
+      16,
+      3,
+      SparkMode::LogUp,
+      MultiCommitGens::new,
+    );
+    let gens_prod = SparseMatPolyCommitmentGens::<G>::new(
+      b"gens_sparse_poly",
+      num_vars,
+      num_vars,
+      16,
+      3,
+      SparkMode::ProductTree,
+      MultiCommitGens::new,
+    );
+    let (poly_comm, dense) =
+      SparseMatPolynomial::multi_commit(&[&poly_M, &poly_M, &poly_M], &gens_logup);
+
+    let rx: Vec<G::ScalarField> = (0..num_vars)
+      .map(|_i| G::ScalarField::rand(&mut prng))
+      .collect();
+    let ry: Vec<G::ScalarField> = (0..num_vars)
+      .map(|_i| G::ScalarField::rand(&mut prng))
+      .collect();
+    let eval = SparseMatPolynomial::multi_evaluate(&[&poly_M], &rx, &ry);
+    let evals = vec![eval[0], eval[0], eval[0]];
+
+    let mut random_tape = RandomTape::new(b"proof");
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SparseMatPolyEvalProof::prove(
+      &dense,
+      &rx,
+      &ry,
+      &evals,
+      &gens_logup,
+      &mut prover_transcript,
+      &mut random_tape,
+    );
+
+    // a logUp proof is not accepted under generators for the product-tree argument
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(
+        &poly_comm,
+        &rx,
+        &ry,
+        &evals,
+        &gens_prod,
+        &mut verifier_transcript,
+      )
+      .is_err());
   }
 }
@@ -1,16 +1,58 @@
+//! The sum-check protocol over combinations of multilinear polynomials.
+//!
+//! Given multilinear polynomials `P_1, ..., P_k` in `n` variables and a combination `f` of total
+//! degree `d`, the prover shows that `sum_{x in {0,1}^n} f(P_1(x), ..., P_k(x))` equals a claim.
+//! After `n` rounds the verifier is left with a random point `r` and a claim about
+//! `f(P_1(r), ..., P_k(r))`, which it checks by other means, e.g., by evaluation proofs from the
+//! `pcs` module, or by reducing it to another sum-check as in GKR-style protocols.
+//!
+//! `SumcheckInstanceProof` reveals the round polynomials, while `ZKSumcheckInstanceProof` only
+//! reveals commitments to them, and takes and produces commitments to the claims.
+//!
+//! ```
+//! # use ark_bls12_381::{Fr, G1Projective};
+//! # use libspartan::pcs::MultilinearPolynomial;
+//! # use libspartan::sumcheck::SumcheckInstanceProof;
+//! # use merlin::Transcript;
+//! // sum_x A(x) * B(x) * C(x) over {0,1}^2
+//! let A = MultilinearPolynomial::new((1..=4u64).map(Fr::from).collect());
+//! let B = MultilinearPolynomial::new((5..=8u64).map(Fr::from).collect());
+//! let C = MultilinearPolynomial::new((2..=5u64).map(Fr::from).collect());
+//! let claim = Fr::from(1 * 5 * 2 + 2 * 6 * 3 + 3 * 7 * 4 + 4 * 8 * 5u64);
+//! let comb_func = |evals: &[Fr]| evals[0] * evals[1] * evals[2];
+//!
+//! let mut prover_transcript = Transcript::new(b"sumcheck_example");
+//! let (proof, r, evals) = SumcheckInstanceProof::prove::<_, G1Projective>(
+//!   &claim,
+//!   vec![A.clone(), B.clone(), C.clone()],
+//!   comb_func,
+//!   3,
+//!   &mut prover_transcript,
+//! )
+//! .unwrap();
+//!
+//! let mut verifier_transcript = Transcript::new(b"sumcheck_example");
+//! let (claim_final, r_verifier) = proof
+//!   .verify::<G1Projective>(claim, 2, 3, &mut verifier_transcript)
+//!   .unwrap();
+//! assert_eq!(r, r_verifier);
+//! assert_eq!(evals, vec![A.evaluate(&r), B.evaluate(&r), C.evaluate(&r)]);
+//! assert_eq!(claim_final, comb_func(&evals));
+//! ```
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
-use super::commitments::{Commitments, MultiCommitGens};
+use super::commitments::{Commitments, GensDerivation, MultiCommitGens};
 use super::dense_mlpoly::DensePolynomial;
-use super::errors::ProofVerifyError;
-use super::nizk::DotProductProof;
+use super::errors::{ProofVerifyError, SumcheckError};
+use super::nizk::{DotProductProofGens, DotProductProofLog};
+use super::pcs::MultilinearPolynomial;
 use super::random::RandomTape;
 use super::transcript::{AppendToTranscript, ProofTranscript};
 use super::unipoly::{CompressedUniPoly, UniPoly};
 use ark_ec::CurveGroup;
-use ark_ec::VariableBaseMSM;
-use ark_ff::PrimeField;
+use ark_ff::{Field, PrimeField};
 use ark_serialize::*;
+use ark_std::rand::{CryptoRng, RngCore};
 use ark_std::{One, Zero};
 
 use itertools::izip;
@@ -22,10 +64,43 @@ pub struct SumcheckInstanceProof<F: PrimeField> {
 }
 
 impl<F: PrimeField> SumcheckInstanceProof<F> {
-  pub fn new(compressed_polys: Vec<CompressedUniPoly<F>>) -> SumcheckInstanceProof<F> {
+  pub(crate) fn new(compressed_polys: Vec<CompressedUniPoly<F>>) -> SumcheckInstanceProof<F> {
     SumcheckInstanceProof { compressed_polys }
   }
 
+  /// Proves that the sum of `comb_func` applied to the evaluations of `polys` over the Boolean
+  /// hypercube is `claim`, where `comb_func` has total degree at most `degree_bound`. Returns the
+  /// proof, the final evaluation point, and the evaluations of `polys` at it.
+  pub fn prove<Func, G>(
+    claim: &F,
+    polys: Vec<MultilinearPolynomial<F>>,
+    comb_func: Func,
+    degree_bound: usize,
+    transcript: &mut Transcript,
+  ) -> Result<(Self, Vec<F>, Vec<F>), SumcheckError>
+  where
+    Func: Fn(&[F]) -> F,
+    G: CurveGroup<ScalarField = F>,
+  {
+    let num_rounds = num_rounds(&polys)?;
+    let mut polys = polys
+      .into_iter()
+      .map(|poly| poly.poly)
+      .collect::<Vec<DensePolynomial<F>>>();
+    Ok(SumcheckInstanceProof::prove_arbitrary::<_, G>(
+      claim,
+      num_rounds,
+      &mut polys,
+      comb_func,
+      degree_bound,
+      transcript,
+    ))
+  }
+
+  /// Verifies a sum-check of `num_rounds` rounds over a combination of total degree
+  /// `degree_bound` that sums to `claim`. Returns the claimed evaluation of the combination at the
+  /// final evaluation point and the point itself; the proof is sound only once the caller checks
+  /// this claim against the polynomials.
   pub fn verify<G>(
     &self,
     claim: F,
@@ -40,15 +115,25 @@ impl<F: PrimeField> SumcheckInstanceProof<F> {
     let mut r: Vec<F> = Vec::new();
 
     // verify that there is a univariate polynomial for each round
-    assert_eq!(self.compressed_polys.len(), num_rounds);
+    if self.compressed_polys.len() != num_rounds {
+      return Err(ProofVerifyError::InternalError);
+    }
     for i in 0..self.compressed_polys.len() {
+      // verify the number of coefficients before decompressing
+      if self.compressed_polys[i].degree() != degree_bound {
+        return Err(ProofVerifyError::InternalError);
+      }
       let poly = self.compressed_polys[i].decompress(&e);
 
       // verify degree bound
-      assert_eq!(poly.degree(), degree_bound);
+      if poly.degree() != degree_bound {
+        return Err(ProofVerifyError::InternalError);
+      }
 
       // check if G_k(0) + G_k(1) = e
-      assert_eq!(poly.eval_at_zero() + poly.eval_at_one(), e);
+      if poly.eval_at_zero() + poly.eval_at_one() != e {
+        return Err(ProofVerifyError::InternalError);
+      }
 
       // append the prover's message to the transcript
       <UniPoly<F> as AppendToTranscript<G>>::append_to_transcript(&poly, b"poly", transcript);
@@ -67,126 +152,303 @@ impl<F: PrimeField> SumcheckInstanceProof<F> {
   }
 }
 
+// the number of variables of polys, which is the number of rounds of a sum-check over them
+fn num_rounds<F: PrimeField>(polys: &[MultilinearPolynomial<F>]) -> Result<usize, SumcheckError> {
+  let num_rounds = polys
+    .first()
+    .ok_or(SumcheckError::NoPolynomials)?
+    .num_vars();
+  if polys.iter().any(|poly| poly.num_vars() != num_rounds) {
+    return Err(SumcheckError::MismatchedPolynomials);
+  }
+  Ok(num_rounds)
+}
+
+/// Generators for `ZKSumcheckInstanceProof`. The compressed polynomial of each round is
+/// committed under its own slice of `gens_n`, so that a linear combination of the commitments of
+/// all rounds is a commitment to the concatenation of their scaled coefficients; `gens_n` shares
+/// the blinding generator of `gens_1`, under which the claims are committed.
+pub struct ZKSumcheckGens<G: CurveGroup> {
+  pub(crate) gens_1: MultiCommitGens<G>,
+  // over gens_n and gens_1, built once since the dot-product proof of every sum-check uses a
+  // prefix of gens_n
+  gens_dotproduct: DotProductProofGens<G>,
+}
+
+impl<G: CurveGroup> ZKSumcheckGens<G> {
+  /// Produces generators for up to `capacity` coefficients across all rounds, i.e., for
+  /// sum-checks with `num_rounds * degree_bound <= capacity`; claims are committed under `gens_1`
+  pub fn new(capacity: usize, label: &[u8], gens_1: &MultiCommitGens<G>) -> Self {
+    ZKSumcheckGens::new_with_derivation(capacity, label, gens_1, MultiCommitGens::new)
+  }
+
+  pub(crate) fn new_with_derivation(
+    capacity: usize,
+    label: &[u8],
+    gens_1: &MultiCommitGens<G>,
+    derive: GensDerivation<G>,
+  ) -> Self {
+    let gens_n = derive(capacity.next_power_of_two(), label);
+    let gens_n = MultiCommitGens::from_generators(gens_n.G().to_vec(), gens_1.h());
+    ZKSumcheckGens {
+      gens_1: gens_1.clone(),
+      gens_dotproduct: DotProductProofGens::from_gens(gens_n, gens_1.clone()),
+    }
+  }
+
+  // the number of coefficients across all rounds that the generators support
+  fn capacity(&self) -> usize {
+    self.gens_dotproduct.gens_n.n()
+  }
+
+  // the generators under which the polynomial of round j is committed
+  fn gens_round(&self, j: usize, degree: usize) -> MultiCommitGens<G> {
+    self
+      .gens_dotproduct
+      .gens_n
+      .slice(j * degree..(j + 1) * degree)
+  }
+
+  // the generators of a dot-product proof over the first n generators of gens_n
+  fn gens_dotproduct(&self, n: usize) -> &DotProductProofGens<G> {
+    assert!(
+      n <= self.capacity(),
+      "the sum-check has more coefficients than generators"
+    );
+    &self.gens_dotproduct
+  }
+}
+
+/// A zero-knowledge sum-check proof. In each round, the prover commits to the round polynomial
+/// without its linear term, which is implied by the claim of the round. Since the claim of the
+/// next round is a linear function of the claim and of the committed coefficients, the claim
+/// after the last round is a public linear combination of the initial claim and of the
+/// coefficients of all rounds, which a single log-sized dot-product proof establishes over the
+/// commitments of the rounds folded with random challenges.
 #[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
 pub struct ZKSumcheckInstanceProof<G: CurveGroup> {
   comm_polys: Vec<G>,
-  comm_evals: Vec<G>,
-  proofs: Vec<DotProductProof<G>>,
+  comm_eval: G,
+  proof: DotProductProofLog<G>,
 }
 
 impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
-  pub fn new(comm_polys: Vec<G>, comm_evals: Vec<G>, proofs: Vec<DotProductProof<G>>) -> Self {
-    ZKSumcheckInstanceProof {
-      comm_polys,
-      comm_evals,
-      proofs,
+  // With p_j(0) + p_j(1) = e_{j-1}, the claim of round j is
+  //   e_j = p_j(r_j) = r_j * e_{j-1} + (1 - 2 r_j) c_0 + sum_{k >= 2} (r_j^k - r_j) c_k
+  // for the coefficients (c_0, c_2, ..., c_d) of the compressed p_j. Unrolling it gives
+  //   e_n = (prod_j r_j) * e_0 + <a, (c_0, c_2, ..., c_d)_{j = 1..n}>
+  // and this returns a, padded to a power of two, and prod_j r_j.
+  fn weights(degree: usize, r: &[G::ScalarField]) -> (Vec<G::ScalarField>, G::ScalarField) {
+    let mut a = vec![G::ScalarField::zero(); (r.len() * degree).max(1).next_power_of_two()];
+    // the product of the challenges of the rounds after round j
+    let mut scale = G::ScalarField::one();
+    for j in (0..r.len()).rev() {
+      let a_j = &mut a[j * degree..(j + 1) * degree];
+      a_j[0] = scale * (G::ScalarField::one() - r[j] - r[j]);
+      let mut power = r[j];
+      for a_jk in a_j[1..].iter_mut() {
+        power *= r[j];
+        *a_jk = scale * (power - r[j]);
+      }
+      scale *= r[j];
     }
+    (a, scale)
   }
 
+  // Draws a challenge rho_j per round once the commitments of all rounds and to the final claim
+  // are in the transcript, and divides the weights of round j by rho_j; the commitments of the
+  // rounds are then folded with rho. A commitment to coefficients outside the slice of its round
+  // contributes terms in rho_j / rho_k for j != k to the inner product, so that, unlike their
+  // plain sum, the fold binds each round to its slice. Returns None if some rho_j is zero.
+  fn fold(
+    comm_eval: &G,
+    num_rounds: usize,
+    degree: usize,
+    a: &mut [G::ScalarField],
+    transcript: &mut Transcript,
+  ) -> Option<Vec<G::ScalarField>> {
+    <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_eval", comm_eval);
+    let rho = <Transcript as ProofTranscript<G>>::challenge_vector(
+      transcript,
+      b"challenge_fold",
+      num_rounds,
+    );
+    for (a_j, rho_j) in a.chunks_mut(degree).zip(rho.iter()) {
+      let rho_j_inv = rho_j.inverse()?;
+      for a_jk in a_j.iter_mut() {
+        *a_jk *= rho_j_inv;
+      }
+    }
+    Some(rho)
+  }
+
+  /// Proves that the sum of `comb_func` applied to the evaluations of `polys` over the Boolean
+  /// hypercube is `claim`, committed with `blind_claim` under `gens.gens_1`, where `comb_func` has
+  /// total degree at most `degree_bound`. Returns the proof, the final evaluation point, the
+  /// evaluations of `polys` at it, and the blind of the commitment to the final claim. The blinds
+  /// of the round commitments are drawn from `rng`.
+  pub fn prove<Func, R: RngCore + CryptoRng>(
+    claim: &G::ScalarField,
+    blind_claim: &G::ScalarField,
+    polys: Vec<MultilinearPolynomial<G::ScalarField>>,
+    comb_func: Func,
+    degree_bound: usize,
+    gens: &ZKSumcheckGens<G>,
+    transcript: &mut Transcript,
+    rng: &mut R,
+  ) -> Result<
+    (
+      Self,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    SumcheckError,
+  >
+  where
+    Func: Fn(&[G::ScalarField]) -> G::ScalarField,
+  {
+    let num_rounds = num_rounds(&polys)?;
+    if num_rounds * degree_bound > gens.capacity() {
+      return Err(SumcheckError::InsufficientGenerators);
+    }
+    let mut polys = polys
+      .into_iter()
+      .map(|poly| poly.poly)
+      .collect::<Vec<DensePolynomial<G::ScalarField>>>();
+    let mut random_tape = RandomTape::new_with_rng(b"sumcheck_randomness", rng);
+    ZKSumcheckInstanceProof::prove_arbitrary(
+      claim,
+      blind_claim,
+      num_rounds,
+      &mut polys,
+      comb_func,
+      degree_bound,
+      gens,
+      transcript,
+      &mut random_tape,
+    )
+  }
+
+  /// Verifies a sum-check of `num_rounds` rounds over a combination of total degree
+  /// `degree_bound` that sums to the value committed to in `comm_claim`. Returns the commitment to
+  /// the claimed evaluation of the combination at the final evaluation point and the point itself,
+  /// which the caller checks against the polynomials.
   pub fn verify(
     &self,
     comm_claim: &G,
     num_rounds: usize,
     degree_bound: usize,
-    gens_1: &MultiCommitGens<G>,
-    gens_n: &MultiCommitGens<G>,
+    gens: &ZKSumcheckGens<G>,
     transcript: &mut Transcript,
   ) -> Result<(G, Vec<G::ScalarField>), ProofVerifyError> {
-    // verify degree bound
-    assert_eq!(gens_n.n, degree_bound + 1);
-
     // verify that there is a univariate polynomial for each round
-    assert_eq!(self.comm_polys.len(), num_rounds);
-    assert_eq!(self.comm_evals.len(), num_rounds);
+    if self.comm_polys.len() != num_rounds
+      || degree_bound == 0
+      || num_rounds * degree_bound > gens.capacity()
+    {
+      return Err(ProofVerifyError::InternalError);
+    }
 
     let mut r: Vec<G::ScalarField> = Vec::new();
-    for i in 0..self.comm_polys.len() {
-      let comm_poly = &self.comm_polys[i];
-
+    for comm_poly in self.comm_polys.iter() {
       // append the prover's polynomial to the transcript
       <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_poly", comm_poly);
 
       //derive the verifier's challenge for the next round
       let r_i =
         <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_nextround");
+      r.push(r_i);
+    }
 
-      // verify the proof of sum-check and evals
-      let res = {
-        let comm_claim_per_round = if i == 0 {
-          comm_claim
-        } else {
-          &self.comm_evals[i - 1]
-        };
-        let comm_eval = &self.comm_evals[i];
-
-        // add two claims to transcript
-        <Transcript as ProofTranscript<G>>::append_point(
-          transcript,
-          b"comm_claim_per_round",
-          comm_claim_per_round,
-        );
-        <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_eval", comm_eval);
-
-        // produce two weights
-        let w = <Transcript as ProofTranscript<G>>::challenge_vector(
-          transcript,
-          b"combine_two_claims_to_one",
-          2,
-        );
-
-        // compute a weighted sum of the RHS
-        let bases = vec![comm_claim_per_round.into_affine(), comm_eval.into_affine()];
-
-        let comm_target = VariableBaseMSM::msm(bases.as_ref(), w.as_ref()).unwrap();
-
-        let a = {
-          // the vector to use to decommit for sum-check test
-          let a_sc = {
-            let mut a = vec![G::ScalarField::one(); degree_bound + 1];
-            a[0] += G::ScalarField::one();
-            a
-          };
-
-          // the vector to use to decommit for evaluation
-          let a_eval = {
-            let mut a = vec![G::ScalarField::one(); degree_bound + 1];
-            for j in 1..a.len() {
-              a[j] = a[j - 1] * r_i;
-            }
-            a
-          };
-
-          // take weighted sum of the two vectors using w
-          assert_eq!(a_sc.len(), a_eval.len());
-          (0..a_sc.len())
-            .map(|i| w[0] * a_sc[i] + w[1] * a_eval[i])
-            .collect::<Vec<G::ScalarField>>()
-        };
-
-        self.proofs[i]
-          .verify(
-            gens_1,
-            gens_n,
-            transcript,
-            &a,
-            &self.comm_polys[i],
-            &comm_target,
-          )
-          .is_ok()
-      };
-      if !res {
-        return Err(ProofVerifyError::InternalError);
-      }
+    // the commitments of the rounds, folded with rho, commit to the coefficients of all rounds
+    // scaled by rho, and comm_eval - scale * comm_claim commits to their inner product with a
+    // scaled by the inverses
+    let (mut a, scale) = Self::weights(degree_bound, &r);
+    let rho = Self::fold(
+      &self.comm_eval,
+      num_rounds,
+      degree_bound,
+      &mut a,
+      transcript,
+    )
+    .ok_or(ProofVerifyError::InternalError)?;
+    let comm_coeffs = izip!(&rho, &self.comm_polys)
+      .map(|(rho_j, comm_poly)| *comm_poly * rho_j)
+      .sum::<G>();
+    let comm_target = self.comm_eval - *comm_claim * scale;
+    self.proof.verify(
+      a.len(),
+      gens.gens_dotproduct(a.len()),
+      transcript,
+      &a,
+      &comm_coeffs,
+      &comm_target,
+    )?;
+
+    Ok((self.comm_eval, r))
+  }
 
-      r.push(r_i);
+  // proves that the claim after the last round follows from the initial claim and the
+  // committed polynomials, returning the proof and the blind of the final claim
+  fn prove_rounds(
+    claim: &G::ScalarField,
+    blind_claim: &G::ScalarField,
+    eval: &G::ScalarField,
+    comm_polys: Vec<G>,
+    polys: &[CompressedUniPoly<G::ScalarField>],
+    blinds_poly: &[G::ScalarField],
+    r: &[G::ScalarField],
+    degree: usize,
+    gens: &ZKSumcheckGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> Result<(Self, G::ScalarField), SumcheckError> {
+    if polys.is_empty() {
+      return Err(SumcheckError::NoRounds);
     }
+    let (mut a, scale) = Self::weights(degree, r);
+
+    let blind_eval = random_tape.random_scalar(b"blind_eval");
+    let comm_eval = eval.commit(&blind_eval, &gens.gens_1);
+
+    let rho = Self::fold(&comm_eval, r.len(), degree, &mut a, transcript)
+      .expect("the folding challenges are non-zero");
+    let coeffs = {
+      let mut coeffs = izip!(&rho, polys)
+        .flat_map(|(rho_j, poly)| poly.as_vec().into_iter().map(move |c| c * rho_j))
+        .collect::<Vec<G::ScalarField>>();
+      coeffs.resize(a.len(), G::ScalarField::zero());
+      coeffs
+    };
+    let blind_coeffs = izip!(&rho, blinds_poly)
+      .map(|(rho_j, blind_poly)| *rho_j * blind_poly)
+      .sum::<G::ScalarField>();
+
+    let (proof, _comm_coeffs, _comm_target) = DotProductProofLog::prove(
+      gens.gens_dotproduct(a.len()),
+      transcript,
+      random_tape,
+      &coeffs,
+      &blind_coeffs,
+      &a,
+      &(*eval - scale * claim),
+      &(blind_eval - scale * blind_claim),
+    );
 
-    Ok((self.comm_evals[self.comm_evals.len() - 1], r))
+    Ok((
+      ZKSumcheckInstanceProof {
+        comm_polys,
+        comm_eval,
+        proof,
+      },
+      blind_eval,
+    ))
   }
 }
 
 impl<F: PrimeField> SumcheckInstanceProof<F> {
-  pub fn prove_cubic<Func, G>(
+  pub(crate) fn prove_cubic<Func, G>(
     claim: &F,
     num_rounds: usize,
     poly_A: &mut DensePolynomial<F>,
@@ -260,7 +522,7 @@ impl<F: PrimeField> SumcheckInstanceProof<F> {
     )
   }
 
-  pub fn prove_cubic_batched<Func, G>(
+  pub(crate) fn prove_cubic_batched<Func, G>(
     claim: &F,
     num_rounds: usize,
     poly_vec_par: (
@@ -428,43 +690,330 @@ impl<F: PrimeField> SumcheckInstanceProof<F> {
       claims_dotp,
     )
   }
+  pub(crate) fn prove_quad<Func, G>(
+    claim: &F,
+    num_rounds: usize,
+    poly_A: &mut DensePolynomial<F>,
+    poly_B: &mut DensePolynomial<F>,
+    comb_func: Func,
+    transcript: &mut Transcript,
+  ) -> (Self, Vec<F>, Vec<F>)
+  where
+    Func: Fn(&F, &F) -> F,
+    G: CurveGroup<ScalarField = F>,
+  {
+    let mut e = *claim;
+    let mut r: Vec<F> = Vec::new();
+    let mut quad_polys: Vec<CompressedUniPoly<F>> = Vec::new();
+    for _j in 0..num_rounds {
+      let mut eval_point_0 = F::zero();
+      let mut eval_point_2 = F::zero();
+
+      let len = poly_A.len() / 2;
+      for i in 0..len {
+        // eval 0: bound_func is A(low)
+        eval_point_0 += comb_func(&poly_A[i], &poly_B[i]);
+
+        // eval 2: bound_func is -A(low) + 2*A(high)
+        let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
+        let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
+        eval_point_2 += comb_func(&poly_A_bound_point, &poly_B_bound_point);
+      }
+
+      let evals = vec![eval_point_0, e - eval_point_0, eval_point_2];
+      let poly = UniPoly::from_evals(&evals);
+
+      // append the prover's message to the transcript
+      <UniPoly<F> as AppendToTranscript<G>>::append_to_transcript(&poly, b"poly", transcript);
+
+      //derive the verifier's challenge for the next round
+      let r_j =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_nextround");
+
+      r.push(r_j);
+      // bound all tables to the verifier's challenege
+      poly_A.bound_poly_var_top(&r_j);
+      poly_B.bound_poly_var_top(&r_j);
+      e = poly.evaluate(&r_j);
+      quad_polys.push(poly.compress());
+    }
+
+    (
+      SumcheckInstanceProof::new(quad_polys),
+      r,
+      vec![poly_A[0], poly_B[0]],
+    )
+  }
+
+  // computes the polynomial of a round of a sum-check over comb_func applied to polys, from its
+  // evaluations at 0, 2, 3, ..., degree_bound; the evaluation at 1 is derived from the claim
+  fn compute_round_poly<Func>(
+    claim: &F,
+    polys: &[DensePolynomial<F>],
+    comb_func: &Func,
+    degree_bound: usize,
+  ) -> UniPoly<F>
+  where
+    Func: Fn(&[F]) -> F,
+  {
+    let mut evals_except_one = vec![F::zero(); degree_bound];
+
+    let len = polys[0].len() / 2;
+    let mut point = vec![F::zero(); polys.len()];
+    let mut step = vec![F::zero(); polys.len()];
+    for i in 0..len {
+      // eval 0: bound_func is A(low)
+      for (k, poly) in polys.iter().enumerate() {
+        point[k] = poly[i];
+        step[k] = poly[len + i] - poly[i];
+      }
+      evals_except_one[0] += comb_func(&point);
+
+      // eval t: bound_func is A(low) + t * (A(high) - A(low)); computed incrementally from eval(t-1)
+      for k in 0..polys.len() {
+        point[k] += step[k];
+      }
+      for eval in evals_except_one.iter_mut().skip(1) {
+        for k in 0..polys.len() {
+          point[k] += step[k];
+        }
+        *eval += comb_func(&point);
+      }
+    }
+
+    let mut evals = vec![evals_except_one[0], *claim - evals_except_one[0]];
+    evals.extend(&evals_except_one[1..]);
+    UniPoly::from_evals(&evals)
+  }
+
+  // proves a sum-check over an arbitrary combination of multilinear polynomials,
+  // where comb_func has total degree at most degree_bound
+  pub(crate) fn prove_arbitrary<Func, G>(
+    claim: &F,
+    num_rounds: usize,
+    polys: &mut [DensePolynomial<F>],
+    comb_func: Func,
+    degree_bound: usize,
+    transcript: &mut Transcript,
+  ) -> (Self, Vec<F>, Vec<F>)
+  where
+    Func: Fn(&[F]) -> F,
+    G: CurveGroup<ScalarField = F>,
+  {
+    let mut e = *claim;
+    let mut r: Vec<F> = Vec::new();
+    let mut compressed_polys: Vec<CompressedUniPoly<F>> = Vec::new();
+    for _j in 0..num_rounds {
+      let poly = Self::compute_round_poly(&e, polys, &comb_func, degree_bound);
+
+      // append the prover's message to the transcript
+      <UniPoly<F> as AppendToTranscript<G>>::append_to_transcript(&poly, b"poly", transcript);
+
+      //derive the verifier's challenge for the next round
+      let r_j =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_nextround");
+      r.push(r_j);
+
+      // bound all tables to the verifier's challenege
+      for poly in polys.iter_mut() {
+        poly.bound_poly_var_top(&r_j);
+      }
+      e = poly.evaluate(&r_j);
+      compressed_polys.push(poly.compress());
+    }
+
+    let claims = polys.iter().map(|poly| poly[0]).collect::<Vec<F>>();
+    (SumcheckInstanceProof::new(compressed_polys), r, claims)
+  }
+
+  pub(crate) fn prove_cubic_with_additive_term<Func, G>(
+    claim: &F,
+    num_rounds: usize,
+    poly_A: &mut DensePolynomial<F>,
+    poly_B: &mut DensePolynomial<F>,
+    poly_C: &mut DensePolynomial<F>,
+    poly_D: &mut DensePolynomial<F>,
+    comb_func: Func,
+    transcript: &mut Transcript,
+  ) -> (Self, Vec<F>, Vec<F>)
+  where
+    Func: Fn(&F, &F, &F, &F) -> F,
+    G: CurveGroup<ScalarField = F>,
+  {
+    let mut e = *claim;
+    let mut r: Vec<F> = Vec::new();
+    let mut cubic_polys: Vec<CompressedUniPoly<F>> = Vec::new();
+    for _j in 0..num_rounds {
+      let mut eval_point_0 = F::zero();
+      let mut eval_point_2 = F::zero();
+      let mut eval_point_3 = F::zero();
+
+      let len = poly_A.len() / 2;
+      for i in 0..len {
+        // eval 0: bound_func is A(low)
+        eval_point_0 += comb_func(&poly_A[i], &poly_B[i], &poly_C[i], &poly_D[i]);
+
+        // eval 2: bound_func is -A(low) + 2*A(high)
+        let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
+        let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
+        let poly_C_bound_point = poly_C[len + i] + poly_C[len + i] - poly_C[i];
+        let poly_D_bound_point = poly_D[len + i] + poly_D[len + i] - poly_D[i];
+        eval_point_2 += comb_func(
+          &poly_A_bound_point,
+          &poly_B_bound_point,
+          &poly_C_bound_point,
+          &poly_D_bound_point,
+        );
+
+        // eval 3: bound_func is -2A(low) + 3A(high); computed incrementally with bound_func applied to eval(2)
+        let poly_A_bound_point = poly_A_bound_point + poly_A[len + i] - poly_A[i];
+        let poly_B_bound_point = poly_B_bound_point + poly_B[len + i] - poly_B[i];
+        let poly_C_bound_point = poly_C_bound_point + poly_C[len + i] - poly_C[i];
+        let poly_D_bound_point = poly_D_bound_point + poly_D[len + i] - poly_D[i];
+        eval_point_3 += comb_func(
+          &poly_A_bound_point,
+          &poly_B_bound_point,
+          &poly_C_bound_point,
+          &poly_D_bound_point,
+        );
+      }
+
+      let evals = vec![eval_point_0, e - eval_point_0, eval_point_2, eval_point_3];
+      let poly = UniPoly::from_evals(&evals);
+
+      // append the prover's message to the transcript
+      <UniPoly<F> as AppendToTranscript<G>>::append_to_transcript(&poly, b"poly", transcript);
+
+      //derive the verifier's challenge for the next round
+      let r_j =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_nextround");
+
+      r.push(r_j);
+      // bound all tables to the verifier's challenege
+      poly_A.bound_poly_var_top(&r_j);
+      poly_B.bound_poly_var_top(&r_j);
+      poly_C.bound_poly_var_top(&r_j);
+      poly_D.bound_poly_var_top(&r_j);
+      e = poly.evaluate(&r_j);
+      cubic_polys.push(poly.compress());
+    }
+
+    (
+      SumcheckInstanceProof::new(cubic_polys),
+      r,
+      vec![poly_A[0], poly_B[0], poly_C[0], poly_D[0]],
+    )
+  }
 }
 
 impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
-  pub fn prove_quad<Func>(
+  // the zero-knowledge counterpart of SumcheckInstanceProof::prove_arbitrary
+  pub(crate) fn prove_arbitrary<Func>(
+    claim: &G::ScalarField,
+    blind_claim: &G::ScalarField,
+    num_rounds: usize,
+    polys: &mut [DensePolynomial<G::ScalarField>],
+    comb_func: Func,
+    degree_bound: usize,
+    gens: &ZKSumcheckGens<G>,
+    transcript: &mut Transcript,
+    random_tape: &mut RandomTape<G>,
+  ) -> Result<
+    (
+      Self,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    SumcheckError,
+  >
+  where
+    Func: Fn(&[G::ScalarField]) -> G::ScalarField,
+  {
+    let blinds_poly = random_tape.random_vector(b"blinds_poly", num_rounds);
+    let mut claim_per_round = *claim;
+
+    let mut r: Vec<G::ScalarField> = Vec::new();
+    let mut compressed_polys: Vec<CompressedUniPoly<G::ScalarField>> = Vec::new();
+    let mut comm_polys: Vec<G> = Vec::new();
+
+    for (j, blind_poly) in blinds_poly.iter().enumerate() {
+      let poly = SumcheckInstanceProof::compute_round_poly(
+        &claim_per_round,
+        polys,
+        &comb_func,
+        degree_bound,
+      );
+
+      // append the prover's message to the transcript
+      let compressed_poly = poly.compress();
+      let comm_poly = compressed_poly.commit(&gens.gens_round(j, degree_bound), blind_poly);
+      <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_poly", &comm_poly);
+      comm_polys.push(comm_poly);
+      compressed_polys.push(compressed_poly);
+
+      //derive the verifier's challenge for the next round
+      let r_j =
+        <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_nextround");
+
+      // bound all tables to the verifier's challenege
+      for poly in polys.iter_mut() {
+        poly.bound_poly_var_top(&r_j);
+      }
+
+      claim_per_round = poly.evaluate(&r_j);
+      r.push(r_j);
+    }
+
+    let (proof, blind_eval) = ZKSumcheckInstanceProof::prove_rounds(
+      claim,
+      blind_claim,
+      &claim_per_round,
+      comm_polys,
+      &compressed_polys,
+      &blinds_poly,
+      &r,
+      degree_bound,
+      gens,
+      transcript,
+      random_tape,
+    )?;
+
+    let claims = polys.iter().map(|poly| poly[0]).collect();
+    Ok((proof, r, claims, blind_eval))
+  }
+
+  pub(crate) fn prove_quad<Func>(
     claim: &G::ScalarField,
     blind_claim: &G::ScalarField,
     num_rounds: usize,
     poly_A: &mut DensePolynomial<G::ScalarField>,
     poly_B: &mut DensePolynomial<G::ScalarField>,
     comb_func: Func,
-    gens_1: &MultiCommitGens<G>,
-    gens_n: &MultiCommitGens<G>,
+    gens: &ZKSumcheckGens<G>,
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
-  ) -> (
-    Self,
-    Vec<G::ScalarField>,
-    Vec<G::ScalarField>,
-    G::ScalarField,
-  )
+  ) -> Result<
+    (
+      Self,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    SumcheckError,
+  >
   where
     Func: Fn(&G::ScalarField, &G::ScalarField) -> G::ScalarField,
   {
-    let (blinds_poly, blinds_evals) = (
-      random_tape.random_vector(b"blinds_poly", num_rounds),
-      random_tape.random_vector(b"blinds_evals", num_rounds),
-    );
+    let blinds_poly = random_tape.random_vector(b"blinds_poly", num_rounds);
     let mut claim_per_round = *claim;
-    let mut comm_claim_per_round = claim_per_round.commit(blind_claim, gens_1);
 
     let mut r: Vec<G::ScalarField> = Vec::new();
+    let mut polys: Vec<CompressedUniPoly<G::ScalarField>> = Vec::new();
     let mut comm_polys: Vec<G> = Vec::new();
-    let mut comm_evals: Vec<G> = Vec::new();
-    let mut proofs: Vec<DotProductProof<G>> = Vec::new();
 
-    for j in 0..num_rounds {
-      let (poly, comm_poly) = {
+    for (j, blind_poly) in blinds_poly.iter().enumerate() {
+      let poly = {
         let mut eval_point_0 = G::ScalarField::zero();
         let mut eval_point_2 = G::ScalarField::zero();
 
@@ -480,14 +1029,15 @@ impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
         }
 
         let evals = vec![eval_point_0, claim_per_round - eval_point_0, eval_point_2];
-        let poly = UniPoly::from_evals(&evals);
-        let comm_poly = poly.commit(gens_n, &blinds_poly[j]);
-        (poly, comm_poly)
+        UniPoly::from_evals(&evals)
       };
 
       // append the prover's message to the transcript
+      let compressed_poly = poly.compress();
+      let comm_poly = compressed_poly.commit(&gens.gens_round(j, 2), blind_poly);
       <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_poly", &comm_poly);
       comm_polys.push(comm_poly);
+      polys.push(compressed_poly);
 
       //derive the verifier's challenge for the next round
       let r_j =
@@ -497,110 +1047,28 @@ impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
       poly_A.bound_poly_var_top(&r_j);
       poly_B.bound_poly_var_top(&r_j);
 
-      // produce a proof of sum-check and of evaluation
-      let (proof, claim_next_round, comm_claim_next_round) = {
-        let eval = poly.evaluate(&r_j);
-        let comm_eval = eval.commit(&blinds_evals[j], gens_1);
-
-        // we need to prove the following under homomorphic commitments:
-        // (1) poly(0) + poly(1) = claim_per_round
-        // (2) poly(r_j) = eval
-
-        // Our technique is to leverage dot product proofs:
-        // (1) we can prove: <poly_in_coeffs_form, (2, 1, 1, 1)> = claim_per_round
-        // (2) we can prove: <poly_in_coeffs_form, (1, r_j, r^2_j, ..) = eval
-        // for efficiency we batch them using random weights
-
-        // add two claims to transcript
-        <Transcript as ProofTranscript<G>>::append_point(
-          transcript,
-          b"comm_claim_per_round",
-          &comm_claim_per_round,
-        );
-        <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_eval", &comm_eval);
-
-        // produce two weights
-        let w = <Transcript as ProofTranscript<G>>::challenge_vector(
-          transcript,
-          b"combine_two_claims_to_one",
-          2,
-        );
-
-        // compute a weighted sum of the RHS
-        let target = w[0] * claim_per_round + w[1] * eval;
-
-        let bases = vec![comm_claim_per_round.into_affine(), comm_eval.into_affine()];
-        let comm_target = VariableBaseMSM::msm(bases.as_ref(), w.as_ref()).unwrap();
-
-        let blind = {
-          let blind_sc = if j == 0 {
-            blind_claim
-          } else {
-            &blinds_evals[j - 1]
-          };
-
-          let blind_eval = &blinds_evals[j];
-
-          w[0] * blind_sc + w[1] * blind_eval
-        };
-        assert_eq!(target.commit(&blind, gens_1), comm_target);
-
-        let a = {
-          // the vector to use to decommit for sum-check test
-          let a_sc = {
-            let mut a = vec![G::ScalarField::one(); poly.degree() + 1];
-            a[0] += G::ScalarField::one();
-            a
-          };
-
-          // the vector to use to decommit for evaluation
-          let a_eval = {
-            let mut a = vec![G::ScalarField::one(); poly.degree() + 1];
-            for j in 1..a.len() {
-              a[j] = a[j - 1] * r_j;
-            }
-            a
-          };
-
-          // take weighted sum of the two vectors using w
-          assert_eq!(a_sc.len(), a_eval.len());
-          (0..a_sc.len())
-            .map(|i| w[0] * a_sc[i] + w[1] * a_eval[i])
-            .collect::<Vec<G::ScalarField>>()
-        };
-
-        let (proof, _comm_poly, _comm_sc_eval) = DotProductProof::prove(
-          gens_1,
-          gens_n,
-          transcript,
-          random_tape,
-          &poly.as_vec(),
-          &blinds_poly[j],
-          &a,
-          &target,
-          &blind,
-        );
-
-        (proof, eval, comm_eval)
-      };
-
-      claim_per_round = claim_next_round;
-      comm_claim_per_round = comm_claim_next_round;
-
-      proofs.push(proof);
+      claim_per_round = poly.evaluate(&r_j);
       r.push(r_j);
-      comm_evals.push(comm_claim_per_round);
     }
 
-    (
-      ZKSumcheckInstanceProof::new(comm_polys, comm_evals, proofs),
-      r,
-      vec![poly_A[0], poly_B[0]],
-      blinds_evals[num_rounds - 1],
-    )
+    let (proof, blind_eval) = ZKSumcheckInstanceProof::prove_rounds(
+      claim,
+      blind_claim,
+      &claim_per_round,
+      comm_polys,
+      &polys,
+      &blinds_poly,
+      &r,
+      2,
+      gens,
+      transcript,
+      random_tape,
+    )?;
+
+    Ok((proof, r, vec![poly_A[0], poly_B[0]], blind_eval))
   }
 
-  pub fn prove_cubic_with_additive_term<Func>(
+  pub(crate) fn prove_cubic_with_additive_term<Func>(
     claim: &G::ScalarField,
     blind_claim: &G::ScalarField,
     num_rounds: usize,
@@ -609,34 +1077,30 @@ impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
     poly_C: &mut DensePolynomial<G::ScalarField>,
     poly_D: &mut DensePolynomial<G::ScalarField>,
     comb_func: Func,
-    gens_1: &MultiCommitGens<G>,
-    gens_n: &MultiCommitGens<G>,
+    gens: &ZKSumcheckGens<G>,
     transcript: &mut Transcript,
     random_tape: &mut RandomTape<G>,
-  ) -> (
-    Self,
-    Vec<G::ScalarField>,
-    Vec<G::ScalarField>,
-    G::ScalarField,
-  )
+  ) -> Result<
+    (
+      Self,
+      Vec<G::ScalarField>,
+      Vec<G::ScalarField>,
+      G::ScalarField,
+    ),
+    SumcheckError,
+  >
   where
     Func: Fn(&G::ScalarField, &G::ScalarField, &G::ScalarField, &G::ScalarField) -> G::ScalarField,
   {
-    let (blinds_poly, blinds_evals) = (
-      random_tape.random_vector(b"blinds_poly", num_rounds),
-      random_tape.random_vector(b"blinds_evals", num_rounds),
-    );
-
+    let blinds_poly = random_tape.random_vector(b"blinds_poly", num_rounds);
     let mut claim_per_round = *claim;
-    let mut comm_claim_per_round = claim_per_round.commit(blind_claim, gens_1);
 
     let mut r: Vec<G::ScalarField> = Vec::new();
+    let mut polys: Vec<CompressedUniPoly<G::ScalarField>> = Vec::new();
     let mut comm_polys: Vec<G> = Vec::new();
-    let mut comm_evals: Vec<G> = Vec::new();
-    let mut proofs: Vec<DotProductProof<G>> = Vec::new();
 
-    for j in 0..num_rounds {
-      let (poly, comm_poly) = {
+    for (j, blind_poly) in blinds_poly.iter().enumerate() {
+      let poly = {
         let mut eval_point_0 = G::ScalarField::zero();
         let mut eval_point_2 = G::ScalarField::zero();
         let mut eval_point_3 = G::ScalarField::zero();
@@ -677,14 +1141,15 @@ impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
           eval_point_2,
           eval_point_3,
         ];
-        let poly = UniPoly::from_evals(&evals);
-        let comm_poly = poly.commit(gens_n, &blinds_poly[j]);
-        (poly, comm_poly)
+        UniPoly::from_evals(&evals)
       };
 
       // append the prover's message to the transcript
+      let compressed_poly = poly.compress();
+      let comm_poly = compressed_poly.commit(&gens.gens_round(j, 3), blind_poly);
       <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_poly", &comm_poly);
       comm_polys.push(comm_poly);
+      polys.push(compressed_poly);
 
       //derive the verifier's challenge for the next round
       let r_j =
@@ -696,107 +1161,323 @@ impl<G: CurveGroup> ZKSumcheckInstanceProof<G> {
       poly_C.bound_poly_var_top(&r_j);
       poly_D.bound_poly_var_top(&r_j);
 
-      // produce a proof of sum-check and of evaluation
-      let (proof, claim_next_round, comm_claim_next_round) = {
-        let eval = poly.evaluate(&r_j);
-        let comm_eval = eval.commit(&blinds_evals[j], gens_1);
-
-        // we need to prove the following under homomorphic commitments:
-        // (1) poly(0) + poly(1) = claim_per_round
-        // (2) poly(r_j) = eval
-
-        // Our technique is to leverage dot product proofs:
-        // (1) we can prove: <poly_in_coeffs_form, (2, 1, 1, 1)> = claim_per_round
-        // (2) we can prove: <poly_in_coeffs_form, (1, r_j, r^2_j, ..) = eval
-        // for efficiency we batch them using random weights
-
-        // add two claims to transcript
-        <Transcript as ProofTranscript<G>>::append_point(
-          transcript,
-          b"comm_claim_per_round",
-          &comm_claim_per_round,
-        );
-        <Transcript as ProofTranscript<G>>::append_point(transcript, b"comm_eval", &comm_eval);
+      claim_per_round = poly.evaluate(&r_j);
+      r.push(r_j);
+    }
 
-        // produce two weights
-        let w = <Transcript as ProofTranscript<G>>::challenge_vector(
-          transcript,
-          b"combine_two_claims_to_one",
-          2,
-        );
+    let (proof, blind_eval) = ZKSumcheckInstanceProof::prove_rounds(
+      claim,
+      blind_claim,
+      &claim_per_round,
+      comm_polys,
+      &polys,
+      &blinds_poly,
+      &r,
+      3,
+      gens,
+      transcript,
+      random_tape,
+    )?;
+
+    Ok((
+      proof,
+      r,
+      vec![poly_A[0], poly_B[0], poly_C[0], poly_D[0]],
+      blind_eval,
+    ))
+  }
+}
 
-        // compute a weighted sum of the RHS
-        let target = w[0] * claim_per_round + w[1] * eval;
-
-        let bases = vec![comm_claim_per_round.into_affine(), comm_eval.into_affine()];
-
-        let comm_target = VariableBaseMSM::msm(bases.as_ref(), w.as_ref()).unwrap();
-
-        let blind = {
-          let blind_sc = if j == 0 {
-            blind_claim
-          } else {
-            &blinds_evals[j - 1]
-          };
-
-          let blind_eval = &blinds_evals[j];
-
-          w[0] * blind_sc + w[1] * blind_eval
-        };
-
-        assert_eq!(target.commit(&blind, gens_1), comm_target);
-
-        let a = {
-          // the vector to use to decommit for sum-check test
-          let a_sc = {
-            let mut a = vec![G::ScalarField::one(); poly.degree() + 1];
-            a[0] += G::ScalarField::one();
-            a
-          };
-
-          // the vector to use to decommit for evaluation
-          let a_eval = {
-            let mut a = vec![G::ScalarField::one(); poly.degree() + 1];
-            for j in 1..a.len() {
-              a[j] = a[j - 1] * r_j;
-            }
-            a
-          };
-
-          // take weighted sum of the two vectors using w
-          assert_eq!(a_sc.len(), a_eval.len());
-          (0..a_sc.len())
-            .map(|i| w[0] * a_sc[i] + w[1] * a_eval[i])
-            .collect::<Vec<G::ScalarField>>()
-        };
-
-        let (proof, _comm_poly, _comm_sc_eval) = DotProductProof::prove(
-          gens_1,
-          gens_n,
-          transcript,
-          random_tape,
-          &poly.as_vec(),
-          &blinds_poly[j],
-          &a,
-          &target,
-          &blind,
-        );
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ark_bls12_381::G1Projective;
+  use ark_std::rand::{rngs::StdRng, SeedableRng};
+  use ark_std::{test_rng, UniformRand};
+
+  fn random_polys<F: PrimeField>(
+    num_polys: usize,
+    num_vars: usize,
+  ) -> Vec<MultilinearPolynomial<F>> {
+    let mut prng = test_rng();
+    (0..num_polys)
+      .map(|_| MultilinearPolynomial::new((0..1 << num_vars).map(|_| F::rand(&mut prng)).collect()))
+      .collect()
+  }
 
-        (proof, eval, comm_eval)
-      };
+  // sum_x A(x) * B(x) * C(x) * D(x) + A(x), of degree 4
+  fn comb_func<F: PrimeField>(evals: &[F]) -> F {
+    evals[0] * evals[1] * evals[2] * evals[3] + evals[0]
+  }
 
-      proofs.push(proof);
-      claim_per_round = claim_next_round;
-      comm_claim_per_round = comm_claim_next_round;
-      r.push(r_j);
-      comm_evals.push(comm_claim_per_round);
+  fn sum<F: PrimeField>(polys: &[MultilinearPolynomial<F>]) -> F {
+    (0..polys[0].poly.len())
+      .map(|i| comb_func(&polys.iter().map(|poly| poly.poly[i]).collect::<Vec<F>>()))
+      .sum()
+  }
+
+  #[test]
+  fn check_sumcheck() {
+    check_sumcheck_helper::<G1Projective>()
+  }
+
+  fn check_sumcheck_helper<G: CurveGroup>() {
+    let num_vars = 5;
+    let polys = random_polys::<G::ScalarField>(4, num_vars);
+    let claim = sum(&polys);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, r, evals) = SumcheckInstanceProof::prove::<_, G>(
+      &claim,
+      polys.clone(),
+      comb_func,
+      4,
+      &mut prover_transcript,
+    )
+    .unwrap();
+    for (poly, eval) in polys.iter().zip(evals.iter()) {
+      assert_eq!(poly.evaluate(&r), *eval);
     }
 
-    (
-      ZKSumcheckInstanceProof::new(comm_polys, comm_evals, proofs),
-      r,
-      vec![poly_A[0], poly_B[0], poly_C[0], poly_D[0]],
-      blinds_evals[num_rounds - 1],
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (claim_final, r_verifier) = proof
+      .verify::<G>(claim, num_vars, 4, &mut verifier_transcript)
+      .unwrap();
+    assert_eq!(r, r_verifier);
+    assert_eq!(claim_final, comb_func(&evals));
+
+    // a wrong claim leads to a wrong final claim
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (claim_final, _r) = proof
+      .verify::<G>(
+        claim + G::ScalarField::one(),
+        num_vars,
+        4,
+        &mut verifier_transcript,
+      )
+      .unwrap();
+    assert_ne!(claim_final, comb_func(&evals));
+
+    // a wrong number of rounds or degree is rejected
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify::<G>(claim, num_vars + 1, 4, &mut verifier_transcript)
+      .is_err());
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify::<G>(claim, num_vars, 3, &mut verifier_transcript)
+      .is_err());
+
+    // a round polynomial without coefficients is rejected
+    let mut bytes = Vec::new();
+    1u64.serialize_compressed(&mut bytes).unwrap();
+    0u64.serialize_compressed(&mut bytes).unwrap();
+    let empty =
+      SumcheckInstanceProof::<G::ScalarField>::deserialize_compressed(&bytes[..]).unwrap();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(empty
+      .verify::<G>(claim, 1, 3, &mut verifier_transcript)
+      .is_err());
+
+    // no polynomials, or polynomials in different numbers of variables, are rejected
+    let mut prover_transcript = Transcript::new(b"example");
+    assert!(matches!(
+      SumcheckInstanceProof::prove::<_, G>(&claim, vec![], comb_func, 4, &mut prover_transcript),
+      Err(SumcheckError::NoPolynomials)
+    ));
+    let mut mismatched = random_polys::<G::ScalarField>(3, num_vars);
+    mismatched.extend(random_polys::<G::ScalarField>(1, num_vars - 1));
+    let mut prover_transcript = Transcript::new(b"example");
+    assert!(matches!(
+      SumcheckInstanceProof::prove::<_, G>(
+        &claim,
+        mismatched,
+        comb_func,
+        4,
+        &mut prover_transcript
+      ),
+      Err(SumcheckError::MismatchedPolynomials)
+    ));
+  }
+
+  #[test]
+  fn check_zk_sumcheck() {
+    check_zk_sumcheck_helper::<G1Projective>()
+  }
+
+  fn check_zk_sumcheck_helper<G: CurveGroup>() {
+    let num_vars = 5;
+    let polys = random_polys::<G::ScalarField>(4, num_vars);
+    let claim = sum(&polys);
+
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-zk-sumcheck-gens-1");
+    let gens = ZKSumcheckGens::new(num_vars * 4, b"test-zk-sumcheck", &gens_1);
+    let blind_claim = G::ScalarField::rand(&mut test_rng());
+    let comm_claim = claim.commit(&blind_claim, &gens_1);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let (proof, r, evals, blind_eval) = ZKSumcheckInstanceProof::prove(
+      &claim,
+      &blind_claim,
+      polys.clone(),
+      comb_func,
+      4,
+      &gens,
+      &mut prover_transcript,
+      &mut StdRng::seed_from_u64(0),
+    )
+    .unwrap();
+    for (poly, eval) in polys.iter().zip(evals.iter()) {
+      assert_eq!(poly.evaluate(&r), *eval);
+    }
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    let (comm_eval, r_verifier) = proof
+      .verify(&comm_claim, num_vars, 4, &gens, &mut verifier_transcript)
+      .unwrap();
+    assert_eq!(r, r_verifier);
+    assert_eq!(comm_eval, comb_func(&evals).commit(&blind_eval, &gens_1));
+
+    // a commitment to a wrong claim is rejected
+    let comm_wrong = (claim + G::ScalarField::one()).commit(&blind_claim, &gens_1);
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm_wrong, num_vars, 4, &gens, &mut verifier_transcript)
+      .is_err());
+
+    // the blinds come from the rng, so proofs of the same claim differ
+    let mut prover_transcript = Transcript::new(b"example");
+    let (other, _, _, _) = ZKSumcheckInstanceProof::prove(
+      &claim,
+      &blind_claim,
+      polys.clone(),
+      comb_func,
+      4,
+      &gens,
+      &mut prover_transcript,
+      &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    assert_ne!(proof.comm_polys, other.comm_polys);
+
+    // a sum-check without rounds, or with more coefficients than generators, is rejected
+    let constants = random_polys::<G::ScalarField>(4, 0);
+    let mut prover_transcript = Transcript::new(b"example");
+    assert!(matches!(
+      ZKSumcheckInstanceProof::prove(
+        &claim,
+        &blind_claim,
+        constants,
+        comb_func,
+        4,
+        &gens,
+        &mut prover_transcript,
+        &mut StdRng::seed_from_u64(0),
+      ),
+      Err(SumcheckError::NoRounds)
+    ));
+    let mut prover_transcript = Transcript::new(b"example");
+    assert!(matches!(
+      ZKSumcheckInstanceProof::prove(
+        &claim,
+        &blind_claim,
+        polys,
+        comb_func,
+        7,
+        &gens,
+        &mut prover_transcript,
+        &mut StdRng::seed_from_u64(0),
+      ),
+      Err(SumcheckError::InsufficientGenerators)
+    ));
+  }
+
+  #[test]
+  fn check_zk_sumcheck_binds_rounds() {
+    check_zk_sumcheck_binds_rounds_helper::<G1Projective>()
+  }
+
+  // a prover that moves part of the coefficients of round 1 into the commitment of round 2, after
+  // learning the challenge of round 1, to prove a wrong claim; the commitments of the rounds still
+  // add up to a commitment to coefficients that satisfy the claim, so the proof is only rejected
+  // because the rounds are folded with challenges
+  fn check_zk_sumcheck_binds_rounds_helper<G: CurveGroup>() {
+    let num_vars = 2;
+    let comb_func = |evals: &[G::ScalarField]| evals[0] * evals[1];
+    let mut polys = random_polys::<G::ScalarField>(2, num_vars)
+      .into_iter()
+      .map(|poly| poly.poly)
+      .collect::<Vec<DensePolynomial<G::ScalarField>>>();
+    let claim = (0..polys[0].len())
+      .map(|i| polys[0][i] * polys[1][i])
+      .sum::<G::ScalarField>();
+    let wrong_claim = claim + G::ScalarField::one();
+
+    let gens_1 = MultiCommitGens::<G>::new(1, b"test-zk-sumcheck-gens-1");
+    let gens = ZKSumcheckGens::new(num_vars * 2, b"test-zk-sumcheck", &gens_1);
+    let blind_claim = G::ScalarField::rand(&mut test_rng());
+    let comm_wrong = wrong_claim.commit(&blind_claim, &gens_1);
+    let mut random_tape = RandomTape::<G>::new(b"test-zk-sumcheck");
+    let blinds_poly = random_tape.random_vector(b"blinds_poly", num_vars);
+
+    let mut transcript = Transcript::new(b"example");
+    let mut r = Vec::new();
+    let mut comm_polys = Vec::new();
+
+    // round 1: commit to the polynomial of the right claim
+    let poly_1 = SumcheckInstanceProof::compute_round_poly(&claim, &polys, &comb_func, 2);
+    let comm_poly = poly_1
+      .compress()
+      .commit(&gens.gens_round(0, 2), &blinds_poly[0]);
+    <Transcript as ProofTranscript<G>>::append_point(&mut transcript, b"comm_poly", &comm_poly);
+    comm_polys.push(comm_poly);
+    let r_1 =
+      <Transcript as ProofTranscript<G>>::challenge_scalar(&mut transcript, b"challenge_nextround");
+    for poly in polys.iter_mut() {
+      poly.bound_poly_var_top(&r_1);
+    }
+    r.push(r_1);
+
+    // p_1 + delta * (1 - 2X) sums to the wrong claim over {0, 1} and agrees with p_1 at r_1
+    let one = G::ScalarField::one();
+    let delta = -r_1 * (one - r_1 - r_1).inverse().unwrap();
+    let shifted_poly_1 = UniPoly::from_evals(&[
+      poly_1.evaluate(&G::ScalarField::zero()) + delta,
+      poly_1.evaluate(&one) - delta,
+      poly_1.evaluate(&(one + one)) - delta - delta - delta,
+    ]);
+
+    // round 2: the shift of the constant term of round 1 goes into the commitment of round 2
+    let poly_2 =
+      SumcheckInstanceProof::compute_round_poly(&poly_1.evaluate(&r_1), &polys, &comb_func, 2);
+    let comm_poly = poly_2
+      .compress()
+      .commit(&gens.gens_round(1, 2), &blinds_poly[1])
+      + gens.gens_round(0, 2).G()[0] * delta;
+    <Transcript as ProofTranscript<G>>::append_point(&mut transcript, b"comm_poly", &comm_poly);
+    comm_polys.push(comm_poly);
+    let r_2 =
+      <Transcript as ProofTranscript<G>>::challenge_scalar(&mut transcript, b"challenge_nextround");
+    r.push(r_2);
+
+    let (proof, _blind_eval) = ZKSumcheckInstanceProof::prove_rounds(
+      &wrong_claim,
+      &blind_claim,
+      &poly_2.evaluate(&r_2),
+      comm_polys,
+      &[shifted_poly_1.compress(), poly_2.compress()],
+      &blinds_poly,
+      &r,
+      2,
+      &gens,
+      &mut transcript,
+      &mut random_tape,
     )
+    .unwrap();
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm_wrong, num_vars, 2, &gens, &mut verifier_transcript)
+      .is_err());
   }
 }
@@ -20,10 +20,13 @@ pub struct CompressedUniPoly<F: PrimeField> {
 }
 
 impl<F: PrimeField> UniPoly<F> {
+  // interpolates the polynomial of degree evals.len() - 1 given its evaluations at 0, 1, 2, ...
   pub fn from_evals(evals: &[F]) -> Self {
-    // we only support degree-2 or degree-3 univariate polynomials
-    assert!(evals.len() == 3 || evals.len() == 4);
-    let coeffs = if evals.len() == 3 {
+    // degree-2 and degree-3 polynomials use closed-form expressions
+    assert!(evals.len() >= 3);
+    let coeffs = if evals.len() > 4 {
+      UniPoly::interpolate(evals)
+    } else if evals.len() == 3 {
       // ax^2 + bx + c
       let two_inv = F::from(2u64).inverse().unwrap();
 
@@ -53,12 +56,35 @@ impl<F: PrimeField> UniPoly<F> {
     UniPoly { coeffs }
   }
 
-  pub fn degree(&self) -> usize {
-    self.coeffs.len() - 1
+  // Lagrange interpolation over the points 0, 1, ..., evals.len() - 1
+  fn interpolate(evals: &[F]) -> Vec<F> {
+    let n = evals.len();
+    let mut coeffs = vec![F::zero(); n];
+    for (i, eval) in evals.iter().enumerate() {
+      // the numerator prod_{j != i} (x - j) as coefficients, and the denominator prod_{j != i} (i - j)
+      let mut basis = vec![F::one()];
+      let mut denom = F::one();
+      for j in (0..n).filter(|j| *j != i) {
+        let x_j = F::from(j as u64);
+        let mut next = vec![F::zero(); basis.len() + 1];
+        for (k, b) in basis.iter().enumerate() {
+          next[k + 1] += b;
+          next[k] -= *b * x_j;
+        }
+        basis = next;
+        denom *= F::from(i as u64) - x_j;
+      }
+
+      let scale = *eval * denom.inverse().unwrap();
+      for (c, b) in coeffs.iter_mut().zip(basis.iter()) {
+        *c += *b * scale;
+      }
+    }
+    coeffs
   }
 
-  pub fn as_vec(&self) -> Vec<F> {
-    self.coeffs.clone()
+  pub fn degree(&self) -> usize {
+    self.coeffs.len() - 1
   }
 
   pub fn eval_at_zero(&self) -> F {
@@ -86,13 +112,22 @@ impl<F: PrimeField> UniPoly<F> {
       coeffs_except_linear_term,
     }
   }
+}
+
+impl<F: PrimeField> CompressedUniPoly<F> {
+  // the degree of the polynomial, whose coefficients except the linear term are stored
+  pub fn degree(&self) -> usize {
+    self.coeffs_except_linear_term.len()
+  }
+
+  pub fn as_vec(&self) -> Vec<F> {
+    self.coeffs_except_linear_term.clone()
+  }
 
   pub fn commit<G: CurveGroup<ScalarField = F>>(&self, gens: &MultiCommitGens<G>, blind: &F) -> G {
-    Commitments::batch_commit(&self.coeffs, blind, gens)
+    Commitments::batch_commit(&self.coeffs_except_linear_term, blind, gens)
   }
-}
 
-impl<F: PrimeField> CompressedUniPoly<F> {
   // we require eval(0) + eval(1) = hint, so we can solve for the linear term as:
   // linear_term = hint - 2 * constant_term - deg2 term - deg3 term
   pub fn decompress(&self, hint: &F) -> UniPoly<F> {
@@ -187,4 +222,26 @@ mod tests {
     let e4 = F::from(109u64);
     assert_eq!(poly.evaluate(&F::from(4u64)), e4);
   }
+
+  #[test]
+  fn test_from_evals_high_degree() {
+    test_from_evals_high_degree_helper::<Fr>()
+  }
+  fn test_from_evals_high_degree_helper<F: PrimeField>() {
+    // polynomial is 4x^5 + x^3 + 2x + 7
+    let coeffs = [7u64, 2, 0, 1, 0, 4].map(F::from);
+    let expected = UniPoly {
+      coeffs: coeffs.to_vec(),
+    };
+    let evals = (0..6u64)
+      .map(|i| expected.evaluate(&F::from(i)))
+      .collect::<Vec<F>>();
+    let poly = UniPoly::from_evals(&evals);
+    assert_eq!(poly.coeffs, coeffs.to_vec());
+    assert_eq!(poly.degree(), 5);
+
+    let hint = poly.eval_at_zero() + poly.eval_at_one();
+    let decompressed_poly = poly.compress().decompress(&hint);
+    assert_eq!(decompressed_poly.coeffs, poly.coeffs);
+  }
 }